@@ -0,0 +1,17 @@
+// Writes the `root.toJSON()` of postcss next to every case in
+// crates/recursive-parser/tests/official-cases, which tests/postcss.rs compares with the
+// JSON of the recursive parser.
+const fs = require("fs");
+const path = require("path");
+const parse = require("postcss/lib/parse");
+
+const dir = path.join(__dirname, "../crates/recursive-parser/tests/official-cases");
+
+for (const file of fs.readdirSync(dir).filter((file) => file.endsWith(".css"))) {
+  const css = fs.readFileSync(path.join(dir, file)).toString();
+  const json = parse(css).toJSON();
+  fs.writeFileSync(
+    path.join(dir, file.replace(/\.css$/, ".json")),
+    JSON.stringify(json, null, 2) + "\n",
+  );
+}
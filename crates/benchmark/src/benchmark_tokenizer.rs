@@ -12,9 +12,7 @@ fn main() {
   let css: &str = &read_to_string(format!("assets/{}", file)).unwrap();
   let start = Instant::now();
   let processor = Tokenizer::new(css, false);
-  while !processor.end_of_file() {
-    processor.next_token(false);
-  }
+  for _ in processor {}
   let end = start.elapsed();
   print!("{}", end.as_nanos());
 }
@@ -120,6 +120,7 @@ impl<'a, W: Write> VisitMut<'a, std::fmt::Result> for SimplePrettier<W> {
 }
 
 #[derive(Default)]
+#[allow(dead_code)]
 struct ReverseProp {}
 
 impl<'a> VisitMut<'a> for ReverseProp {
@@ -137,6 +137,19 @@ impl<'a, W: Write> Visit<'a, Result> for AstPrinter<W> {
       " ".repeat(self.level * 2),
       decl.value,
     )?;
+    for child in &decl.children {
+      match child {
+        parser::RuleOrAtRuleOrDecl::Rule(rule) => {
+          self.visit_rule(rule)?;
+        }
+        parser::RuleOrAtRuleOrDecl::AtRule(at_rule) => {
+          self.visit_at_rule(at_rule)?;
+        }
+        parser::RuleOrAtRuleOrDecl::Declaration(decl) => {
+          self.visit_declaration(decl)?;
+        }
+      }
+    }
     self.level -= 1;
     Ok(())
   }
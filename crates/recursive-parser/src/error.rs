@@ -1,4 +1,5 @@
 use thiserror::Error;
+use tokenizer::line_index::{Encoding, LineCol, LineIndex};
 
 #[derive(Error, Debug)]
 pub enum PostcssError {
@@ -9,4 +10,17 @@ pub enum PostcssError {
   Unknown,
 }
 
+impl PostcssError {
+  /// The zero-based start and end of a parse error, columns counted in `encoding`.
+  pub fn line_cols(&self, index: &LineIndex, encoding: Encoding) -> Option<(LineCol, LineCol)> {
+    match self {
+      PostcssError::ParseError(_, start, end) => Some((
+        index.line_col(*start, encoding)?,
+        index.line_col(*end, encoding)?,
+      )),
+      PostcssError::Unknown => None,
+    }
+  }
+}
+
 pub type Result<T> = std::result::Result<T, PostcssError>;
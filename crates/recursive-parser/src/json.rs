@@ -0,0 +1,812 @@
+//! The tree as JSON in the shape of postcss's `root.toJSON()`, to diff against postcss.js, cache
+//! trees on disk and exchange them with JavaScript tools.
+//!
+//! The tree keeps comments in the raws around its nodes, the JSON has `comment` nodes like
+//! postcss, and a `!important` at the end of a value becomes `important`. Positions count
+//! UTF-16 code units like JavaScript: `line` and `column` start at 1, `end` is the last char
+//! of the node and its `offset` the one after it.
+//!
+//! An SCSS `//` comment between nodes is a `comment` node with `raws.inline`, like in
+//! postcss-scss. One inside a selector, params or value stays in their text, where postcss-scss
+//! cleans it, so only the JSON of plain CSS matches postcss there.
+//!
+//! ```
+//! use recursive_parser::parser::{Parser, Root};
+//!
+//! let root = Parser::new("a { color: red !important; /* x */ }").parse().unwrap();
+//! let json = root.to_json();
+//! assert_eq!(json["nodes"][0]["nodes"][0]["value"], "red");
+//! assert_eq!(json["nodes"][0]["nodes"][1]["text"], "x");
+//!
+//! let root = Root::from_json(&json.to_string()).unwrap();
+//! assert_eq!(
+//!   recursive_parser::stringifier::stringify(&root),
+//!   "a { color: red !important; /* x */ }"
+//! );
+//! ```
+use crate::line_index::{Encoding, LineCol, LineIndex};
+use crate::parser::{AtRule, Declaration, Raws, Root, Rule, RuleOrAtRuleOrDecl};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+use tokenizer::{Token, TokenType, Tokenizer};
+
+impl<'a> Root<'a> {
+  /// The tree like postcss's `root.toJSON()`.
+  pub fn to_json(&self) -> serde_json::Value {
+    serde_json::to_value(self).unwrap()
+  }
+
+  /// Rebuild a tree from the JSON of [`Root::to_json`] or postcss's `root.toJSON()`. Missing
+  /// raws take the defaults of the postcss stringifier, so a tree built by hand prints as
+  /// compact CSS.
+  pub fn from_json(json: &str) -> serde_json::Result<Root<'static>> {
+    serde_json::from_str(json)
+  }
+}
+
+impl<'a> Serialize for Root<'a> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Writer::new(&self.css).root(self).serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for Root<'static> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    Reader::root(Node::deserialize(deserializer)?).map_err(D::Error::custom)
+  }
+}
+
+/// A place where two JSON trees differ, see [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+  /// like `nodes[0].nodes[2].raws.between`
+  pub path: String,
+  /// `None` when the key is missing
+  pub expected: Option<Value>,
+  pub actual: Option<Value>,
+}
+
+impl fmt::Display for Difference {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let show = |value: &Option<Value>| {
+      value
+        .as_ref()
+        .map_or("nothing".to_string(), Value::to_string)
+    };
+    write!(
+      f,
+      "{}: expected {}, found {}",
+      self.path,
+      show(&self.expected),
+      show(&self.actual)
+    )
+  }
+}
+
+/// Compare `actual` with `expected` key by key and item by item, e.g. the
+/// [`to_json`](Root::to_json) of a tree with the `toJSON()` of postcss. Every difference is
+/// reported once at the deepest path where it shows. They come in the order of the keys of
+/// `expected`, which `serde_json` sorts, followed by the keys only `actual` has.
+pub fn diff(expected: &Value, actual: &Value) -> Vec<Difference> {
+  let mut differences = vec![];
+  diff_at(
+    String::new(),
+    Some(expected),
+    Some(actual),
+    &mut differences,
+  );
+  differences
+}
+
+fn diff_at(
+  path: String,
+  expected: Option<&Value>,
+  actual: Option<&Value>,
+  differences: &mut Vec<Difference>,
+) {
+  let join = |key: &str| {
+    if path.is_empty() {
+      key.to_string()
+    } else {
+      format!("{}.{}", path, key)
+    }
+  };
+  match (expected, actual) {
+    (Some(Value::Object(expected)), Some(Value::Object(actual))) => {
+      for (key, value) in expected {
+        diff_at(join(key), Some(value), actual.get(key), differences);
+      }
+      for (key, value) in actual {
+        if !expected.contains_key(key) {
+          diff_at(join(key), None, Some(value), differences);
+        }
+      }
+    }
+    (Some(Value::Array(expected)), Some(Value::Array(actual))) => {
+      for i in 0..expected.len().max(actual.len()) {
+        diff_at(
+          format!("{}[{}]", path, i),
+          expected.get(i),
+          actual.get(i),
+          differences,
+        );
+      }
+    }
+    (expected, actual) if expected != actual => differences.push(Difference {
+      path,
+      expected: expected.cloned(),
+      actual: actual.cloned(),
+    }),
+    _ => {}
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Node<'a> {
+  Root {
+    #[serde(default)]
+    raws: NodeRaws<'a>,
+    #[serde(default)]
+    nodes: Vec<Node<'a>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Source>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inputs: Vec<Input<'a>>,
+  },
+  Rule {
+    selector: Cow<'a, str>,
+    #[serde(default)]
+    nodes: Vec<Node<'a>>,
+    #[serde(default)]
+    raws: NodeRaws<'a>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Source>,
+  },
+  #[serde(rename = "atrule")]
+  AtRule {
+    name: Cow<'a, str>,
+    #[serde(default)]
+    params: Cow<'a, str>,
+    /// absent for an at-rule without a block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nodes: Option<Vec<Node<'a>>>,
+    #[serde(default)]
+    raws: NodeRaws<'a>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Source>,
+    /// a Less variable, like postcss-less
+    #[serde(default, skip_serializing_if = "is_false")]
+    variable: bool,
+    /// a Less mixin call, like postcss-less
+    #[serde(default, skip_serializing_if = "is_false")]
+    mixin: bool,
+  },
+  Decl {
+    prop: Cow<'a, str>,
+    value: Cow<'a, str>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    important: bool,
+    /// an SCSS nested property, like postcss-scss
+    #[serde(default, rename = "isNested", skip_serializing_if = "is_false")]
+    is_nested: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nodes: Option<Vec<Node<'a>>>,
+    #[serde(default)]
+    raws: NodeRaws<'a>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Source>,
+  },
+  Comment {
+    text: Cow<'a, str>,
+    #[serde(default)]
+    raws: NodeRaws<'a>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<Source>,
+  },
+}
+
+fn is_false(value: &bool) -> bool {
+  !value
+}
+
+/// The raws of every node type, only those of its type are set.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeRaws<'a> {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  before: Option<Cow<'a, str>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  between: Option<Cow<'a, str>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  after_name: Option<Cow<'a, str>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  semicolon: Option<bool>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  after: Option<Cow<'a, str>>,
+  /// whitespace between `/*` and the text of a comment
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  left: Option<Cow<'a, str>>,
+  /// whitespace between the text of a comment and `*/`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  right: Option<Cow<'a, str>>,
+  /// an SCSS `//` comment, like postcss-scss
+  #[serde(default, skip_serializing_if = "is_false")]
+  inline: bool,
+  /// the `!important` as written, when it isn't ` !important`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  important: Option<Cow<'a, str>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  identifier: Option<Cow<'a, str>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  selector: Option<RawValue<'a>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  params: Option<RawValue<'a>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  value: Option<RawValue<'a>>,
+}
+
+/// A selector, params or value with comments, `value` is the field without them.
+#[derive(Debug, Serialize, Deserialize)]
+struct RawValue<'a> {
+  value: Cow<'a, str>,
+  raw: Cow<'a, str>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Source {
+  #[serde(default)]
+  input_id: usize,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  start: Option<Position>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  end: Option<Position>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Position {
+  offset: usize,
+  line: usize,
+  column: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Input<'a> {
+  #[serde(default, rename = "hasBOM")]
+  has_bom: bool,
+  css: Cow<'a, str>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  file: Option<Cow<'a, str>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  id: Option<Cow<'a, str>>,
+}
+
+/// The value of a selector, params or declaration without its comments, the way postcss
+/// cleans them: a comment between two tokens other than whitespace stays, unless it follows
+/// a `,`, and a trailing space is dropped. `None` when there is nothing to clean.
+fn clean(raw: &str) -> Option<String> {
+  if !raw.contains("/*") && !raw.ends_with(|ch: char| ch.is_ascii_whitespace()) {
+    return None;
+  }
+  let tokens = Tokenizer::new(raw, true).collect::<Vec<_>>();
+  let mut value = String::with_capacity(raw.len());
+  let mut clean = true;
+  for (i, &Token(kind, start, end)) in tokens.iter().enumerate() {
+    let text = &raw[start..end];
+    let safe = |token: Option<&Token>| token.is_none_or(|token| token.0 == TokenType::Space);
+    if kind == TokenType::Space && i + 1 == tokens.len() {
+      clean = false;
+    } else if kind == TokenType::Comment {
+      let prev = i.checked_sub(1).and_then(|i| tokens.get(i));
+      if !safe(prev) && !safe(tokens.get(i + 1)) && !value.ends_with(',') {
+        value.push_str(text);
+      } else {
+        clean = false;
+      }
+    } else {
+      value.push_str(text);
+    }
+  }
+  (!clean).then_some(value)
+}
+
+/// `value` split in front of a trailing `!important`, with the whitespace before it
+fn split_important(value: &str) -> Option<(&str, &str)> {
+  let trimmed = value.trim_end();
+  let word = trimmed.len().checked_sub("important".len())?;
+  if !trimmed.is_char_boundary(word) || !trimmed[word..].eq_ignore_ascii_case("important") {
+    return None;
+  }
+  let bang = trimmed[..word].trim_end().strip_suffix('!')?;
+  let rest = bang.trim_end();
+  Some((rest, &value[rest.len()..]))
+}
+
+/// Converts a tree to [`Node`]s, the offsets of the tree are turned into [`Position`]s of
+/// `css`.
+struct Writer<'a> {
+  css: &'a str,
+  index: LineIndex,
+  /// the UTF-16 offset of every line start, empty for an ASCII source
+  line_starts: Vec<usize>,
+}
+
+impl<'a> Writer<'a> {
+  fn new(css: &'a str) -> Self {
+    let index = LineIndex::new(css);
+    let mut line_starts = vec![];
+    if !css.is_ascii() {
+      let mut offset = 0;
+      for line in 0..index.len_lines() {
+        line_starts.push(offset);
+        offset += css[index.line_range(line).unwrap()].encode_utf16().count();
+      }
+    }
+    Self {
+      css,
+      index,
+      line_starts,
+    }
+  }
+
+  fn position(&self, offset: usize) -> Position {
+    let offset = offset.min(self.css.len());
+    let LineCol { line, col } = self.index.line_col(offset, Encoding::Utf16).unwrap();
+    Position {
+      offset: self
+        .line_starts
+        .get(line)
+        .map_or(offset, |start| start + col),
+      line: line + 1,
+      column: col + 1,
+    }
+  }
+
+  fn source(&self, start: usize, end: usize) -> Option<Source> {
+    let last = self
+      .css
+      .get(..end)
+      .and_then(|css| css.chars().next_back())
+      .map_or(end, |ch| end - ch.len_utf8());
+    let mut end = self.position(last.max(start));
+    end.offset += 1;
+    Some(Source {
+      input_id: 0,
+      start: Some(self.position(start)),
+      end: Some(end),
+    })
+  }
+
+  fn root(&self, root: &'a Root<'a>) -> Node<'a> {
+    let (nodes, after) = self.nodes(&root.children, &root.raws.after, root.end);
+    Node::Root {
+      raws: NodeRaws {
+        semicolon: Some(semicolon(&root.children)),
+        after: Some(after),
+        ..NodeRaws::default()
+      },
+      nodes,
+      source: Some(Source {
+        input_id: 0,
+        start: Some(self.position(0)),
+        end: Some(self.position(root.end)),
+      }),
+      inputs: vec![Input {
+        has_bom: root.has_bom,
+        css: Cow::Borrowed(self.css),
+        file: None,
+        id: None,
+      }],
+    }
+  }
+
+  /// The nodes of `children` with the comments around them, and what follows the last
+  /// comment of `after`, which ends at `end`.
+  fn nodes(
+    &self,
+    children: &'a [RuleOrAtRuleOrDecl<'a>],
+    after: &'a str,
+    end: usize,
+  ) -> (Vec<Node<'a>>, Cow<'a, str>) {
+    let mut nodes = vec![];
+    for child in children {
+      let before = &child.raws().before;
+      let start = child.range().0.saturating_sub(before.len());
+      let before = self.comments(before, start, &mut nodes);
+      nodes.push(match child {
+        RuleOrAtRuleOrDecl::Rule(rule) => self.rule(rule, before),
+        RuleOrAtRuleOrDecl::AtRule(at_rule) => self.at_rule(at_rule, before),
+        RuleOrAtRuleOrDecl::Declaration(decl) => self.declaration(decl, before),
+      });
+    }
+    let after = self.comments(after, end.saturating_sub(after.len()), &mut nodes);
+    (nodes, after)
+  }
+
+  /// Push the comments in `text`, which starts at `start`, and return the text after them.
+  /// An SCSS `//` comment runs up to the end of its line, like in postcss-scss.
+  fn comments(&self, text: &'a str, start: usize, nodes: &mut Vec<Node<'a>>) -> Cow<'a, str> {
+    let mut rest = 0;
+    while let Some(open) = text[rest..]
+      .match_indices('/')
+      .map(|(i, _)| rest + i)
+      .find(|&i| matches!(text.as_bytes().get(i + 1), Some(b'*' | b'/')))
+    {
+      let inline = text.as_bytes()[open + 1] == b'/';
+      let (inner, end) = if inline {
+        let end = text[open..]
+          .find(['\n', '\r', '\x0c'])
+          .map_or(text.len(), |i| open + i);
+        (&text[open + 2..end], end)
+      } else {
+        let close = text[open + 2..].find("*/").map(|i| open + 2 + i);
+        let end = close.map_or(text.len(), |close| close + 2);
+        (&text[open + 2..close.unwrap_or(text.len())], end)
+      };
+      let trimmed = inner.trim_start();
+      let left = &inner[..inner.len() - trimmed.len()];
+      let text_part = trimmed.trim_end();
+      nodes.push(Node::Comment {
+        text: Cow::Borrowed(text_part),
+        raws: NodeRaws {
+          before: Some(Cow::Borrowed(&text[rest..open])),
+          left: Some(Cow::Borrowed(left)),
+          right: Some(Cow::Borrowed(&trimmed[text_part.len()..])),
+          inline,
+          ..NodeRaws::default()
+        },
+        source: self.source(start + open, start + end),
+      });
+      rest = end;
+    }
+    Cow::Borrowed(&text[rest..])
+  }
+
+  fn rule(&self, rule: &'a Rule<'a>, before: Cow<'a, str>) -> Node<'a> {
+    let (nodes, after) = self.nodes(&rule.children, &rule.raws.after, rule.end.saturating_sub(1));
+    let cleaned = clean(&rule.selector);
+    Node::Rule {
+      raws: NodeRaws {
+        before: Some(before),
+        between: Some(Cow::Borrowed(&rule.raws.between)),
+        semicolon: (!rule.children.is_empty()).then(|| semicolon(&rule.children)),
+        after: Some(after),
+        selector: cleaned.clone().map(|value| RawValue {
+          value: Cow::Owned(value),
+          raw: Cow::Borrowed(&rule.selector),
+        }),
+        ..NodeRaws::default()
+      },
+      selector: cleaned.map_or(Cow::Borrowed(&rule.selector), Cow::Owned),
+      nodes,
+      source: self.source(rule.start, rule.end),
+    }
+  }
+
+  fn at_rule(&self, at_rule: &'a AtRule<'a>, before: Cow<'a, str>) -> Node<'a> {
+    let mut raws = NodeRaws {
+      before: Some(before),
+      between: Some(Cow::Borrowed(&at_rule.raws.between)),
+      after_name: Some(Cow::Borrowed(&at_rule.raws.after_name)),
+      ..NodeRaws::default()
+    };
+    if at_rule.mixin {
+      raws.identifier = Some(Cow::Borrowed(&at_rule.raws.identifier));
+    }
+    let mut nodes = None;
+    if at_rule.has_block {
+      let (children, after) = self.nodes(
+        &at_rule.children,
+        &at_rule.raws.after,
+        at_rule.end.saturating_sub(1),
+      );
+      if !at_rule.children.is_empty() {
+        raws.semicolon = Some(semicolon(&at_rule.children));
+      }
+      raws.after = Some(after);
+      nodes = Some(children);
+    }
+    let cleaned = clean(&at_rule.params);
+    raws.params = cleaned.clone().map(|value| RawValue {
+      value: Cow::Owned(value),
+      raw: Cow::Borrowed(&at_rule.params),
+    });
+    Node::AtRule {
+      name: Cow::Borrowed(&at_rule.name),
+      params: cleaned.map_or(Cow::Borrowed(&at_rule.params), Cow::Owned),
+      nodes,
+      raws,
+      source: self.source(at_rule.start, at_rule.end),
+      variable: at_rule.variable,
+      mixin: at_rule.mixin,
+    }
+  }
+
+  fn declaration(&self, decl: &'a Declaration<'a>, before: Cow<'a, str>) -> Node<'a> {
+    let mut raws = NodeRaws {
+      before: Some(before),
+      between: Some(Cow::Borrowed(&decl.raws.between)),
+      ..NodeRaws::default()
+    };
+    let mut nodes = None;
+    // the whitespace in front of the `;` is part of the value in postcss
+    let value: Cow<'a, str> = if decl.is_nested {
+      let (children, after) =
+        self.nodes(&decl.children, &decl.raws.after, decl.end.saturating_sub(1));
+      if !decl.children.is_empty() {
+        raws.semicolon = Some(semicolon(&decl.children));
+      }
+      raws.after = Some(after);
+      nodes = Some(children);
+      Cow::Borrowed(&decl.value)
+    } else if decl.raws.after.is_empty() {
+      Cow::Borrowed(&decl.value)
+    } else {
+      Cow::Owned(format!("{}{}", decl.value, decl.raws.after))
+    };
+
+    let custom = decl.prop.starts_with("--");
+    let (raw, important) = match split_important(&value) {
+      Some((raw, important)) => (raw.to_string(), Some(important.to_string())),
+      None => (value.to_string(), None),
+    };
+    let cleaned = if custom { None } else { clean(&raw) };
+    raws.important = important
+      .clone()
+      .filter(|important| important != " !important")
+      .map(Cow::Owned);
+    raws.value = cleaned.clone().map(|value| RawValue {
+      value: Cow::Owned(value),
+      raw: Cow::Owned(raw.clone()),
+    });
+    Node::Decl {
+      prop: Cow::Borrowed(&decl.prop),
+      value: match (cleaned, important.is_some(), value) {
+        (Some(cleaned), _, _) => Cow::Owned(cleaned),
+        (None, false, value) => value,
+        (None, true, _) => Cow::Owned(raw),
+      },
+      important: important.is_some(),
+      is_nested: decl.is_nested,
+      nodes,
+      raws,
+      source: self.source(decl.start, decl.end),
+    }
+  }
+}
+
+/// whether the last of `children` ends with a `;`, which is the `raws.semicolon` of their
+/// parent in postcss
+fn semicolon(children: &[RuleOrAtRuleOrDecl<'_>]) -> bool {
+  children.last().is_some_and(|child| child.raws().semicolon)
+}
+
+/// Converts [`Node`]s back to a tree, [`Position`]s are turned into offsets of the css of the
+/// first input.
+struct Reader {
+  css: String,
+  index: Option<LineIndex>,
+}
+
+type Owned<T> = Result<T, String>;
+
+impl Reader {
+  fn root(node: Node<'static>) -> Owned<Root<'static>> {
+    let (raws, nodes, inputs) = match node {
+      Node::Root {
+        raws,
+        nodes,
+        inputs,
+        ..
+      } => (raws, nodes, inputs),
+      _ => return Err("expected a node of type `root`".to_string()),
+    };
+    let has_bom = inputs.first().is_some_and(|input| input.has_bom);
+    let reader = match inputs.into_iter().next() {
+      Some(input) => Reader {
+        index: Some(LineIndex::new(&input.css)),
+        css: input.css.into_owned(),
+      },
+      None => Reader {
+        css: String::new(),
+        index: None,
+      },
+    };
+    let (children, trailing) = reader.nodes(nodes, raws.semicolon.unwrap_or(false))?;
+    Ok(Root {
+      children,
+      start: 0,
+      end: reader.css.len(),
+      raws: Raws {
+        after: Cow::Owned(trailing + &text(raws.after)),
+        ..Raws::default()
+      },
+      css: Cow::Owned(reader.css),
+      has_bom,
+    })
+  }
+
+  fn offset(&self, position: Option<Position>) -> Option<usize> {
+    let position = position?;
+    match &self.index {
+      Some(index) => index.offset(
+        LineCol {
+          line: position.line.checked_sub(1)?,
+          col: position.column.checked_sub(1)?,
+        },
+        Encoding::Utf16,
+      ),
+      None => Some(position.offset),
+    }
+  }
+
+  /// the byte range of `source`, `0..0` when it is missing
+  fn range(&self, source: Option<Source>) -> (usize, usize) {
+    let source = match source {
+      Some(source) => source,
+      None => return (0, 0),
+    };
+    let start = self.offset(source.start).unwrap_or(0);
+    let end = match (&self.index, self.offset(source.end)) {
+      (Some(_), Some(end)) => end + self.css[end..].chars().next().map_or(0, char::len_utf8),
+      (None, Some(end)) => end,
+      (_, None) => start,
+    };
+    (start, end.max(start))
+  }
+
+  /// The children of `nodes`, and the comments after the last of them. A node that isn't the
+  /// last ends with a `;` if it can, the last one when `semicolon` is set.
+  fn nodes(
+    &self,
+    nodes: Vec<Node<'static>>,
+    semicolon: bool,
+  ) -> Owned<(Vec<RuleOrAtRuleOrDecl<'static>>, String)> {
+    let mut children = vec![];
+    let mut comments = String::new();
+    for node in nodes {
+      let mut child = match node {
+        Node::Comment {
+          text: body, raws, ..
+        } => {
+          let (open, close, right) = if raws.inline {
+            ("//", "", "")
+          } else {
+            ("/*", "*/", " ")
+          };
+          comments.push_str(&text(raws.before));
+          comments.push_str(open);
+          comments.push_str(raws.left.as_deref().unwrap_or(" "));
+          comments.push_str(&body);
+          comments.push_str(raws.right.as_deref().unwrap_or(right));
+          comments.push_str(close);
+          continue;
+        }
+        Node::Root { .. } => return Err("a `root` can't be nested".to_string()),
+        Node::Rule {
+          selector,
+          nodes,
+          raws,
+          source,
+        } => {
+          let (children, trailing) = self.nodes(nodes, raws.semicolon.unwrap_or(false))?;
+          let (start, end) = self.range(source);
+          RuleOrAtRuleOrDecl::Rule(Rule {
+            selector: raw_value(selector, raws.selector),
+            children,
+            start,
+            end,
+            raws: Raws {
+              before: Cow::Owned(text(raws.before)),
+              between: Cow::Owned(text(raws.between)),
+              after: Cow::Owned(trailing + &text(raws.after)),
+              ..Raws::default()
+            },
+          })
+        }
+        Node::AtRule {
+          name,
+          params,
+          nodes,
+          raws,
+          source,
+          variable,
+          mixin,
+        } => {
+          let has_block = nodes.is_some();
+          let (children, trailing) =
+            self.nodes(nodes.unwrap_or_default(), raws.semicolon.unwrap_or(false))?;
+          let (start, end) = self.range(source);
+          let params = raw_value(params, raws.params);
+          let after_name = raws
+            .after_name
+            .unwrap_or(Cow::Borrowed(if params.is_empty() { "" } else { " " }));
+          RuleOrAtRuleOrDecl::AtRule(AtRule {
+            params,
+            name,
+            start,
+            end,
+            children,
+            raws: Raws {
+              before: Cow::Owned(text(raws.before)),
+              between: Cow::Owned(text(raws.between)),
+              after: Cow::Owned(trailing + &text(raws.after)),
+              after_name,
+              identifier: raws.identifier.unwrap_or_default(),
+              ..Raws::default()
+            },
+            has_block,
+            variable,
+            mixin,
+          })
+        }
+        Node::Decl {
+          prop,
+          value,
+          important,
+          is_nested,
+          nodes,
+          raws,
+          source,
+        } => {
+          let (children, trailing) =
+            self.nodes(nodes.unwrap_or_default(), raws.semicolon.unwrap_or(false))?;
+          let (start, end) = self.range(source);
+          let mut value = raw_value(value, raws.value).into_owned();
+          if important {
+            value.push_str(raws.important.as_deref().unwrap_or(" !important"));
+          }
+          let trimmed = value.trim_end().len();
+          let after = value.split_off(trimmed);
+          RuleOrAtRuleOrDecl::Declaration(Declaration {
+            prop,
+            value: Cow::Owned(value),
+            start,
+            end,
+            raws: Raws {
+              before: Cow::Owned(text(raws.before)),
+              between: raws.between.unwrap_or(Cow::Borrowed(":")),
+              after: Cow::Owned(if is_nested {
+                trailing + &text(raws.after)
+              } else {
+                after
+              }),
+              after_value: Cow::Borrowed(if is_nested { " " } else { "" }),
+              ..Raws::default()
+            },
+            is_nested,
+            children,
+          })
+        }
+      };
+      child.raws_mut().before = Cow::Owned(std::mem::take(&mut comments) + &child.raws().before);
+      children.push(child);
+    }
+    let last = children.len();
+    for (i, child) in children.iter_mut().enumerate() {
+      let can_end = match child {
+        RuleOrAtRuleOrDecl::Rule(_) => false,
+        RuleOrAtRuleOrDecl::AtRule(at_rule) => !at_rule.has_block,
+        RuleOrAtRuleOrDecl::Declaration(decl) => !decl.is_nested,
+      };
+      child.raws_mut().semicolon = can_end && (i + 1 < last || semicolon);
+    }
+    Ok((children, comments))
+  }
+}
+
+fn text(raw: Option<Cow<'static, str>>) -> String {
+  raw.map(Cow::into_owned).unwrap_or_default()
+}
+
+/// the raw text of a field, if it was cleaned from `value`
+fn raw_value(value: Cow<'static, str>, raw: Option<RawValue<'static>>) -> Cow<'static, str> {
+  match raw {
+    Some(raw) if raw.value == value => raw.raw,
+    _ => value,
+  }
+}
@@ -1,18 +1,28 @@
 mod ast_util;
 
+pub mod json;
+pub mod media;
+pub mod params;
 pub mod parser;
-pub mod syntax;
+pub mod selector;
+pub mod stringifier;
+pub mod sugarss;
+pub mod value;
 pub mod visitor;
 
 pub use ast_util::*;
+pub use tokenizer::line_index;
 
 use codespan_reporting::term;
 use parser::Root;
+use tokenizer::input::Input;
 pub mod error;
 
+/// Parse a stylesheet, a leading byte-order mark is skipped.
 pub fn parse<'a>(input: &'a str, file_name: Option<&'a str>) -> Root<'a> {
   let file_name = file_name.unwrap_or("default.css");
   let parser = parser::Parser::new(input);
+  let input = Input::new(input, None).css;
   match parser.parse() {
     Ok(root) => root,
     Err(err) => match err {
@@ -0,0 +1,855 @@
+//! A parser for media query lists following Media Queries Level 4, for the params of `@media`
+//! and `@import`.
+//!
+//! Queries are printed in a normalized form: keywords in lowercase, single spaces and no
+//! comments. A query that doesn't parse, like an SCSS `#{$query}`, is printed as written and
+//! evaluates like `not all`, as the specification asks.
+//!
+//! ```
+//! use recursive_parser::media::{self, Viewport};
+//!
+//! let queries = media::parse("screen and (400px <= width < 700px), print");
+//! assert_eq!(
+//!   media::stringify(&media::to_min_max(&queries)),
+//!   "screen and (min-width: 400px) and (max-width: 699.999px), print"
+//! );
+//! let phone = Viewport {
+//!   width: 500.0,
+//!   ..Default::default()
+//! };
+//! assert!(media::matches(&queries, &phone));
+//! ```
+use crate::value::unit;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQuery {
+  pub modifier: Option<Modifier>,
+  /// `screen`, `print`, `all`, in lowercase
+  pub media_type: Option<String>,
+  pub condition: Option<Condition>,
+  /// the text of a query that doesn't parse, which is printed instead of the fields; they
+  /// are those of `not all`
+  pub invalid: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+  Not,
+  Only,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+  Feature(Feature),
+  Not(Box<Condition>),
+  And(Vec<Condition>),
+  Or(Vec<Condition>),
+  /// a parenthesized block or function that is neither a feature nor a condition, it never
+  /// matches
+  Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Feature {
+  /// `(color)`
+  Boolean(String),
+  /// `(min-width: 400px)`
+  Plain { name: String, value: MediaValue },
+  /// `(width >= 400px)`, `(400px <= width < 700px)` or `(width = 400px)`
+  Range {
+    name: String,
+    lower: Option<Bound>,
+    upper: Option<Bound>,
+  },
+}
+
+/// One side of a range feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bound {
+  pub value: MediaValue,
+  /// `<=` or `>=` rather than `<` or `>`
+  pub inclusive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaValue {
+  Number(f64),
+  /// `400px`, `2dppx`, the unit in lowercase
+  Dimension(f64, String),
+  /// `16/9`
+  Ratio(f64, f64),
+  /// `landscape`, or anything else like `calc(1px + 2em)` as written
+  Ident(String),
+}
+
+/// What a query is evaluated against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+  pub media_type: String,
+  /// in CSS pixels
+  pub width: f64,
+  pub height: f64,
+  /// in `dppx`
+  pub resolution: f64,
+  /// bits per color component
+  pub color: u32,
+}
+
+impl Default for Viewport {
+  fn default() -> Self {
+    Self {
+      media_type: "screen".to_string(),
+      width: 1024.0,
+      height: 768.0,
+      resolution: 1.0,
+      color: 8,
+    }
+  }
+}
+
+/// Parse a comma separated media query list.
+pub fn parse(input: &str) -> Vec<MediaQuery> {
+  let mut parser = MediaParser {
+    input,
+    bytes: input.as_bytes(),
+    pos: 0,
+  };
+  let mut queries = vec![];
+  loop {
+    let start = parser.pos;
+    parser.skip_spaces();
+    if parser.peek().is_none() && start == 0 {
+      // an empty list matches everything
+      break;
+    }
+    let query_start = parser.pos;
+    let query = parser.query().filter(|_| {
+      parser.skip_spaces();
+      matches!(parser.peek(), None | Some(b','))
+    });
+    parser.skip_to_comma();
+    queries.push(query.unwrap_or_else(|| MediaQuery {
+      invalid: Some(input[query_start..parser.pos].trim_end().to_string()),
+      ..MediaQuery::not_all()
+    }));
+    if parser.peek() == Some(b',') {
+      parser.pos += 1;
+    } else {
+      break;
+    }
+  }
+  queries
+}
+
+/// Parse a condition on its own, like the size query of `@container`. `None` when it doesn't
+/// parse or something follows it.
+pub fn parse_condition(input: &str) -> Option<Condition> {
+  let mut parser = MediaParser {
+    input,
+    bytes: input.as_bytes(),
+    pos: 0,
+  };
+  let condition = parser.condition(true)?;
+  parser.skip_spaces();
+  parser.peek().is_none().then_some(condition)
+}
+
+/// Print a media query list, the queries are joined with `, `.
+pub fn stringify(queries: &[MediaQuery]) -> String {
+  queries
+    .iter()
+    .map(|query| query.to_string())
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Whether any query of the list matches `viewport`, an empty list always does.
+pub fn matches(queries: &[MediaQuery], viewport: &Viewport) -> bool {
+  queries.is_empty() || queries.iter().any(|query| query.matches(viewport))
+}
+
+/// The list with every range feature written as `min-` and `max-` features, for browsers
+/// without Media Queries Level 4. `<` and `>` become `max-` and `min-` features `0.001` apart
+/// from the value.
+pub fn to_min_max(queries: &[MediaQuery]) -> Vec<MediaQuery> {
+  queries.iter().map(MediaQuery::to_min_max).collect()
+}
+
+impl MediaQuery {
+  /// `not all`, which never matches
+  pub fn not_all() -> Self {
+    Self {
+      modifier: Some(Modifier::Not),
+      media_type: Some("all".to_string()),
+      condition: None,
+      invalid: None,
+    }
+  }
+
+  pub fn matches(&self, viewport: &Viewport) -> bool {
+    let media_type = match self.media_type.as_deref() {
+      None | Some("all") => true,
+      Some(media_type) => media_type.eq_ignore_ascii_case(&viewport.media_type),
+    };
+    let condition = self
+      .condition
+      .as_ref()
+      .is_none_or(|condition| condition.matches(viewport));
+    (media_type && condition) != (self.modifier == Some(Modifier::Not))
+  }
+
+  pub fn to_min_max(&self) -> Self {
+    Self {
+      condition: self.condition.as_ref().map(Condition::to_min_max),
+      ..self.clone()
+    }
+  }
+}
+
+impl Condition {
+  pub fn matches(&self, viewport: &Viewport) -> bool {
+    match self {
+      Condition::Feature(feature) => feature.matches(viewport),
+      Condition::Not(condition) => !condition.matches(viewport),
+      Condition::And(conditions) => conditions.iter().all(|c| c.matches(viewport)),
+      Condition::Or(conditions) => conditions.iter().any(|c| c.matches(viewport)),
+      Condition::Unknown(_) => false,
+    }
+  }
+
+  pub fn to_min_max(&self) -> Self {
+    match self {
+      Condition::Feature(feature) => feature.to_min_max(),
+      Condition::Not(condition) => Condition::Not(Box::new(condition.to_min_max())),
+      Condition::And(conditions) => {
+        let mut and = vec![];
+        for condition in conditions {
+          // `(a <= width <= b)` becomes two features of this `and`
+          match condition.to_min_max() {
+            Condition::And(conditions) => and.extend(conditions),
+            condition => and.push(condition),
+          }
+        }
+        Condition::And(and)
+      }
+      Condition::Or(conditions) => Condition::Or(conditions.iter().map(Self::to_min_max).collect()),
+      Condition::Unknown(raw) => Condition::Unknown(raw.clone()),
+    }
+  }
+}
+
+impl Feature {
+  pub fn matches(&self, viewport: &Viewport) -> bool {
+    match self {
+      Feature::Boolean(name) => match name.as_str() {
+        "orientation" => true,
+        name => environment(name, viewport).is_some_and(|value| value != 0.0),
+      },
+      Feature::Plain { name, value } => {
+        if name == "orientation" {
+          let orientation = if viewport.height >= viewport.width {
+            "portrait"
+          } else {
+            "landscape"
+          };
+          return matches!(value, MediaValue::Ident(ident) if ident == orientation);
+        }
+        let (name, compare): (&str, fn(f64, f64) -> bool) =
+          if let Some(name) = name.strip_prefix("min-") {
+            (name, |actual, value| actual >= value - f64::EPSILON)
+          } else if let Some(name) = name.strip_prefix("max-") {
+            (name, |actual, value| actual <= value + f64::EPSILON)
+          } else {
+            (name, |actual, value| (actual - value).abs() <= f64::EPSILON)
+          };
+        match (environment(name, viewport), value.resolve(viewport)) {
+          (Some(actual), Some(value)) => compare(actual, value),
+          _ => false,
+        }
+      }
+      Feature::Range { name, lower, upper } => {
+        let actual = match environment(name, viewport) {
+          Some(actual) => actual,
+          None => return false,
+        };
+        let lower = lower.as_ref().map_or(Some(true), |bound| {
+          let value = bound.value.resolve(viewport)?;
+          Some(if bound.inclusive {
+            actual >= value
+          } else {
+            actual > value
+          })
+        });
+        let upper = upper.as_ref().map_or(Some(true), |bound| {
+          let value = bound.value.resolve(viewport)?;
+          Some(if bound.inclusive {
+            actual <= value
+          } else {
+            actual < value
+          })
+        });
+        lower == Some(true) && upper == Some(true)
+      }
+    }
+  }
+
+  pub fn to_min_max(&self) -> Condition {
+    let (name, lower, upper) = match self {
+      Feature::Range { name, lower, upper } => (name, lower, upper),
+      feature => return Condition::Feature(feature.clone()),
+    };
+    if let (Some(lower), Some(upper)) = (lower, upper) {
+      if lower == upper && lower.inclusive {
+        return Condition::Feature(Feature::Plain {
+          name: name.clone(),
+          value: lower.value.clone(),
+        });
+      }
+    }
+    let min = lower.as_ref().map(|bound| (bound, "min-", 0.001));
+    let max = upper.as_ref().map(|bound| (bound, "max-", -0.001));
+    let mut features = vec![];
+    for (bound, prefix, step) in min.into_iter().chain(max) {
+      let value = if bound.inclusive {
+        Some(bound.value.clone())
+      } else {
+        bound.value.offset(step)
+      };
+      features.push(match value {
+        Some(value) => Feature::Plain {
+          name: format!("{}{}", prefix, name),
+          value,
+        },
+        // `(aspect-ratio > 16/9)` can't be written with `min-`
+        None => self.clone(),
+      });
+    }
+    if features.len() == 2 && features[0] == features[1] {
+      features.pop();
+    }
+    match features.len() {
+      1 => Condition::Feature(features.pop().unwrap()),
+      _ => Condition::And(features.into_iter().map(Condition::Feature).collect()),
+    }
+  }
+}
+
+impl MediaValue {
+  /// the value in the unit [`Viewport`] uses for it, `None` for idents and unknown units
+  fn resolve(&self, viewport: &Viewport) -> Option<f64> {
+    match self {
+      MediaValue::Number(number) => Some(*number),
+      MediaValue::Ratio(a, b) => Some(a / b),
+      MediaValue::Dimension(number, unit) => {
+        let factor = match unit.as_str() {
+          "px" | "dppx" | "x" => 1.0,
+          "em" | "rem" => 16.0,
+          "in" => 96.0,
+          "cm" => 96.0 / 2.54,
+          "mm" => 96.0 / 25.4,
+          "q" => 96.0 / 101.6,
+          "pt" => 96.0 / 72.0,
+          "pc" => 16.0,
+          "vw" => viewport.width / 100.0,
+          "vh" => viewport.height / 100.0,
+          "dpi" => 1.0 / 96.0,
+          "dpcm" => 2.54 / 96.0,
+          _ => return None,
+        };
+        Some(number * factor)
+      }
+      MediaValue::Ident(_) => None,
+    }
+  }
+
+  fn offset(&self, step: f64) -> Option<MediaValue> {
+    // rounded, so `400px - 0.001` doesn't print as `399.99899999999997px`
+    let round = |number: f64| ((number + step) * 1000.0).round() / 1000.0;
+    match self {
+      MediaValue::Number(number) => Some(MediaValue::Number(round(*number))),
+      MediaValue::Dimension(number, unit) => {
+        Some(MediaValue::Dimension(round(*number), unit.clone()))
+      }
+      _ => None,
+    }
+  }
+}
+
+/// the value of a media feature for `viewport`
+fn environment(name: &str, viewport: &Viewport) -> Option<f64> {
+  match name {
+    "width" | "device-width" => Some(viewport.width),
+    "height" | "device-height" => Some(viewport.height),
+    "aspect-ratio" | "device-aspect-ratio" => Some(viewport.width / viewport.height),
+    "resolution" => Some(viewport.resolution),
+    "color" => Some(viewport.color as f64),
+    "monochrome" | "grid" | "color-index" => Some(0.0),
+    _ => None,
+  }
+}
+
+impl fmt::Display for MediaQuery {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if let Some(invalid) = &self.invalid {
+      return f.write_str(invalid);
+    }
+    let mut words = vec![];
+    match self.modifier {
+      Some(Modifier::Not) if self.media_type.is_some() => words.push("not".to_string()),
+      Some(Modifier::Only) => words.push("only".to_string()),
+      _ => {}
+    }
+    if let Some(media_type) = &self.media_type {
+      words.push(media_type.clone());
+    }
+    if let Some(condition) = &self.condition {
+      if !words.is_empty() {
+        words.push("and".to_string());
+        // `and` chains and `not` are allowed after the media type, `or` isn't
+        match condition {
+          Condition::Or(_) => words.push(condition.nested()),
+          _ => words.push(condition.to_string()),
+        }
+      } else if self.modifier == Some(Modifier::Not) {
+        words.push(format!("not {}", condition.nested()));
+      } else {
+        words.push(condition.to_string());
+      }
+    }
+    f.write_str(&words.join(" "))
+  }
+}
+
+impl Condition {
+  /// `self` wrapped in parentheses unless it already is
+  fn nested(&self) -> String {
+    match self {
+      Condition::Feature(_) | Condition::Unknown(_) => self.to_string(),
+      _ => format!("({})", self),
+    }
+  }
+}
+
+impl fmt::Display for Condition {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Condition::Feature(feature) => write!(f, "{}", feature),
+      Condition::Not(condition) => write!(f, "not {}", condition.nested()),
+      Condition::And(conditions) | Condition::Or(conditions) => {
+        let separator = if matches!(self, Condition::And(_)) {
+          " and "
+        } else {
+          " or "
+        };
+        let conditions = conditions.iter().map(Condition::nested).collect::<Vec<_>>();
+        f.write_str(&conditions.join(separator))
+      }
+      Condition::Unknown(raw) => f.write_str(raw),
+    }
+  }
+}
+
+impl fmt::Display for Feature {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Feature::Boolean(name) => write!(f, "({})", name),
+      Feature::Plain { name, value } => write!(f, "({}: {})", name, value),
+      Feature::Range { name, lower, upper } => {
+        let op = |bound: &Bound, ch: char| {
+          if bound.inclusive {
+            format!("{}=", ch)
+          } else {
+            ch.to_string()
+          }
+        };
+        match (lower, upper) {
+          (Some(lower), Some(upper)) if lower == upper && lower.inclusive => {
+            write!(f, "({} = {})", name, lower.value)
+          }
+          (Some(lower), Some(upper)) => write!(
+            f,
+            "({} {} {} {} {})",
+            lower.value,
+            op(lower, '<'),
+            name,
+            op(upper, '<'),
+            upper.value
+          ),
+          (Some(lower), None) => write!(f, "({} {} {})", name, op(lower, '>'), lower.value),
+          (None, Some(upper)) => write!(f, "({} {} {})", name, op(upper, '<'), upper.value),
+          (None, None) => write!(f, "({})", name),
+        }
+      }
+    }
+  }
+}
+
+impl fmt::Display for MediaValue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MediaValue::Number(number) => write!(f, "{}", number),
+      MediaValue::Dimension(number, unit) => write!(f, "{}{}", number, unit),
+      MediaValue::Ratio(a, b) => write!(f, "{}/{}", a, b),
+      MediaValue::Ident(ident) => f.write_str(ident),
+    }
+  }
+}
+
+struct MediaParser<'a> {
+  input: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+/// comparison operators, `(ch, inclusive)`
+type Op = (u8, bool);
+
+impl<'a> MediaParser<'a> {
+  fn peek(&self) -> Option<u8> {
+    self.bytes.get(self.pos).copied()
+  }
+
+  /// skip whitespace and comments
+  fn skip_spaces(&mut self) {
+    loop {
+      match self.peek() {
+        Some(b) if b.is_ascii_whitespace() => self.pos += 1,
+        Some(b'/') if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+          self.pos = memchr::memmem::find(&self.bytes[self.pos + 2..], b"*/")
+            .map_or(self.bytes.len(), |end| self.pos + 2 + end + 2);
+        }
+        _ => return,
+      }
+    }
+  }
+
+  /// skip to the next `,` outside of parentheses, interpolations and comments, or to the end
+  fn skip_to_comma(&mut self) {
+    let mut depth = 0usize;
+    while let Some(b) = self.peek() {
+      match b {
+        b'(' | b'{' => depth += 1,
+        b')' | b'}' => depth = depth.saturating_sub(1),
+        b',' if depth == 0 => return,
+        b'/' if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+          self.skip_spaces();
+          continue;
+        }
+        _ => {}
+      }
+      self.pos += 1;
+    }
+  }
+
+  /// the identifier at the current position, in lowercase, without consuming it
+  fn peek_ident(&self) -> Option<(String, usize)> {
+    let rest = &self.input[self.pos..];
+    let len = rest
+      .find(|ch: char| !(ch.is_alphanumeric() || ch == '-' || ch == '_'))
+      .unwrap_or(rest.len());
+    let ident = &rest[..len];
+    let starts = ident
+      .chars()
+      .next()
+      .is_some_and(|ch| ch.is_alphabetic() || ch == '-' || ch == '_');
+    (starts && ident != "-").then(|| (ident.to_ascii_lowercase(), self.pos + len))
+  }
+
+  /// consume `keyword` if it is the next identifier
+  fn keyword(&mut self, keyword: &str) -> bool {
+    match self.peek_ident() {
+      Some((ident, end)) if ident == keyword => {
+        self.pos = end;
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn query(&mut self) -> Option<MediaQuery> {
+    self.skip_spaces();
+    let start = self.pos;
+    if self.peek() == Some(b'(') || self.keyword("not") && self.condition_follows() {
+      self.pos = start;
+      return Some(MediaQuery {
+        modifier: None,
+        media_type: None,
+        condition: Some(self.condition(true)?),
+        invalid: None,
+      });
+    }
+    self.pos = start;
+    let mut modifier = None;
+    if self.keyword("not") {
+      modifier = Some(Modifier::Not);
+    } else if self.keyword("only") {
+      modifier = Some(Modifier::Only);
+    }
+    self.skip_spaces();
+    let (media_type, end) = self.peek_ident()?;
+    if ["and", "or", "not", "only", "layer"].contains(&media_type.as_str()) {
+      return None;
+    }
+    self.pos = end;
+    let mut condition = None;
+    let before_and = self.pos;
+    self.skip_spaces();
+    if self.keyword("and") {
+      condition = Some(self.condition(false)?);
+    } else {
+      self.pos = before_and;
+    }
+    Some(MediaQuery {
+      modifier,
+      media_type: Some(media_type),
+      condition,
+      invalid: None,
+    })
+  }
+
+  /// whether a `(` follows, after whitespace
+  fn condition_follows(&mut self) -> bool {
+    let start = self.pos;
+    self.skip_spaces();
+    let follows = self.pos > start && self.peek() == Some(b'(');
+    self.pos = start;
+    follows
+  }
+
+  /// `not <in-parens>`, or `<in-parens>` joined with `and`, or with `or` when `or` is allowed
+  fn condition(&mut self, or: bool) -> Option<Condition> {
+    self.skip_spaces();
+    if self.keyword("not") {
+      self.skip_spaces();
+      return Some(Condition::Not(Box::new(self.in_parens()?)));
+    }
+    let first = self.in_parens()?;
+    let mut conditions = vec![first];
+    let mut joiner = None;
+    loop {
+      let before = self.pos;
+      self.skip_spaces();
+      let and = self.keyword("and");
+      let is_or = !and && or && self.keyword("or");
+      if !and && !is_or {
+        self.pos = before;
+        break;
+      }
+      if joiner.is_some_and(|joiner| joiner != and) {
+        // `and` and `or` can't be mixed without parentheses
+        return None;
+      }
+      joiner = Some(and);
+      self.skip_spaces();
+      conditions.push(self.in_parens()?);
+    }
+    Some(match joiner {
+      None => conditions.pop().unwrap(),
+      Some(true) => Condition::And(conditions),
+      Some(false) => Condition::Or(conditions),
+    })
+  }
+
+  fn in_parens(&mut self) -> Option<Condition> {
+    if self.peek() != Some(b'(') {
+      // a function like `selector()` is general enclosed
+      let (_, end) = self.peek_ident()?;
+      if self.bytes.get(end) != Some(&b'(') {
+        return None;
+      }
+      let start = self.pos;
+      self.pos = end;
+      self.block()?;
+      return Some(Condition::Unknown(self.input[start..self.pos].to_string()));
+    }
+    let start = self.pos;
+    self.pos += 1;
+    self.skip_spaces();
+    let nested = self.peek() == Some(b'(') || {
+      let inner = self.pos;
+      let not = self.keyword("not") && self.condition_follows();
+      self.pos = inner;
+      not
+    };
+    if nested {
+      if let Some(condition) = self.condition(true) {
+        self.skip_spaces();
+        if self.peek() == Some(b')') {
+          self.pos += 1;
+          return Some(condition);
+        }
+      }
+    } else if let Some(feature) = self.feature() {
+      self.skip_spaces();
+      if self.peek() == Some(b')') {
+        self.pos += 1;
+        return Some(Condition::Feature(feature));
+      }
+    }
+    self.pos = start;
+    self.block()?;
+    Some(Condition::Unknown(self.input[start..self.pos].to_string()))
+  }
+
+  /// skip a parenthesized block, `None` when it isn't closed
+  fn block(&mut self) -> Option<()> {
+    let mut depth = 0usize;
+    while let Some(b) = self.peek() {
+      self.pos += 1;
+      match b {
+        b'(' => depth += 1,
+        b')' => {
+          depth -= 1;
+          if depth == 0 {
+            return Some(());
+          }
+        }
+        _ => {}
+      }
+    }
+    None
+  }
+
+  fn feature(&mut self) -> Option<Feature> {
+    if let Some((name, end)) = self.peek_ident() {
+      let after_name = self.pos;
+      self.pos = end;
+      self.skip_spaces();
+      match self.peek() {
+        Some(b')') => return Some(Feature::Boolean(name)),
+        Some(b':') => {
+          self.pos += 1;
+          self.skip_spaces();
+          let value = self.value()?;
+          return Some(Feature::Plain { name, value });
+        }
+        _ => {}
+      }
+      if let Some(op) = self.op() {
+        self.skip_spaces();
+        let value = self.value()?;
+        return Some(range(name, value, op, None));
+      }
+      // the name was a value, like `landscape` in a reversed range
+      self.pos = after_name;
+    }
+    let value = self.value()?;
+    self.skip_spaces();
+    let op = self.op()?;
+    self.skip_spaces();
+    let (name, end) = self.peek_ident()?;
+    self.pos = end;
+    let before = self.pos;
+    self.skip_spaces();
+    if let Some(second) = self.op() {
+      if second.0 != op.0 || op.0 == b'=' {
+        return None;
+      }
+      self.skip_spaces();
+      let upper = self.value()?;
+      return Some(range(name, value, op, Some((second, upper))));
+    }
+    self.pos = before;
+    Some(range(name, value, op, None).reversed())
+  }
+
+  fn op(&mut self) -> Option<Op> {
+    let ch = self.peek().filter(|b| matches!(b, b'<' | b'>' | b'='))?;
+    self.pos += 1;
+    let inclusive = ch == b'=' || self.peek() == Some(b'=');
+    if ch != b'=' && inclusive {
+      self.pos += 1;
+    }
+    Some((ch, inclusive))
+  }
+
+  fn value(&mut self) -> Option<MediaValue> {
+    let start = self.pos;
+    let len = self.input[start..]
+      .find(|ch: char| ch.is_whitespace() || matches!(ch, ')' | '(' | '<' | '>' | '=' | '/' | ','))
+      .unwrap_or(self.input.len() - start);
+    if len == 0 {
+      return None;
+    }
+    let word = &self.input[start..start + len];
+    self.pos += len;
+    if self.peek() == Some(b'(') {
+      // a function like `calc()`
+      self.block()?;
+      return Some(MediaValue::Ident(self.input[start..self.pos].to_string()));
+    }
+    let (number, unit) = match unit(word) {
+      Some((number, unit)) => (number.parse::<f64>().ok()?, unit),
+      None => return Some(MediaValue::Ident(word.to_ascii_lowercase())),
+    };
+    if !unit.is_empty() {
+      return Some(MediaValue::Dimension(number, unit.to_ascii_lowercase()));
+    }
+    let before_slash = self.pos;
+    self.skip_spaces();
+    if self.peek() == Some(b'/') {
+      self.pos += 1;
+      self.skip_spaces();
+      if let Some(MediaValue::Number(denominator)) = self.value() {
+        return Some(MediaValue::Ratio(number, denominator));
+      }
+      return None;
+    }
+    self.pos = before_slash;
+    Some(MediaValue::Number(number))
+  }
+}
+
+/// a range feature from `name op value`, or from `value op name op upper` when `upper` is set
+fn range(name: String, value: MediaValue, op: Op, upper: Option<(Op, MediaValue)>) -> Feature {
+  let bound = |value: MediaValue, (_, inclusive): Op| Bound { value, inclusive };
+  if let Some((second, upper)) = upper {
+    // `a < name < b` or `a > name > b`
+    let (lower, upper) = (bound(value, op), bound(upper, second));
+    return match op.0 {
+      b'<' => Feature::Range {
+        name,
+        lower: Some(lower),
+        upper: Some(upper),
+      },
+      _ => Feature::Range {
+        name,
+        lower: Some(upper),
+        upper: Some(lower),
+      },
+    };
+  }
+  let bound = bound(value, op);
+  match op.0 {
+    b'<' => Feature::Range {
+      name,
+      lower: None,
+      upper: Some(bound),
+    },
+    b'>' => Feature::Range {
+      name,
+      lower: Some(bound),
+      upper: None,
+    },
+    _ => Feature::Range {
+      name,
+      lower: Some(bound.clone()),
+      upper: Some(bound),
+    },
+  }
+}
+
+impl Feature {
+  /// `value op name` read as `name op value`, with the bounds swapped
+  fn reversed(self) -> Feature {
+    match self {
+      Feature::Range { name, lower, upper } => Feature::Range {
+        name,
+        lower: upper,
+        upper: lower,
+      },
+      feature => feature,
+    }
+  }
+}
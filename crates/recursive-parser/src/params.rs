@@ -0,0 +1,657 @@
+//! Typed params of the at-rules with a grammar of their own: `@media`, `@import`, `@supports`,
+//! `@layer`, `@container` and `@keyframes`.
+//!
+//! Params keep the text they were parsed from, with the whitespace and comments around it, and
+//! print it back as long as they are unchanged. Changed params are printed in a normalized form,
+//! with single spaces and lowercase keywords. Declarations, selectors and names keep their text.
+//!
+//! ```
+//! use recursive_parser::params::{self, Params};
+//!
+//! let mut params = params::parse("import", "'theme.css'  layer(theme) supports(display:grid)");
+//! match &mut params {
+//!   Some(Params::Import(import)) => {
+//!     assert_eq!(import.url, "theme.css");
+//!     assert_eq!(import.layer.as_deref(), Some("theme"));
+//!     assert_eq!(
+//!       import.to_string(),
+//!       "'theme.css'  layer(theme) supports(display:grid)"
+//!     );
+//!     import.media = recursive_parser::media::parse("screen");
+//!   }
+//!   _ => unreachable!(),
+//! }
+//! assert_eq!(
+//!   params.unwrap().to_string(),
+//!   "'theme.css' layer(theme) supports(display: grid) screen"
+//! );
+//! ```
+use crate::media::{self, Condition, MediaQuery};
+use memchr::memmem;
+use std::borrow::Cow;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Params<'a> {
+  Media {
+    queries: Vec<MediaQuery>,
+    raws: Raws<'a>,
+  },
+  Import(Import<'a>),
+  Supports {
+    condition: Supports<'a>,
+    raws: Raws<'a>,
+  },
+  /// `@layer base, theme.dark`, empty for an anonymous `@layer {}`
+  Layer {
+    names: Vec<Cow<'a, str>>,
+    raws: Raws<'a>,
+  },
+  Container(Container<'a>),
+  /// `@keyframes fade` or `@keyframes "fade"`
+  Keyframes {
+    name: Cow<'a, str>,
+    quote: Option<char>,
+    raws: Raws<'a>,
+  },
+}
+
+/// The text params were parsed from, split into the whitespace and comments around them and the
+/// params themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Raws<'a> {
+  pub before: Cow<'a, str>,
+  /// the params as written, printed instead of the normalized form while they still parse to
+  /// the same value
+  pub value: Cow<'a, str>,
+  pub after: Cow<'a, str>,
+}
+
+/// `@import url("a.css") layer(base) supports(display: grid) screen`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import<'a> {
+  /// the url without quotes, with its escapes
+  pub url: Cow<'a, str>,
+  /// `url(...)` rather than a string
+  pub function: bool,
+  /// `"` or `'`, `None` for an unquoted `url()`
+  pub quote: Option<char>,
+  /// the layer name, empty for an anonymous `layer`
+  pub layer: Option<Cow<'a, str>>,
+  pub supports: Option<Supports<'a>>,
+  pub media: Vec<MediaQuery>,
+  pub raws: Raws<'a>,
+}
+
+/// A `@supports` condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Supports<'a> {
+  /// `(display: grid)`
+  Declaration {
+    prop: Cow<'a, str>,
+    value: Cow<'a, str>,
+  },
+  /// `selector(a > b)`, with the selector list as written for [`crate::selector::parse`]
+  Selector(Cow<'a, str>),
+  Not(Box<Supports<'a>>),
+  And(Vec<Supports<'a>>),
+  Or(Vec<Supports<'a>>),
+  /// a function like `font-tech(color-colrv1)`, or a parenthesized block that is neither a
+  /// declaration nor a condition
+  Unknown(Cow<'a, str>),
+}
+
+/// `@container sidebar (min-width: 400px)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container<'a> {
+  pub name: Option<Cow<'a, str>>,
+  /// the size query, a style query like `style(--a: 1)` is a [`Condition::Unknown`]
+  pub condition: Option<Condition>,
+  pub raws: Raws<'a>,
+}
+
+/// Parse the `params` of the at-rule `name`, given without the `@`. `None` for other at-rules
+/// and for params that don't parse.
+pub fn parse<'a>(name: &str, params: &'a str) -> Option<Params<'a>> {
+  let mut parser = ParamsParser::new(params);
+  parser.skip_spaces();
+  let start = parser.pos;
+  let end = start + trim_end_spaces(&params[start..]).len();
+  let mut parsed = parse_value(name, &params[start..end])?;
+  let raws = parsed.raws_mut();
+  raws.before = Cow::Borrowed(&params[..start]);
+  raws.after = Cow::Borrowed(&params[end..]);
+  Some(parsed)
+}
+
+/// parse params without whitespace or comments around them
+fn parse_value<'a>(name: &str, value: &'a str) -> Option<Params<'a>> {
+  let mut parser = ParamsParser::new(value);
+  let name = name.to_ascii_lowercase();
+  let mut parsed = match name.as_str() {
+    "media" => Params::Media {
+      queries: media::parse(value),
+      raws: Raws::default(),
+    },
+    "import" => Params::Import(parser.import()?),
+    "supports" => Params::Supports {
+      condition: parser.supports()?,
+      raws: Raws::default(),
+    },
+    "layer" => Params::Layer {
+      names: parser.layers()?,
+      raws: Raws::default(),
+    },
+    "container" => Params::Container(parser.container()?),
+    _ if vendor_prefixed(&name, "keyframes") => parser.keyframes()?,
+    _ => return None,
+  };
+  parser.skip_spaces();
+  if !parser.at_end() && !matches!(parsed, Params::Media { .. }) {
+    return None;
+  }
+  parsed.raws_mut().value = Cow::Borrowed(value);
+  Some(parsed)
+}
+
+/// `input` without the whitespace and comments at its end
+fn trim_end_spaces(mut input: &str) -> &str {
+  loop {
+    input = input.trim_end();
+    match input.strip_suffix("*/").and_then(|rest| rest.rfind("/*")) {
+      Some(start) => input = &input[..start],
+      None => return input,
+    }
+  }
+}
+
+fn vendor_prefixed(name: &str, unprefixed: &str) -> bool {
+  ["", "-webkit-", "-moz-", "-o-"]
+    .iter()
+    .any(|prefix| name.strip_prefix(prefix) == Some(unprefixed))
+}
+
+impl<'a> Params<'a> {
+  pub fn raws(&self) -> &Raws<'a> {
+    match self {
+      Params::Import(Import { raws, .. }) | Params::Container(Container { raws, .. }) => raws,
+      Params::Media { raws, .. }
+      | Params::Supports { raws, .. }
+      | Params::Layer { raws, .. }
+      | Params::Keyframes { raws, .. } => raws,
+    }
+  }
+
+  pub fn raws_mut(&mut self) -> &mut Raws<'a> {
+    match self {
+      Params::Import(Import { raws, .. }) | Params::Container(Container { raws, .. }) => raws,
+      Params::Media { raws, .. }
+      | Params::Supports { raws, .. }
+      | Params::Layer { raws, .. }
+      | Params::Keyframes { raws, .. } => raws,
+    }
+  }
+
+  /// an at-rule name with params like `self`
+  fn name(&self) -> &'static str {
+    match self {
+      Params::Media { .. } => "media",
+      Params::Import(_) => "import",
+      Params::Supports { .. } => "supports",
+      Params::Layer { .. } => "layer",
+      Params::Container(_) => "container",
+      Params::Keyframes { .. } => "keyframes",
+    }
+  }
+
+  /// whether `raws.value` still parses to `self`
+  fn unchanged(&self) -> bool {
+    let raws = self.raws();
+    parse_value(self.name(), &raws.value).is_some_and(|mut parsed| {
+      *parsed.raws_mut() = raws.clone();
+      parsed == *self
+    })
+  }
+}
+
+/// write `raws` around either `raws.value` or, for changed params, their normalized form
+fn write_raws(
+  f: &mut fmt::Formatter<'_>,
+  raws: &Raws<'_>,
+  unchanged: bool,
+  normalized: impl FnOnce(&mut fmt::Formatter<'_>) -> fmt::Result,
+) -> fmt::Result {
+  f.write_str(&raws.before)?;
+  if unchanged {
+    f.write_str(&raws.value)?;
+  } else {
+    normalized(f)?;
+  }
+  f.write_str(&raws.after)
+}
+
+impl<'a> fmt::Display for Params<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write_raws(f, self.raws(), self.unchanged(), |f| match self {
+      Params::Media { queries, .. } => f.write_str(&media::stringify(queries)),
+      Params::Import(import) => import.normalized(f),
+      Params::Supports { condition, .. } => write!(f, "{}", condition),
+      Params::Layer { names, .. } => f.write_str(&names.join(", ")),
+      Params::Container(container) => container.normalized(f),
+      Params::Keyframes { name, quote, .. } => match quote {
+        Some(quote) => write!(f, "{}{}{}", quote, name, quote),
+        None => f.write_str(name),
+      },
+    })
+  }
+}
+
+impl<'a> fmt::Display for Import<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", Params::Import(self.clone()))
+  }
+}
+
+impl<'a> Import<'a> {
+  fn normalized(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let quote = self.quote.map(String::from).unwrap_or_default();
+    if self.function {
+      write!(f, "url({}{}{})", quote, self.url, quote)?;
+    } else {
+      write!(f, "{}{}{}", quote, self.url, quote)?;
+    }
+    match self.layer.as_deref() {
+      Some("") => f.write_str(" layer")?,
+      Some(layer) => write!(f, " layer({})", layer)?,
+      None => {}
+    }
+    match &self.supports {
+      Some(Supports::Declaration { prop, value }) => write!(f, " supports({}: {})", prop, value)?,
+      Some(supports) => write!(f, " supports({})", supports)?,
+      None => {}
+    }
+    if !self.media.is_empty() {
+      write!(f, " {}", media::stringify(&self.media))?;
+    }
+    Ok(())
+  }
+}
+
+impl<'a> Supports<'a> {
+  /// `self` wrapped in parentheses unless it already is
+  fn nested(&self) -> String {
+    match self {
+      Supports::Not(_) | Supports::And(_) | Supports::Or(_) => format!("({})", self),
+      _ => self.to_string(),
+    }
+  }
+}
+
+impl<'a> fmt::Display for Supports<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Supports::Declaration { prop, value } => write!(f, "({}: {})", prop, value),
+      Supports::Selector(selector) => write!(f, "selector({})", selector),
+      Supports::Not(condition) => write!(f, "not {}", condition.nested()),
+      Supports::And(conditions) | Supports::Or(conditions) => {
+        let separator = if matches!(self, Supports::And(_)) {
+          " and "
+        } else {
+          " or "
+        };
+        let conditions = conditions.iter().map(Supports::nested).collect::<Vec<_>>();
+        f.write_str(&conditions.join(separator))
+      }
+      Supports::Unknown(raw) => f.write_str(raw),
+    }
+  }
+}
+
+impl<'a> fmt::Display for Container<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", Params::Container(self.clone()))
+  }
+}
+
+impl<'a> Container<'a> {
+  fn normalized(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match (&self.name, &self.condition) {
+      (Some(name), Some(condition)) => write!(f, "{} {}", name, condition),
+      (Some(name), None) => f.write_str(name),
+      (None, Some(condition)) => write!(f, "{}", condition),
+      (None, None) => Ok(()),
+    }
+  }
+}
+
+struct ParamsParser<'a> {
+  input: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> ParamsParser<'a> {
+  fn new(input: &'a str) -> Self {
+    Self {
+      input,
+      bytes: input.as_bytes(),
+      pos: 0,
+    }
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.bytes.get(self.pos).copied()
+  }
+
+  fn at_end(&self) -> bool {
+    self.pos >= self.bytes.len()
+  }
+
+  /// skip whitespace and comments
+  fn skip_spaces(&mut self) {
+    loop {
+      while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+        self.pos += 1;
+      }
+      if !self.bytes[self.pos..].starts_with(b"/*") {
+        return;
+      }
+      self.skip_comment();
+    }
+  }
+
+  /// skip the comment at the current `/*`, an unclosed one ends at the end of the input
+  fn skip_comment(&mut self) {
+    self.pos = memmem::find(&self.bytes[self.pos + 2..], b"*/")
+      .map_or(self.bytes.len(), |end| self.pos + 2 + end + 2);
+  }
+
+  /// consume the identifier at the current position
+  fn ident(&mut self) -> Option<&'a str> {
+    let rest = &self.input[self.pos..];
+    let mut len = 0;
+    let mut chars = rest.char_indices();
+    while let Some((i, ch)) = chars.next() {
+      if ch == '\\' {
+        // an escaped character
+        len = chars.next().map_or(rest.len(), |(i, ch)| i + ch.len_utf8());
+      } else if ch.is_alphanumeric() || ch == '-' || ch == '_' || !ch.is_ascii() {
+        len = i + ch.len_utf8();
+      } else {
+        break;
+      }
+    }
+    let ident = &rest[..len];
+    if ident.is_empty() || ident == "-" || ident.starts_with(|ch: char| ch.is_ascii_digit()) {
+      return None;
+    }
+    self.pos += len;
+    Some(ident)
+  }
+
+  /// consume `keyword` if it is the next identifier
+  fn keyword(&mut self, keyword: &str) -> bool {
+    let start = self.pos;
+    if self
+      .ident()
+      .is_some_and(|ident| ident.eq_ignore_ascii_case(keyword))
+    {
+      return true;
+    }
+    self.pos = start;
+    false
+  }
+
+  /// consume the string at the current quote, and return its content
+  fn string(&mut self) -> Option<(&'a str, char)> {
+    let quote = self.peek().filter(|b| matches!(b, b'"' | b'\''))?;
+    let start = self.pos + 1;
+    let mut pos = start;
+    while let Some(&b) = self.bytes.get(pos) {
+      match b {
+        b'\\' => pos += 2,
+        b'\n' => return None,
+        _ if b == quote => {
+          self.pos = pos + 1;
+          return Some((&self.input[start..pos], quote as char));
+        }
+        _ => pos += 1,
+      }
+    }
+    None
+  }
+
+  /// consume the parenthesized block at the current `(`, and return what is inside
+  fn block(&mut self) -> Option<&'a str> {
+    if self.peek() != Some(b'(') {
+      return None;
+    }
+    let start = self.pos + 1;
+    let mut depth = 0usize;
+    while let Some(b) = self.peek() {
+      match b {
+        b'"' | b'\'' => {
+          self.string()?;
+          continue;
+        }
+        b'/' if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+          self.skip_comment();
+          continue;
+        }
+        b'\\' => self.pos += 1,
+        b'(' => depth += 1,
+        b')' => {
+          depth -= 1;
+          if depth == 0 {
+            self.pos += 1;
+            return Some(&self.input[start..self.pos - 1]);
+          }
+        }
+        _ => {}
+      }
+      self.pos += 1;
+    }
+    None
+  }
+
+  fn import(&mut self) -> Option<Import<'a>> {
+    self.skip_spaces();
+    let (url, function, quote) = if let Some((url, quote)) = self.string() {
+      (url, false, Some(quote))
+    } else if self.keyword("url") {
+      let url = self.block()?.trim();
+      let mut inner = ParamsParser::new(url);
+      match inner.string() {
+        Some((url, quote)) if inner.at_end() => (url, true, Some(quote)),
+        _ => (url, true, None),
+      }
+    } else {
+      return None;
+    };
+
+    self.skip_spaces();
+    let mut layer = None;
+    if self.keyword("layer") {
+      if self.peek() == Some(b'(') {
+        let name = self.block()?.trim();
+        if name.is_empty() {
+          return None;
+        }
+        layer = Some(Cow::Borrowed(name));
+      } else {
+        layer = Some(Cow::Borrowed(""));
+      }
+    }
+
+    self.skip_spaces();
+    let mut supports = None;
+    let before_supports = self.pos;
+    if self.keyword("supports") && self.peek() == Some(b'(') {
+      let inner = self.block()?;
+      // a bare declaration is allowed, `supports(display: grid)`
+      supports = Some(
+        ParamsParser::new(inner)
+          .condition()
+          .or_else(|| declaration(inner))?,
+      );
+    } else {
+      self.pos = before_supports;
+    }
+
+    self.skip_spaces();
+    let media = &self.input[self.pos..];
+    self.pos = self.input.len();
+    Some(Import {
+      url: Cow::Borrowed(url),
+      function,
+      quote,
+      layer,
+      supports,
+      media: if media.is_empty() {
+        vec![]
+      } else {
+        media::parse(media)
+      },
+      raws: Raws::default(),
+    })
+  }
+
+  /// a condition that makes up all of the input
+  fn condition(mut self) -> Option<Supports<'a>> {
+    let condition = self.supports()?;
+    self.skip_spaces();
+    self.at_end().then_some(condition)
+  }
+
+  /// `not <in-parens>`, or `<in-parens>` joined with either `and` or `or`
+  fn supports(&mut self) -> Option<Supports<'a>> {
+    self.skip_spaces();
+    if self.keyword("not") {
+      self.skip_spaces();
+      return Some(Supports::Not(Box::new(self.in_parens()?)));
+    }
+    let mut conditions = vec![self.in_parens()?];
+    let mut joiner = None;
+    loop {
+      let before = self.pos;
+      self.skip_spaces();
+      let and = self.keyword("and");
+      if !and && !self.keyword("or") {
+        self.pos = before;
+        break;
+      }
+      if joiner.is_some_and(|joiner| joiner != and) {
+        // `and` and `or` can't be mixed without parentheses
+        return None;
+      }
+      joiner = Some(and);
+      self.skip_spaces();
+      conditions.push(self.in_parens()?);
+    }
+    Some(match joiner {
+      None => conditions.pop().unwrap(),
+      Some(true) => Supports::And(conditions),
+      Some(false) => Supports::Or(conditions),
+    })
+  }
+
+  fn in_parens(&mut self) -> Option<Supports<'a>> {
+    let start = self.pos;
+    if self.peek() == Some(b'(') {
+      let inner = self.block()?;
+      return Some(
+        ParamsParser::new(inner)
+          .condition()
+          .or_else(|| declaration(inner))
+          .unwrap_or(Supports::Unknown(Cow::Borrowed(
+            &self.input[start..self.pos],
+          ))),
+      );
+    }
+    let name = self.ident()?;
+    let inner = self.block()?;
+    Some(if name.eq_ignore_ascii_case("selector") {
+      Supports::Selector(Cow::Borrowed(inner.trim()))
+    } else {
+      Supports::Unknown(Cow::Borrowed(&self.input[start..self.pos]))
+    })
+  }
+
+  /// a comma separated list of dotted layer names
+  fn layers(&mut self) -> Option<Vec<Cow<'a, str>>> {
+    let mut names = vec![];
+    self.skip_spaces();
+    if self.at_end() {
+      return Some(names);
+    }
+    loop {
+      let start = self.pos;
+      self.ident()?;
+      while self.peek() == Some(b'.') {
+        self.pos += 1;
+        self.ident()?;
+      }
+      names.push(Cow::Borrowed(&self.input[start..self.pos]));
+      self.skip_spaces();
+      if self.peek() != Some(b',') {
+        break;
+      }
+      self.pos += 1;
+      self.skip_spaces();
+    }
+    Some(names)
+  }
+
+  fn container(&mut self) -> Option<Container<'a>> {
+    self.skip_spaces();
+    let start = self.pos;
+    let mut name = None;
+    if let Some(ident) = self.ident() {
+      let keyword = ["not", "and", "or", "none"]
+        .iter()
+        .any(|keyword| ident.eq_ignore_ascii_case(keyword));
+      if keyword || self.peek() == Some(b'(') {
+        self.pos = start;
+      } else {
+        name = Some(Cow::Borrowed(ident));
+      }
+    }
+    self.skip_spaces();
+    let condition = self.input[self.pos..].trim_end();
+    self.pos = self.input.len();
+    let condition = match condition {
+      "" if name.is_some() => None,
+      condition => Some(media::parse_condition(condition)?),
+    };
+    Some(Container {
+      name,
+      condition,
+      raws: Raws::default(),
+    })
+  }
+
+  fn keyframes(&mut self) -> Option<Params<'a>> {
+    self.skip_spaces();
+    let (name, quote) = match self.string() {
+      Some((name, quote)) => (name, Some(quote)),
+      None => (self.ident()?, None),
+    };
+    Some(Params::Keyframes {
+      name: Cow::Borrowed(name),
+      quote,
+      raws: Raws::default(),
+    })
+  }
+}
+
+/// `prop: value`, with the whitespace around both trimmed
+fn declaration(input: &str) -> Option<Supports<'_>> {
+  let (prop, value) = input.split_once(':')?;
+  let mut parser = ParamsParser::new(prop.trim());
+  let prop = parser.ident()?;
+  let value = value.trim();
+  (parser.at_end() && !value.is_empty()).then_some(Supports::Declaration {
+    prop: Cow::Borrowed(prop),
+    value: Cow::Borrowed(value),
+  })
+}
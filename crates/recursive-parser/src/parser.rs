@@ -1,13 +1,40 @@
 use crate::error::{PostcssError, Result};
-use crate::syntax::Lexer;
+use crate::{params, selector, value};
 use std::borrow::Cow;
-use std::iter::Peekable;
-use tokenizer::{Token, TokenType};
+use tokenizer::input::Input;
+use tokenizer::{Syntax, Token, TokenType, Tokenizer};
+
+/// The source text around a node that is not part of its fields, so that
+/// [`stringify`](crate::stringifier::stringify) gives back the source of an unchanged tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Raws<'a> {
+  /// the whitespace, comments and stray `;` in front of the node
+  pub before: Cow<'a, str>,
+  /// between the selector or params and the `{` or `;`, between the property and the value
+  /// of a declaration (its `:` included)
+  pub between: Cow<'a, str>,
+  /// the end of a block before its `}` or the end of the root, after the value of a
+  /// declaration up to its `;`
+  pub after: Cow<'a, str>,
+  /// between the name and the params of an at-rule
+  pub after_name: Cow<'a, str>,
+  /// between the value of a nested declaration and its `{`
+  pub after_value: Cow<'a, str>,
+  /// the `.` or `#` in front of the name of a Less mixin call
+  pub identifier: Cow<'a, str>,
+  /// whether the node ends with a `;` of its own
+  pub semicolon: bool,
+}
 
 pub struct Root<'a> {
   pub children: Vec<RuleOrAtRuleOrDecl<'a>>,
   pub(crate) start: usize,
   pub(crate) end: usize,
+  pub raws: Raws<'a>,
+  /// the parsed source, the `inputs` of its [JSON](crate::json)
+  pub(crate) css: Cow<'a, str>,
+  /// whether the source started with a byte-order mark, which is not part of `css`
+  pub has_bom: bool,
 }
 
 pub enum RuleOrAtRuleOrDecl<'a> {
@@ -16,16 +43,45 @@ pub enum RuleOrAtRuleOrDecl<'a> {
   Declaration(Declaration<'a>),
 }
 
-// enum AtRuleOrDeclaration<'a> {
-//   Declaration(Declaration<'a>),
-//   AtRule(AtRule<'a>),
-// }
+impl<'a> RuleOrAtRuleOrDecl<'a> {
+  pub(crate) fn range(&self) -> (usize, usize) {
+    match self {
+      RuleOrAtRuleOrDecl::Rule(rule) => (rule.start, rule.end),
+      RuleOrAtRuleOrDecl::AtRule(at_rule) => (at_rule.start, at_rule.end),
+      RuleOrAtRuleOrDecl::Declaration(decl) => (decl.start, decl.end),
+    }
+  }
+
+  pub fn raws(&self) -> &Raws<'a> {
+    match self {
+      RuleOrAtRuleOrDecl::Rule(rule) => &rule.raws,
+      RuleOrAtRuleOrDecl::AtRule(at_rule) => &at_rule.raws,
+      RuleOrAtRuleOrDecl::Declaration(decl) => &decl.raws,
+    }
+  }
+
+  pub fn raws_mut(&mut self) -> &mut Raws<'a> {
+    match self {
+      RuleOrAtRuleOrDecl::Rule(rule) => &mut rule.raws,
+      RuleOrAtRuleOrDecl::AtRule(at_rule) => &mut at_rule.raws,
+      RuleOrAtRuleOrDecl::Declaration(decl) => &mut decl.raws,
+    }
+  }
+}
 
 pub struct Rule<'a> {
   pub children: Vec<RuleOrAtRuleOrDecl<'a>>,
   pub start: usize,
   pub end: usize,
   pub selector: Cow<'a, str>,
+  pub raws: Raws<'a>,
+}
+
+impl<'a> Rule<'a> {
+  /// The selector list as [`selector::Selector`]s, their ranges are offsets into `selector`.
+  pub fn parsed_selector(&self) -> Vec<selector::Selector<'_>> {
+    selector::parse(&self.selector)
+  }
 }
 
 pub struct Declaration<'a> {
@@ -33,6 +89,17 @@ pub struct Declaration<'a> {
   pub value: Cow<'a, str>,
   pub(crate) start: usize,
   pub(crate) end: usize,
+  pub raws: Raws<'a>,
+  /// an SCSS nested property like `font: bold { family: serif }` has a block
+  pub is_nested: bool,
+  pub children: Vec<RuleOrAtRuleOrDecl<'a>>,
+}
+
+impl<'a> Declaration<'a> {
+  /// The value as [`value::Node`]s, their ranges are offsets into `value`.
+  pub fn parsed_value(&self) -> Vec<value::Node<'_>> {
+    value::parse(&self.value)
+  }
 }
 
 pub struct AtRule<'a> {
@@ -41,49 +108,222 @@ pub struct AtRule<'a> {
   pub(crate) start: usize,
   pub(crate) end: usize,
   pub children: Vec<RuleOrAtRuleOrDecl<'a>>,
+  pub raws: Raws<'a>,
+  /// `@media print {}` has an empty block, `@import "a.css";` none
+  pub has_block: bool,
+  /// a Less variable `@name: params;`
+  pub variable: bool,
+  /// a Less mixin call `.name params;`
+  pub mixin: bool,
+}
+
+impl<'a> AtRule<'a> {
+  /// The params as [`params::Params`] for `@media`, `@import`, `@supports`, `@layer`,
+  /// `@container` and `@keyframes`, `None` for other at-rules or params that don't parse.
+  pub fn parsed_params(&self) -> Option<params::Params<'_>> {
+    params::parse(&self.name, &self.params)
+  }
+}
+
+/// What a block may contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+  Declarations,
+  Rules,
+  /// anything, told apart by looking ahead
+  Statements,
+}
+
+/// What a statement starting with a word turns out to be.
+enum Statement {
+  Declaration,
+  NestedDeclaration,
+  Rule,
+  /// `.mixin();` in Less
+  Mixin,
 }
 
 pub struct Parser<'a> {
-  lexer: Peekable<Lexer<'a>>,
+  lexer: Tokenizer<'a>,
+  /// the token [`Parser::peek`] read from `lexer` but didn't consume
+  peeked: Option<Token>,
   source: &'a str,
   pos: usize,
+  syntax: Syntax,
+  has_bom: bool,
 }
 
 impl<'a> Parser<'a> {
   pub fn new(input: &'a str) -> Self {
+    Self::with_syntax(input, Syntax::Css)
+  }
+
+  /// In [`Syntax::Scss`] declarations and rules may be nested in each other and at the top
+  /// level, nested properties become [`Declaration`]s with children.
+  /// [`Syntax::Sugarss`] is parsed by [`sugarss::parse`](crate::sugarss::parse).
+  /// A leading byte-order mark is skipped, the root records it in [`Root::has_bom`].
+  pub fn with_syntax(input: &'a str, syntax: Syntax) -> Self {
+    let input = Input::new(input, None);
     Self {
-      lexer: Lexer::new(input).peekable(),
-      source: input,
+      lexer: Tokenizer::with_syntax(input.css, false, syntax),
+      peeked: None,
+      source: input.css,
       pos: 0,
+      syntax,
+      has_bom: input.has_bom(),
     }
   }
 
   pub fn parse(mut self) -> Result<Root<'a>> {
+    if self.syntax == Syntax::Sugarss {
+      let has_bom = self.has_bom;
+      return crate::sugarss::parse(self.source).map(|root| Root { has_bom, ..root });
+    }
     // self.parse_element();
     let mut children: Vec<RuleOrAtRuleOrDecl> = vec![];
+    let mut cursor = 0;
     while let Some(syntax) = self.peek() {
-      match syntax {
+      let child = match syntax {
         TokenType::Space => {
           self.bump();
-        }
-        TokenType::AtWord => {
-          children.push(RuleOrAtRuleOrDecl::AtRule(self.parse_at_rule()?));
+          continue;
         }
         TokenType::Comment => {
           self.parse_comment();
+          continue;
         }
-        _ => {
-          children.push(RuleOrAtRuleOrDecl::Rule(self.parse_rule()?));
+        TokenType::Semicolon if self.syntax != Syntax::Css => {
+          self.bump();
+          continue;
         }
+        TokenType::AtWord => RuleOrAtRuleOrDecl::AtRule(self.parse_at_rule()?),
+        _ if self.syntax != Syntax::Css => self.parse_statement()?,
+        _ => RuleOrAtRuleOrDecl::Rule(self.parse_rule()?),
       };
+      self.push_child(&mut children, &mut cursor, child);
     }
     Ok(Root {
       children,
       start: 0,
       end: self.pos,
+      raws: Raws {
+        after: Cow::Borrowed(&self.source[cursor..self.pos]),
+        ..Raws::default()
+      },
+      css: Cow::Borrowed(self.source),
+      has_bom: self.has_bom,
     })
   }
 
+  /// Add `child`, the source between the previous node and it becomes its `raws.before`.
+  fn push_child(
+    &self,
+    children: &mut Vec<RuleOrAtRuleOrDecl<'a>>,
+    cursor: &mut usize,
+    mut child: RuleOrAtRuleOrDecl<'a>,
+  ) {
+    let (start, end) = child.range();
+    child.raws_mut().before = Cow::Borrowed(&self.source[*cursor..start]);
+    *cursor = end;
+    children.push(child);
+  }
+
+  /// A statement in a [`Block::Statements`] which does not start with an at-word.
+  fn parse_statement(&mut self) -> Result<RuleOrAtRuleOrDecl<'a>> {
+    Ok(match self.look_ahead() {
+      Statement::Declaration => RuleOrAtRuleOrDecl::Declaration(self.parse_declaration()?),
+      Statement::NestedDeclaration => {
+        RuleOrAtRuleOrDecl::Declaration(self.parse_nested_declaration()?)
+      }
+      Statement::Rule => RuleOrAtRuleOrDecl::Rule(self.parse_rule()?),
+      Statement::Mixin => RuleOrAtRuleOrDecl::AtRule(self.parse_mixin()?),
+    })
+  }
+
+  /// A statement ending with `;` or `}` is a declaration, one with a block is a rule, unless
+  /// it reads like `prop: value {` in SCSS (the way `postcss-scss` tells nested properties
+  /// apart). In Less, a statement without a `:` starting with `.` or `#` calls a mixin.
+  fn look_ahead(&mut self) -> Statement {
+    let start = match self.peek_token() {
+      Some(token) => token.1,
+      None => return Statement::Declaration,
+    };
+    let checkpoint = self.lexer.checkpoint();
+    let statement = self.scan_statement(start);
+    self.lexer.rewind(checkpoint);
+    statement
+  }
+
+  /// Read the statement at `start` for [`Parser::look_ahead`], from the peeked token up to the
+  /// first `;` outside brackets or the first brace, even inside brackets, so the scan never
+  /// runs past the current block.
+  fn scan_statement(&mut self, start: usize) -> Statement {
+    let source = self.source;
+    let mut depth = 0usize;
+    let mut colon = false;
+    let mut any_colon = false;
+    let mut newline = false;
+    // the first char of the value and whether there is more than whitespace
+    let mut value_start = None;
+    let mut value_blank = true;
+    let peeked = self.peeked;
+    for Token(kind, start, end) in peeked.into_iter().chain(self.lexer.by_ref()) {
+      match kind {
+        TokenType::OpenParentheses | TokenType::OpenSquare => depth += 1,
+        TokenType::CloseParentheses | TokenType::CloseSquare => {
+          depth = depth.saturating_sub(1);
+        }
+        TokenType::Semicolon if depth == 0 => break,
+        TokenType::CloseCurly => break,
+        TokenType::OpenCurly => {
+          let nested = self.syntax == Syntax::Scss
+            && colon
+            && !value_blank
+            && !value_start
+              .is_some_and(|ch: char| matches!(ch, '#' | ':' | '-') || ch.is_ascii_alphabetic());
+          return if nested {
+            Statement::NestedDeclaration
+          } else {
+            Statement::Rule
+          };
+        }
+        TokenType::Colon if depth == 0 => {
+          any_colon = true;
+          if !colon && !newline {
+            colon = true;
+            continue;
+          }
+        }
+        TokenType::Space if !colon && source[start..end].contains('\n') => newline = true,
+        _ => {}
+      }
+      if colon && kind != TokenType::Comment {
+        let text = &source[start..end];
+        value_start = value_start.or_else(|| text.chars().next());
+        value_blank &= text.trim().is_empty();
+      }
+    }
+    if self.syntax == Syntax::Less && !any_colon && source[start..].starts_with(['.', '#']) {
+      Statement::Mixin
+    } else {
+      Statement::Declaration
+    }
+  }
+
+  fn rule_block(&self) -> Block {
+    match self.syntax {
+      Syntax::Css => Block::Declarations,
+      _ => Block::Statements,
+    }
+  }
+
+  fn at_rule_block(&self) -> Block {
+    match self.syntax {
+      Syntax::Css => Block::Rules,
+      _ => Block::Statements,
+    }
+  }
+
   #[inline]
   pub fn parse_comment(&mut self) {
     self.bump();
@@ -95,12 +335,17 @@ impl<'a> Parser<'a> {
     if let Some(kind) = self.peek() {
       match kind {
         TokenType::OpenCurly => {
-          return Ok(Rule {
+          let (children, after) = self.parse_curly_block(self.rule_block())?;
+          Ok(Rule {
             selector: Cow::Borrowed(""),
-            children: self.parse_curly_block(false)?,
+            children,
             start,
             end: self.pos,
-          });
+            raws: Raws {
+              after,
+              ..Raws::default()
+            },
+          })
         }
         _ => {
           self.parse_component()?;
@@ -109,11 +354,18 @@ impl<'a> Parser<'a> {
             match self.peek() {
               Some(kind) => match kind {
                 TokenType::OpenCurly => {
+                  let between = Cow::Borrowed(&self.source[selector_end..self.pos]);
+                  let (children, after) = self.parse_curly_block(self.rule_block())?;
                   return Ok(Rule {
                     selector: Cow::Borrowed(&self.source[start..selector_end]),
-                    children: self.parse_curly_block(false)?,
+                    children,
                     start,
                     end: self.pos,
+                    raws: Raws {
+                      between,
+                      after,
+                      ..Raws::default()
+                    },
                   });
                 }
                 TokenType::Space | TokenType::Comment => {
@@ -252,36 +504,42 @@ impl<'a> Parser<'a> {
     Ok(())
   }
 
-  fn parse_curly_block(&mut self, rule: bool) -> Result<Vec<RuleOrAtRuleOrDecl<'a>>> {
+  /// The children of a block and the source after the last of them, up to the `}`.
+  fn parse_curly_block(
+    &mut self,
+    block: Block,
+  ) -> Result<(Vec<RuleOrAtRuleOrDecl<'a>>, Cow<'a, str>)> {
     use TokenType::*;
     // println!("parse curlyblock");
     let mut ret: Vec<RuleOrAtRuleOrDecl> = vec![];
     self.bump(); // bump {
+    let mut cursor = self.pos;
     self.skip_whitespace_comment();
     loop {
-      match self.peek() {
+      let child = match self.peek() {
         Some(kind) => match kind {
           Semicolon => {
             self.bump();
+            continue;
           }
-          AtWord => ret.push(RuleOrAtRuleOrDecl::AtRule(self.parse_at_rule()?)),
+          AtWord => RuleOrAtRuleOrDecl::AtRule(self.parse_at_rule()?),
           Space | Comment => {
             self.bump();
+            continue;
           }
           CloseCurly => {
+            let after = Cow::Borrowed(&self.source[cursor..self.pos]);
             self.bump();
             // println!("finish close curly");
-            break;
-          }
-          _ => {
-            if rule {
-              // println!("parse rule -->");
-              ret.push(RuleOrAtRuleOrDecl::Rule(self.parse_rule()?));
-            } else {
-              // println!("parse declaration");
-              ret.push(RuleOrAtRuleOrDecl::Declaration(self.parse_declaration()?));
-            }
+            return Ok((ret, after));
           }
+          _ => match block {
+            // println!("parse rule -->");
+            Block::Rules => RuleOrAtRuleOrDecl::Rule(self.parse_rule()?),
+            // println!("parse declaration");
+            Block::Declarations => RuleOrAtRuleOrDecl::Declaration(self.parse_declaration()?),
+            Block::Statements => self.parse_statement()?,
+          },
         },
         None => {
           return Err(PostcssError::ParseError(
@@ -290,12 +548,13 @@ impl<'a> Parser<'a> {
             self.pos,
           ));
         }
-      }
+      };
+      self.push_child(&mut ret, &mut cursor, child);
     }
-    Ok(ret)
   }
 
-  fn parse_declaration(&mut self) -> Result<Declaration<'a>> {
+  /// A declaration up to the end of its value, which ends before `stop`.
+  fn parse_prop_value(&mut self, stop: fn(TokenType) -> bool) -> Result<Declaration<'a>> {
     use TokenType::*;
     match self.peek() {
       Some(Word) => {}
@@ -303,7 +562,7 @@ impl<'a> Parser<'a> {
         return Err(PostcssError::ParseError(
           format!("expected token word, found `{}`", other),
           self.pos,
-          self.lexer.peek().unwrap().2,
+          self.peek_token().unwrap().2,
         ));
       }
       None => {
@@ -325,7 +584,7 @@ impl<'a> Parser<'a> {
         return Err(PostcssError::ParseError(
           format!("expected `:`, found `{}`", other),
           self.pos,
-          self.lexer.peek().unwrap().2,
+          self.peek_token().unwrap().2,
         ));
       }
       None => {
@@ -338,20 +597,20 @@ impl<'a> Parser<'a> {
     }
     self.bump();
     self.skip_whitespace_comment();
-    let mut has_finish = false;
-    let mut value: Cow<'a, str> = Cow::default();
     let value_start = self.pos;
+    let between = Cow::Borrowed(&self.source[prop_end..value_start]);
     let mut value_end = self.pos;
     while let Some(kind) = self.peek() {
       match kind {
-        CloseCurly | Semicolon => {
-          has_finish = true;
-          value = Cow::Borrowed(&self.source[value_start..value_end]);
-          break;
-        }
+        _ if stop(kind) => break,
         Space => {
           self.bump();
         }
+        // an inline comment runs up to the end of the line, after the value it goes to
+        // `raws.after` with the spaces around it
+        Comment if self.source[self.pos..].starts_with("//") => {
+          self.bump();
+        }
         _ => {
           // println!("parse the component");
           self.parse_component()?;
@@ -359,23 +618,41 @@ impl<'a> Parser<'a> {
         }
       }
     }
-    if !has_finish {
-      // value.end = value_end;
-      value = Cow::Borrowed(&self.source[value_start..value_end]);
-    }
-    let end = if matches!(self.peek(), Some(Semicolon)) {
-      self.lexer.peek().unwrap().2
-    } else {
-      value_end
-    };
     Ok(Declaration {
       start: prop_start,
-      end,
+      end: value_end,
       prop,
-      value,
+      value: Cow::Borrowed(&self.source[value_start..value_end]),
+      raws: Raws {
+        between,
+        ..Raws::default()
+      },
+      is_nested: false,
+      children: vec![],
     })
   }
 
+  fn parse_declaration(&mut self) -> Result<Declaration<'a>> {
+    let mut decl =
+      self.parse_prop_value(|kind| matches!(kind, TokenType::CloseCurly | TokenType::Semicolon))?;
+    if let Some(&Token(TokenType::Semicolon, semicolon_start, semicolon_end)) = self.peek_token() {
+      decl.raws.after = Cow::Borrowed(&self.source[decl.end..semicolon_start]);
+      decl.raws.semicolon = true;
+      decl.end = semicolon_end;
+    }
+    Ok(decl)
+  }
+
+  /// `font: bold { family: serif }` in SCSS
+  fn parse_nested_declaration(&mut self) -> Result<Declaration<'a>> {
+    let mut decl = self.parse_prop_value(|kind| kind == TokenType::OpenCurly)?;
+    decl.raws.after_value = Cow::Borrowed(&self.source[decl.end..self.pos]);
+    (decl.children, decl.raws.after) = self.parse_curly_block(Block::Statements)?;
+    decl.is_nested = true;
+    decl.end = self.pos;
+    Ok(decl)
+  }
+
   pub fn parse_at_rule(&mut self) -> Result<AtRule<'a>> {
     // TODO: should parse declaration inside a at_rule
     use TokenType::*;
@@ -385,16 +662,24 @@ impl<'a> Parser<'a> {
     let mut children = vec![];
     let params_start = self.pos;
     let mut params_end = self.pos;
+    let mut between_end = None;
+    let mut after = Cow::default();
+    let mut has_block = false;
+    let mut semicolon = false;
     while let Some(kind) = self.peek() {
       match kind {
         OpenCurly => {
           //   self.finish_node(); finish params
-          children = self.parse_curly_block(true)?;
+          between_end = Some(self.pos);
+          (children, after) = self.parse_curly_block(self.at_rule_block())?;
+          has_block = true;
           break;
         }
         Semicolon => {
           //   self.finish_node();
+          between_end = Some(self.pos);
           self.bump();
+          semicolon = true;
           break;
         }
         CloseCurly => {
@@ -407,12 +692,73 @@ impl<'a> Parser<'a> {
         }
       }
     }
+    let between_end = between_end.unwrap_or(self.pos);
+    // the at-word of a Less variable ends with its `:`
+    let variable = self.syntax == Syntax::Less && self.source[..word_end].ends_with(':');
+    let name_end = if variable { word_end - 1 } else { word_end };
     Ok(AtRule {
       params: Cow::Borrowed(&self.source[params_start..params_end]),
-      name: Cow::Borrowed(&self.source[word_start + 1..word_end]),
+      name: Cow::Borrowed(&self.source[word_start + 1..name_end]),
       start,
       end: self.pos,
       children,
+      raws: Raws {
+        between: Cow::Borrowed(&self.source[params_end..between_end]),
+        after,
+        after_name: Cow::Borrowed(&self.source[name_end..params_start]),
+        semicolon,
+        ..Raws::default()
+      },
+      has_block,
+      variable,
+      mixin: false,
+    })
+  }
+
+  /// A Less mixin call like `.mixin(@a; 1px) !important;`, an at-rule named `mixin` (the
+  /// `.` is its [`identifier`](Raws::identifier)) with the params `(@a; 1px) !important`.
+  fn parse_mixin(&mut self) -> Result<AtRule<'a>> {
+    use TokenType::*;
+    let Token(_, start, name_end) = self.bump();
+    let mut params_start = None;
+    let mut params_end = name_end;
+    while let Some(kind) = self.peek() {
+      match kind {
+        Semicolon | CloseCurly => break,
+        Space | Comment => {
+          self.bump();
+        }
+        _ => {
+          params_start.get_or_insert(self.pos);
+          self.parse_component()?;
+          params_end = self.pos;
+        }
+      }
+    }
+    let params_start = params_start.unwrap_or(name_end);
+    let mut raws = Raws {
+      identifier: Cow::Borrowed(&self.source[start..start + 1]),
+      after_name: Cow::Borrowed(&self.source[name_end..params_start]),
+      ..Raws::default()
+    };
+    let end = match self.peek_token() {
+      Some(&Token(Semicolon, semicolon_start, semicolon_end)) => {
+        raws.between = Cow::Borrowed(&self.source[params_end..semicolon_start]);
+        raws.semicolon = true;
+        semicolon_end
+      }
+      _ => params_end,
+    };
+    Ok(AtRule {
+      params: Cow::Borrowed(&self.source[params_start..params_end]),
+      name: Cow::Borrowed(&self.source[start + 1..name_end]),
+      start,
+      end,
+      children: vec![],
+      raws,
+      has_block: false,
+      variable: false,
+      mixin: true,
     })
   }
 
@@ -425,11 +771,19 @@ impl<'a> Parser<'a> {
 
   #[inline]
   pub fn peek(&mut self) -> Option<TokenType> {
-    self.lexer.peek().map(|token| token.0)
+    self.peek_token().map(|token| token.0)
+  }
+
+  #[inline]
+  fn peek_token(&mut self) -> Option<&Token> {
+    if self.peeked.is_none() {
+      self.peeked = self.lexer.next();
+    }
+    self.peeked.as_ref()
   }
 
   pub fn bump(&mut self) -> Token {
-    let token = self.lexer.next().unwrap();
+    let token = self.peeked.take().or_else(|| self.lexer.next()).unwrap();
     self.pos = token.2;
     token
   }
@@ -0,0 +1,747 @@
+//! A parser for selector lists, like `postcss-selector-parser`.
+//!
+//! ```
+//! use recursive_parser::selector::{self, Node, Specificity};
+//!
+//! let mut selectors = selector::parse("a.title > :is(#main, .b)::before");
+//! assert_eq!(selectors[0].specificity(), Specificity(1, 1, 2));
+//!
+//! selector::walk_mut(&mut selectors, &mut |node| {
+//!   if let Node::Class { name, .. } = node {
+//!     *name = format!("_{}_hash", name).into();
+//!   }
+//!   true
+//! });
+//! assert_eq!(
+//!   selector::stringify(&selectors),
+//!   "a._title_hash > :is(#main, ._b_hash)::before"
+//! );
+//! ```
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::{Add, Range};
+
+/// A complex selector, one item of a selector list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector<'a> {
+  /// whitespace after the `,` or `(` in front of the selector
+  pub before: Cow<'a, str>,
+  pub nodes: Vec<Node<'a>>,
+  /// whitespace in front of the next `,` or `)`
+  pub after: Cow<'a, str>,
+  /// where the selector is in the parsed string, including `before` and `after`
+  pub range: Range<usize>,
+}
+
+/// A part of a [`Selector`]. Names are kept as written, with their escapes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node<'a> {
+  /// `div`, `svg|circle`, or a keyframe selector like `50%`
+  Tag {
+    name: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `.name`
+  Class {
+    name: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `#name`
+  Id {
+    name: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `[attribute op "value" i]`
+  Attribute {
+    attribute: Cow<'a, str>,
+    /// `=`, `~=`, `|=`, `^=`, `$=` or `*=`
+    op: Option<Cow<'a, str>>,
+    /// the value without its quotes
+    value: Option<Cow<'a, str>>,
+    quote: Option<char>,
+    /// the `i` flag
+    insensitive: bool,
+    raws: AttributeRaws<'a>,
+    range: Range<usize>,
+  },
+  /// `:hover`, `::before`, `:not(.a)`, `:nth-child(2n + 1)`
+  Pseudo {
+    /// the name with its colons
+    value: Cow<'a, str>,
+    args: Option<PseudoArgs<'a>>,
+    range: Range<usize>,
+  },
+  /// ` `, `>`, `+`, `~` or `||` between two compound selectors. A descendant combinator's
+  /// value is the whitespace itself.
+  Combinator {
+    value: Cow<'a, str>,
+    before: Cow<'a, str>,
+    after: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `&`
+  Nesting { range: Range<usize> },
+  /// `*` or `ns|*`
+  Universal {
+    value: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `/* comment */`, the value is the text between `/*` and `*/`
+  Comment {
+    value: Cow<'a, str>,
+    range: Range<usize>,
+  },
+}
+
+/// The whitespace and exact flag inside the brackets of an attribute selector.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttributeRaws<'a> {
+  pub after_open: Cow<'a, str>,
+  pub after_attribute: Cow<'a, str>,
+  pub after_op: Cow<'a, str>,
+  pub after_value: Cow<'a, str>,
+  /// `i`, `I`, `s` or `S`
+  pub flag: Option<Cow<'a, str>>,
+  pub before_close: Cow<'a, str>,
+}
+
+/// What is between the parentheses of a functional pseudo-class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PseudoArgs<'a> {
+  /// the selector list of `:not()`, `:is()`, `:where()`, `:has()`, `:global()`, ...
+  Selectors(Vec<Selector<'a>>),
+  /// anything else, e.g. `2n + 1` or `en`
+  Raw(Cow<'a, str>),
+}
+
+/// The `(id, class, type)` specificity of a selector, ordered like the cascade orders it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+impl Add for Specificity {
+  type Output = Specificity;
+
+  fn add(self, other: Specificity) -> Specificity {
+    Specificity(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+  }
+}
+
+impl<'a> Selector<'a> {
+  pub fn specificity(&self) -> Specificity {
+    self
+      .nodes
+      .iter()
+      .map(Node::specificity)
+      .fold(Specificity::default(), Add::add)
+  }
+}
+
+impl<'a> Node<'a> {
+  pub fn range(&self) -> Range<usize> {
+    match self {
+      Node::Tag { range, .. }
+      | Node::Class { range, .. }
+      | Node::Id { range, .. }
+      | Node::Attribute { range, .. }
+      | Node::Pseudo { range, .. }
+      | Node::Combinator { range, .. }
+      | Node::Nesting { range }
+      | Node::Universal { range, .. }
+      | Node::Comment { range, .. } => range.clone(),
+    }
+  }
+
+  /// What the node adds to the specificity of its selector.
+  pub fn specificity(&self) -> Specificity {
+    match self {
+      Node::Id { .. } => Specificity(1, 0, 0),
+      Node::Class { .. } | Node::Attribute { .. } => Specificity(0, 1, 0),
+      Node::Tag { .. } => Specificity(0, 0, 1),
+      Node::Pseudo { value, args, .. } => {
+        let name = value.trim_start_matches(':').to_ascii_lowercase();
+        let args = match args {
+          Some(PseudoArgs::Selectors(selectors)) => selectors
+            .iter()
+            .map(Selector::specificity)
+            .max()
+            .unwrap_or_default(),
+          Some(PseudoArgs::Raw(raw)) if name == "nth-child" || name == "nth-last-child" => {
+            match nth_of_selectors(raw) {
+              Some(selectors) => parse(selectors)
+                .iter()
+                .map(Selector::specificity)
+                .max()
+                .unwrap_or_default(),
+              None => Specificity::default(),
+            }
+          }
+          _ => Specificity::default(),
+        };
+        if value.starts_with("::") || LEGACY_PSEUDO_ELEMENTS.contains(&name.as_str()) {
+          Specificity(0, 0, 1) + args
+        } else {
+          match name.as_str() {
+            "where" => Specificity::default(),
+            "not" | "is" | "has" | "matches" | "any" | "-webkit-any" | "-moz-any" | "global"
+            | "local" => args,
+            _ => Specificity(0, 1, 0) + args,
+          }
+        }
+      }
+      Node::Combinator { .. }
+      | Node::Nesting { .. }
+      | Node::Universal { .. }
+      | Node::Comment { .. } => Specificity::default(),
+    }
+  }
+}
+
+/// the selector list `S` of `An+B of S`
+fn nth_of_selectors(args: &str) -> Option<&str> {
+  let bytes = args.as_bytes();
+  // `An+B` has no `o`, so the first `of` between whitespace is the keyword
+  (1..bytes.len().saturating_sub(2))
+    .find(|&i| {
+      bytes[i - 1].is_ascii_whitespace()
+        && bytes[i..i + 2].eq_ignore_ascii_case(b"of")
+        && bytes[i + 2].is_ascii_whitespace()
+    })
+    .map(|i| &args[i + 2..])
+}
+
+/// `:before` and friends, which are pseudo-elements written with one colon
+const LEGACY_PSEUDO_ELEMENTS: [&str; 4] = ["before", "after", "first-line", "first-letter"];
+
+/// pseudo-classes whose argument is a selector list
+const SELECTOR_PSEUDOS: [&str; 15] = [
+  "not",
+  "is",
+  "where",
+  "has",
+  "matches",
+  "any",
+  "-webkit-any",
+  "-moz-any",
+  "host",
+  "host-context",
+  "slotted",
+  "global",
+  "local",
+  "current",
+  "cue",
+];
+
+impl<'a> fmt::Display for Selector<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.before)?;
+    for node in &self.nodes {
+      write!(f, "{}", node)?;
+    }
+    f.write_str(&self.after)
+  }
+}
+
+impl<'a> fmt::Display for Node<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Node::Tag { name, .. } => f.write_str(name),
+      Node::Class { name, .. } => write!(f, ".{}", name),
+      Node::Id { name, .. } => write!(f, "#{}", name),
+      Node::Attribute {
+        attribute,
+        op,
+        value,
+        quote,
+        insensitive,
+        raws,
+        ..
+      } => {
+        write!(
+          f,
+          "[{}{}{}",
+          raws.after_open, attribute, raws.after_attribute
+        )?;
+        if let (Some(op), Some(value)) = (op, value) {
+          write!(f, "{}{}", op, raws.after_op)?;
+          match quote {
+            Some(quote) => write!(f, "{}{}{}", quote, value, quote)?,
+            None => f.write_str(value)?,
+          }
+          f.write_str(&raws.after_value)?;
+        }
+        let flag = match &raws.flag {
+          Some(flag) if flag.eq_ignore_ascii_case("i") == *insensitive => Some(flag.as_ref()),
+          _ if *insensitive => Some("i"),
+          _ => None,
+        };
+        if let Some(flag) = flag {
+          // a flag that wasn't there needs a space after an unquoted value
+          let spaced = if value.is_some() {
+            !raws.after_value.is_empty() || quote.is_some()
+          } else {
+            !raws.after_attribute.is_empty()
+          };
+          if raws.flag.is_none() && !spaced {
+            f.write_str(" ")?;
+          }
+          f.write_str(flag)?;
+        }
+        write!(f, "{}]", raws.before_close)
+      }
+      Node::Pseudo { value, args, .. } => {
+        f.write_str(value)?;
+        match args {
+          Some(PseudoArgs::Selectors(selectors)) => write!(f, "({})", stringify(selectors)),
+          Some(PseudoArgs::Raw(raw)) => write!(f, "({})", raw),
+          None => Ok(()),
+        }
+      }
+      Node::Combinator {
+        value,
+        before,
+        after,
+        ..
+      } => write!(f, "{}{}{}", before, value, after),
+      Node::Nesting { .. } => f.write_str("&"),
+      Node::Universal { value, .. } => f.write_str(value),
+      Node::Comment { value, .. } => write!(f, "/*{}*/", value),
+    }
+  }
+}
+
+/// Parse a selector list.
+pub fn parse(input: &str) -> Vec<Selector<'_>> {
+  let mut parser = SelectorParser {
+    input,
+    bytes: input.as_bytes(),
+    pos: 0,
+  };
+  parser.selectors(false)
+}
+
+/// Print a selector list, the selectors are joined with `,`.
+pub fn stringify(selectors: &[Selector<'_>]) -> String {
+  selectors
+    .iter()
+    .map(|selector| selector.to_string())
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Call `f` for every node in depth-first order, including the selectors in the arguments of
+/// pseudo-classes. When `f` returns `false` for a pseudo-class, its arguments are skipped.
+pub fn walk<'a, F: FnMut(&Node<'a>) -> bool>(selectors: &[Selector<'a>], f: &mut F) {
+  for selector in selectors {
+    for node in &selector.nodes {
+      if f(node) {
+        if let Node::Pseudo {
+          args: Some(PseudoArgs::Selectors(selectors)),
+          ..
+        } = node
+        {
+          walk(selectors, f);
+        }
+      }
+    }
+  }
+}
+
+/// [`walk`] with mutable nodes.
+pub fn walk_mut<'a, F: FnMut(&mut Node<'a>) -> bool>(selectors: &mut [Selector<'a>], f: &mut F) {
+  for selector in selectors {
+    for node in &mut selector.nodes {
+      if f(node) {
+        if let Node::Pseudo {
+          args: Some(PseudoArgs::Selectors(selectors)),
+          ..
+        } = node
+        {
+          walk_mut(selectors, f);
+        }
+      }
+    }
+  }
+}
+
+struct SelectorParser<'a> {
+  input: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> SelectorParser<'a> {
+  fn peek(&self) -> Option<u8> {
+    self.bytes.get(self.pos).copied()
+  }
+
+  fn peek_at(&self, offset: usize) -> Option<u8> {
+    self.bytes.get(self.pos + offset).copied()
+  }
+
+  fn slice(&self, start: usize) -> Cow<'a, str> {
+    Cow::Borrowed(&self.input[start..self.pos])
+  }
+
+  fn spaces(&mut self) -> Cow<'a, str> {
+    let start = self.pos;
+    while self.peek().is_some_and(is_space) {
+      self.pos += 1;
+    }
+    self.slice(start)
+  }
+
+  /// Parse selectors separated by `,` up to the end or, in a pseudo-class, up to its `)`.
+  fn selectors(&mut self, nested: bool) -> Vec<Selector<'a>> {
+    let mut selectors = vec![self.selector(nested)];
+    while self.peek() == Some(b',') {
+      self.pos += 1;
+      selectors.push(self.selector(nested));
+    }
+    selectors
+  }
+
+  fn selector(&mut self, nested: bool) -> Selector<'a> {
+    let start = self.pos;
+    let before = self.spaces();
+    let mut nodes = vec![];
+    let mut after = Cow::Borrowed("");
+    while let Some(b) = self.peek() {
+      let node_start = self.pos;
+      let node = match b {
+        b',' => break,
+        b')' if nested => break,
+        _ if is_space(b) => {
+          let spaces = self.spaces();
+          match self.peek() {
+            None | Some(b',') => {
+              after = spaces;
+              break;
+            }
+            Some(b')') if nested => {
+              after = spaces;
+              break;
+            }
+            Some(b'>' | b'+' | b'~') => self.combinator(node_start, spaces),
+            Some(b'|') if self.peek_at(1) == Some(b'|') => self.combinator(node_start, spaces),
+            _ => Node::Combinator {
+              value: spaces,
+              before: Cow::Borrowed(""),
+              after: Cow::Borrowed(""),
+              range: node_start..self.pos,
+            },
+          }
+        }
+        b'>' | b'+' | b'~' => self.combinator(node_start, Cow::Borrowed("")),
+        b'|' if self.peek_at(1) == Some(b'|') => self.combinator(node_start, Cow::Borrowed("")),
+        b'/' if self.peek_at(1) == Some(b'*') => self.comment(),
+        b'.' => {
+          self.pos += 1;
+          let name = self.ident();
+          Node::Class {
+            name,
+            range: node_start..self.pos,
+          }
+        }
+        b'#' => {
+          self.pos += 1;
+          let name = self.ident();
+          Node::Id {
+            name,
+            range: node_start..self.pos,
+          }
+        }
+        b'[' => self.attribute(),
+        b':' => self.pseudo(),
+        b'&' => {
+          self.pos += 1;
+          Node::Nesting {
+            range: node_start..self.pos,
+          }
+        }
+        _ => self.tag(),
+      };
+      nodes.push(node);
+    }
+    Selector {
+      before,
+      nodes,
+      after,
+      range: start..self.pos,
+    }
+  }
+
+  fn combinator(&mut self, start: usize, before: Cow<'a, str>) -> Node<'a> {
+    let value_start = self.pos;
+    self.pos += if self.peek() == Some(b'|') { 2 } else { 1 };
+    let value = self.slice(value_start);
+    let after = self.spaces();
+    Node::Combinator {
+      value,
+      before,
+      after,
+      range: start..self.pos,
+    }
+  }
+
+  fn comment(&mut self) -> Node<'a> {
+    let start = self.pos;
+    let end = memchr::memmem::find(&self.bytes[start + 2..], b"*/")
+      .map_or(self.bytes.len(), |i| start + 2 + i);
+    self.pos = (end + 2).min(self.bytes.len());
+    Node::Comment {
+      value: Cow::Borrowed(&self.input[start + 2..end]),
+      range: start..self.pos,
+    }
+  }
+
+  /// a name up to the next delimiter, with its escapes
+  fn ident(&mut self) -> Cow<'a, str> {
+    let start = self.pos;
+    while let Some(b) = self.peek() {
+      if b == b'\\' {
+        self.escape();
+      } else if is_delimiter(b) {
+        break;
+      } else {
+        self.pos += 1;
+      }
+    }
+    self.slice(start)
+  }
+
+  /// a type or universal selector, with its namespace prefix
+  fn tag(&mut self) -> Node<'a> {
+    let start = self.pos;
+    let mut universal;
+    loop {
+      if self.peek() == Some(b'*') {
+        self.pos += 1;
+        universal = true;
+      } else {
+        universal = false;
+        self.ident();
+      }
+      if self.peek() == Some(b'|') && self.peek_at(1) != Some(b'|') {
+        self.pos += 1;
+      } else {
+        break;
+      }
+    }
+    if self.pos == start {
+      // a character that can't start anything, keep it to make progress
+      self.pos += self.char_len(self.pos);
+    }
+    let value = self.slice(start);
+    if universal {
+      Node::Universal {
+        value,
+        range: start..self.pos,
+      }
+    } else {
+      Node::Tag {
+        name: value,
+        range: start..self.pos,
+      }
+    }
+  }
+
+  fn attribute(&mut self) -> Node<'a> {
+    let start = self.pos;
+    self.pos += 1;
+    let mut raws = AttributeRaws {
+      after_open: self.spaces(),
+      ..Default::default()
+    };
+    let attribute_start = self.pos;
+    while let Some(b) = self.peek() {
+      let op = matches!(b, b'~' | b'|' | b'^' | b'$' | b'*') && self.peek_at(1) == Some(b'=');
+      if b == b'\\' {
+        self.escape();
+      } else if op || is_space(b) || b == b'=' || b == b']' {
+        break;
+      } else {
+        self.pos += 1;
+      }
+    }
+    let attribute = self.slice(attribute_start);
+    raws.after_attribute = self.spaces();
+
+    let mut op = None;
+    let mut value = None;
+    let mut quote = None;
+    let op_start = self.pos;
+    match self.peek() {
+      Some(b'=') => self.pos += 1,
+      Some(b'~' | b'|' | b'^' | b'$' | b'*') if self.peek_at(1) == Some(b'=') => self.pos += 2,
+      _ => {}
+    }
+    if self.pos > op_start {
+      op = Some(self.slice(op_start));
+      raws.after_op = self.spaces();
+      match self.peek() {
+        Some(q @ (b'"' | b'\'')) => {
+          self.pos += 1;
+          let value_start = self.pos;
+          while let Some(b) = self.peek() {
+            if b == b'\\' {
+              self.pos += 1 + self.char_len(self.pos + 1);
+            } else if b == q {
+              break;
+            } else {
+              self.pos += 1;
+            }
+          }
+          value = Some(self.slice(value_start.min(self.pos)));
+          quote = Some(q as char);
+          if self.peek() == Some(q) {
+            self.pos += 1;
+          }
+        }
+        _ => {
+          let value_start = self.pos;
+          while let Some(b) = self.peek().filter(|&b| !is_space(b) && b != b']') {
+            if b == b'\\' {
+              self.escape();
+            } else {
+              self.pos += 1;
+            }
+          }
+          value = Some(self.slice(value_start));
+        }
+      }
+      raws.after_value = self.spaces();
+    }
+
+    let mut insensitive = false;
+    if let Some(b'i' | b'I' | b's' | b'S') = self.peek() {
+      if matches!(self.peek_at(1), None | Some(b']')) || self.peek_at(1).is_some_and(is_space) {
+        insensitive = matches!(self.peek(), Some(b'i' | b'I'));
+        let flag_start = self.pos;
+        self.pos += 1;
+        raws.flag = Some(self.slice(flag_start));
+        raws.before_close = self.spaces();
+      }
+    }
+    // anything else up to the `]` is kept, so the selector still prints as it was written
+    let rest = self.pos;
+    while self.peek().is_some_and(|b| b != b']') {
+      self.pos += 1;
+    }
+    if self.pos > rest {
+      raws.before_close = Cow::Owned(format!(
+        "{}{}",
+        raws.before_close,
+        &self.input[rest..self.pos]
+      ));
+    }
+    if self.peek() == Some(b']') {
+      self.pos += 1;
+    }
+    Node::Attribute {
+      attribute,
+      op,
+      value,
+      quote,
+      insensitive,
+      raws,
+      range: start..self.pos,
+    }
+  }
+
+  fn pseudo(&mut self) -> Node<'a> {
+    let start = self.pos;
+    self.pos += if self.peek_at(1) == Some(b':') { 2 } else { 1 };
+    self.ident();
+    let value = self.slice(start);
+    let mut args = None;
+    if self.peek() == Some(b'(') {
+      self.pos += 1;
+      let name = value.trim_start_matches(':').to_ascii_lowercase();
+      if SELECTOR_PSEUDOS.contains(&name.as_str()) {
+        args = Some(PseudoArgs::Selectors(self.selectors(true)));
+      } else {
+        let args_start = self.pos;
+        let mut depth = 0usize;
+        while let Some(b) = self.peek() {
+          match b {
+            b'\\' => self.pos += self.char_len(self.pos + 1),
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            _ => {}
+          }
+          self.pos += 1;
+        }
+        let end = self.pos.min(self.bytes.len());
+        args = Some(PseudoArgs::Raw(Cow::Borrowed(&self.input[args_start..end])));
+        self.pos = end;
+      }
+      if self.peek() == Some(b')') {
+        self.pos += 1;
+      }
+    }
+    Node::Pseudo {
+      value,
+      args,
+      range: start..self.pos,
+    }
+  }
+
+  /// the length of the character at `pos`, 0 at the end of the input
+  /// consume the escape at the current `\\`: up to 6 hex digits and a whitespace ending them,
+  /// or any other character
+  fn escape(&mut self) {
+    self.pos += 1;
+    let hex = self.bytes[self.pos..]
+      .iter()
+      .take(6)
+      .take_while(|b| b.is_ascii_hexdigit())
+      .count();
+    if hex == 0 {
+      self.pos += self.char_len(self.pos);
+      return;
+    }
+    self.pos += hex;
+    if self.input[self.pos..].starts_with("\r\n") {
+      self.pos += 2;
+    } else if self.peek().is_some_and(is_space) {
+      self.pos += 1;
+    }
+  }
+
+  fn char_len(&self, pos: usize) -> usize {
+    self
+      .input
+      .get(pos..)
+      .and_then(|rest| rest.chars().next())
+      .map_or(0, char::len_utf8)
+  }
+}
+
+fn is_space(b: u8) -> bool {
+  matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')
+}
+
+fn is_delimiter(b: u8) -> bool {
+  is_space(b)
+    || matches!(
+      b,
+      b'.'
+        | b'#'
+        | b'['
+        | b']'
+        | b':'
+        | b'('
+        | b')'
+        | b','
+        | b'>'
+        | b'+'
+        | b'~'
+        | b'*'
+        | b'&'
+        | b'|'
+        | b'/'
+        | b'"'
+        | b'\''
+    )
+}
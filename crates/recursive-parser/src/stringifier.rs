@@ -0,0 +1,120 @@
+use crate::parser::{self, RuleOrAtRuleOrDecl};
+use crate::visitor::Visit;
+use std::fmt::{Result, Write};
+
+/// Writes a tree back as source text, with the [`Raws`](parser::Raws) of each node around its
+/// fields: an unchanged tree gives back exactly what was parsed.
+pub struct Stringifier<W: Write> {
+  writer: W,
+  bom: bool,
+  /// whether the node being written has a sibling after it, which a `;` has to separate
+  /// it from even if it was parsed without one, e.g. from SugarSS
+  semicolon: bool,
+}
+
+impl<W: Write> Stringifier<W> {
+  pub fn new(writer: W) -> Self {
+    Self {
+      writer,
+      bom: false,
+      semicolon: false,
+    }
+  }
+
+  /// Write the byte-order mark of a root that was parsed with one, see [`parser::Root::has_bom`].
+  pub fn with_bom(mut self) -> Self {
+    self.bom = true;
+    self
+  }
+
+  pub fn result(self) -> W {
+    self.writer
+  }
+
+  fn visit_children<'a>(&mut self, children: &[RuleOrAtRuleOrDecl<'a>]) -> Result {
+    for (i, child) in children.iter().enumerate() {
+      self.semicolon = i + 1 < children.len();
+      match child {
+        RuleOrAtRuleOrDecl::Rule(rule) => self.visit_rule(rule)?,
+        RuleOrAtRuleOrDecl::AtRule(at_rule) => self.visit_at_rule(at_rule)?,
+        RuleOrAtRuleOrDecl::Declaration(decl) => self.visit_declaration(decl)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl<'a, W: Write> Visit<'a, Result> for Stringifier<W> {
+  fn visit_root(&mut self, root: &parser::Root<'a>) -> Result {
+    if self.bom && root.has_bom {
+      self.writer.write_char('\u{feff}')?;
+    }
+    self.visit_children(&root.children)?;
+    self.writer.write_str(&root.raws.after)
+  }
+
+  fn visit_rule(&mut self, rule: &parser::Rule<'a>) -> Result {
+    let raws = &rule.raws;
+    write!(
+      self.writer,
+      "{}{}{}{{",
+      raws.before, rule.selector, raws.between
+    )?;
+    self.visit_children(&rule.children)?;
+    write!(self.writer, "{}}}", raws.after)
+  }
+
+  fn visit_at_rule(&mut self, at_rule: &parser::AtRule<'a>) -> Result {
+    let raws = &at_rule.raws;
+    let semicolon = raws.semicolon || self.semicolon;
+    let identifier = if at_rule.mixin { &raws.identifier } else { "@" };
+    write!(
+      self.writer,
+      "{}{}{}{}{}{}",
+      raws.before, identifier, at_rule.name, raws.after_name, at_rule.params, raws.between
+    )?;
+    if at_rule.has_block {
+      self.writer.write_char('{')?;
+      self.visit_children(&at_rule.children)?;
+      write!(self.writer, "{}}}", raws.after)?;
+    } else if semicolon {
+      self.writer.write_char(';')?;
+    }
+    Ok(())
+  }
+
+  fn visit_declaration(&mut self, decl: &parser::Declaration<'a>) -> Result {
+    let raws = &decl.raws;
+    let semicolon = raws.semicolon || self.semicolon;
+    write!(
+      self.writer,
+      "{}{}{}{}",
+      raws.before, decl.prop, raws.between, decl.value
+    )?;
+    if decl.is_nested {
+      write!(self.writer, "{}{{", raws.after_value)?;
+      self.visit_children(&decl.children)?;
+      write!(self.writer, "{}}}", raws.after)
+    } else {
+      self.writer.write_str(&raws.after)?;
+      if semicolon {
+        self.writer.write_char(';')?;
+      }
+      Ok(())
+    }
+  }
+}
+
+/// the source text of `root`
+pub fn stringify(root: &parser::Root) -> String {
+  let mut stringifier = Stringifier::new(String::with_capacity(root.end));
+  stringifier.visit_root(root).unwrap();
+  stringifier.result()
+}
+
+/// the source text of `root`, with its byte-order mark if it had one
+pub fn stringify_with_bom(root: &parser::Root) -> String {
+  let mut stringifier = Stringifier::new(String::with_capacity(root.end + 3)).with_bom();
+  stringifier.visit_root(root).unwrap();
+  stringifier.result()
+}
@@ -0,0 +1,425 @@
+//! SugarSS, the indentation based syntax:
+//!
+//! ```sass
+//! a,
+//! b
+//!   color: red
+//!   &:hover
+//!     background:
+//!       url(a.png)
+//!       no-repeat
+//! ```
+//!
+//! A line is a declaration when it has a `:` followed by whitespace or the end of the line,
+//! more indented lines below it continue its value. Other lines are rules, whose selector goes
+//! on while a line ends with `,`, and at-rules; more indented lines below them are children.
+//! The tree is the one [`Parser`](crate::parser::Parser) builds for CSS, the raws hold the
+//! newlines and indentation, so [`stringify`] gives back the source.
+//!
+//! Like sugarss, a file is indented with either tabs or spaces, siblings have the same
+//! indentation and a declaration can't end with a `;`.
+use crate::error::{PostcssError, Result};
+use crate::parser::{AtRule, Declaration, Raws, Root, Rule, RuleOrAtRuleOrDecl};
+use crate::visitor::Visit;
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::ops::Range;
+use tokenizer::input::Input;
+use tokenizer::{Syntax, Token, TokenType, Tokenizer};
+
+/// A line holding more than whitespace and comments.
+struct Line {
+  /// the length of the indentation
+  indent: usize,
+  /// where the indentation starts
+  indent_start: usize,
+  /// the tokens of the line, from the first one after the indentation
+  tokens: Range<usize>,
+  /// the end of the last token
+  end: usize,
+  /// the end of the last token which is not a comment
+  content_end: usize,
+}
+
+struct Parser<'a> {
+  source: &'a str,
+  tokens: Vec<Token>,
+  lines: Vec<Line>,
+  next: usize,
+}
+
+pub fn parse(source: &str) -> Result<Root<'_>> {
+  let input = Input::new(source, None);
+  let source = input.css;
+  let tokens: Vec<Token> = Tokenizer::with_syntax(source, false, Syntax::Sugarss).collect();
+  let lines = split_lines(source, &tokens);
+  let mut indent_char = None;
+  for line in &lines {
+    let indent = &source[line.indent_start..line.indent_start + line.indent];
+    if indent.bytes().any(|b| *indent_char.get_or_insert(b) != b) {
+      return Err(PostcssError::ParseError(
+        "mixed tabs and spaces are not allowed".to_string(),
+        line.indent_start,
+        line.indent_start + line.indent,
+      ));
+    }
+  }
+  let mut parser = Parser {
+    source,
+    tokens,
+    lines,
+    next: 0,
+  };
+  let (children, cursor) = parser.parse_block(None, 0)?;
+  Ok(Root {
+    children,
+    start: 0,
+    end: source.len(),
+    raws: Raws {
+      after: Cow::Borrowed(&source[cursor..]),
+      ..Raws::default()
+    },
+    css: Cow::Borrowed(source),
+    has_bom: input.has_bom(),
+  })
+}
+
+/// Split at the newlines outside of brackets, the lines without content are dropped.
+fn split_lines(source: &str, tokens: &[Token]) -> Vec<Line> {
+  let mut lines = vec![];
+  let mut depth = 0usize;
+  let mut indent = 0;
+  let mut indent_start = 0;
+  let mut first = None;
+  let mut end = 0;
+  let mut content_end = None;
+  for (i, &Token(kind, start, token_end)) in tokens.iter().enumerate() {
+    let text = &source[start..token_end];
+    match kind {
+      TokenType::Space if depth == 0 && (text.contains('\n') || start == 0) => {
+        if let (Some(first), Some(content_end)) = (first, content_end) {
+          lines.push(Line {
+            indent,
+            indent_start,
+            tokens: first..i,
+            end,
+            content_end,
+          });
+        }
+        indent = text.len() - text.rfind('\n').map_or(0, |newline| newline + 1);
+        indent_start = token_end - indent;
+        first = None;
+        content_end = None;
+        continue;
+      }
+      TokenType::Space => continue,
+      TokenType::OpenParentheses | TokenType::OpenSquare => depth += 1,
+      TokenType::CloseParentheses | TokenType::CloseSquare => depth = depth.saturating_sub(1),
+      _ => {}
+    }
+    first.get_or_insert(i);
+    end = token_end;
+    if kind != TokenType::Comment {
+      content_end = Some(token_end);
+    }
+  }
+  if let (Some(first), Some(content_end)) = (first, content_end) {
+    lines.push(Line {
+      indent,
+      indent_start,
+      tokens: first..tokens.len(),
+      end,
+      content_end,
+    });
+  }
+  lines
+}
+
+impl<'a> Parser<'a> {
+  /// The nodes of the lines indented more than `parent`, and where the last of them ends.
+  fn parse_block(
+    &mut self,
+    parent: Option<usize>,
+    mut cursor: usize,
+  ) -> Result<(Vec<RuleOrAtRuleOrDecl<'a>>, usize)> {
+    let mut children = vec![];
+    let mut level = None;
+    while let Some(line) = self.lines.get(self.next) {
+      if parent.is_some_and(|parent| line.indent <= parent) {
+        break;
+      }
+      let level = *level.get_or_insert(line.indent);
+      if line.indent != level {
+        return Err(PostcssError::ParseError(
+          format!(
+            "expected an indentation of {}, found {}",
+            level, line.indent
+          ),
+          line.indent_start,
+          line.indent_start + line.indent,
+        ));
+      }
+      let start = self.tokens[line.tokens.start].1;
+      let before = Cow::Borrowed(&self.source[cursor..start]);
+      let mut child = match self.tokens[line.tokens.start].0 {
+        TokenType::AtWord => RuleOrAtRuleOrDecl::AtRule(self.parse_at_rule()?),
+        _ if self.declaration_colon(line).is_some() => {
+          RuleOrAtRuleOrDecl::Declaration(self.parse_declaration()?)
+        }
+        _ => RuleOrAtRuleOrDecl::Rule(self.parse_rule()?),
+      };
+      cursor = match &child {
+        RuleOrAtRuleOrDecl::Rule(rule) => rule.end,
+        RuleOrAtRuleOrDecl::AtRule(at_rule) => at_rule.end,
+        RuleOrAtRuleOrDecl::Declaration(decl) => decl.end,
+      };
+      child.raws_mut().before = before;
+      children.push(child);
+    }
+    Ok((children, cursor))
+  }
+
+  /// the index of the `:` of a declaration, followed by whitespace or the end of the line
+  fn declaration_colon(&self, line: &Line) -> Option<usize> {
+    let mut depth = 0usize;
+    for i in line.tokens.clone() {
+      match self.tokens[i].0 {
+        TokenType::OpenParentheses | TokenType::OpenSquare => depth += 1,
+        TokenType::CloseParentheses | TokenType::CloseSquare => depth = depth.saturating_sub(1),
+        TokenType::Colon if depth == 0 => {
+          let next = self.tokens.get(i + 1).map(|token| token.0);
+          if i + 1 == line.tokens.end || matches!(next, Some(TokenType::Space) | None) {
+            return Some(i);
+          }
+        }
+        _ => {}
+      }
+    }
+    None
+  }
+
+  /// the first token after `from` on the line which is not whitespace or a comment
+  fn first_content(&self, line: &Line, from: usize) -> Option<usize> {
+    (from..line.tokens.end)
+      .find(|&i| !matches!(self.tokens[i].0, TokenType::Space | TokenType::Comment))
+  }
+
+  fn parse_rule(&mut self) -> Result<Rule<'a>> {
+    let line = &self.lines[self.next];
+    let indent = line.indent;
+    let start = self.tokens[line.tokens.start].1;
+    let mut selector_end = line.content_end;
+    let mut header_end = line.end;
+    self.next += 1;
+    // a selector ending with `,` goes on on the next line
+    while self.source[..selector_end].ends_with(',') {
+      match self.lines.get(self.next) {
+        Some(line) => {
+          selector_end = line.content_end;
+          header_end = line.end;
+          self.next += 1;
+        }
+        None => break,
+      }
+    }
+    let (children, end) = self.parse_block(Some(indent), header_end)?;
+    Ok(Rule {
+      children,
+      start,
+      end,
+      selector: Cow::Borrowed(&self.source[start..selector_end]),
+      raws: Raws {
+        between: Cow::Borrowed(&self.source[selector_end..header_end]),
+        ..Raws::default()
+      },
+    })
+  }
+
+  fn parse_at_rule(&mut self) -> Result<AtRule<'a>> {
+    let line = &self.lines[self.next];
+    let indent = line.indent;
+    let Token(_, start, name_end) = self.tokens[line.tokens.start];
+    let params_start = self
+      .first_content(line, line.tokens.start + 1)
+      .map_or(name_end, |i| self.tokens[i].1);
+    let params_end = line.content_end.max(params_start);
+    let header_end = line.end;
+    self.next += 1;
+    let (children, end) = self.parse_block(Some(indent), header_end)?;
+    Ok(AtRule {
+      params: Cow::Borrowed(&self.source[params_start..params_end]),
+      name: Cow::Borrowed(&self.source[start + 1..name_end]),
+      start,
+      end,
+      has_block: !children.is_empty(),
+      children,
+      raws: Raws {
+        after_name: Cow::Borrowed(&self.source[name_end..params_start]),
+        between: Cow::Borrowed(&self.source[params_end..header_end]),
+        ..Raws::default()
+      },
+      variable: false,
+      mixin: false,
+    })
+  }
+
+  fn parse_declaration(&mut self) -> Result<Declaration<'a>> {
+    let line = &self.lines[self.next];
+    let indent = line.indent;
+    let colon = self.declaration_colon(line).unwrap();
+    let Token(prop_kind, start, prop_end) = self.tokens[line.tokens.start];
+    if prop_kind != TokenType::Word {
+      return Err(PostcssError::ParseError(
+        format!("expected token word, found `{}`", prop_kind),
+        start,
+        prop_end,
+      ));
+    }
+    let colon_end = self.tokens[colon].2;
+    let mut value_start = self
+      .first_content(line, colon + 1)
+      .map(|i| self.tokens[i].1);
+    let mut value_end = line.content_end.max(colon_end);
+    let mut end = line.end;
+    let mut tokens = line.tokens.clone();
+    self.next += 1;
+    // more indented lines go on with the value
+    while let Some(line) = self.lines.get(self.next) {
+      if line.indent <= indent {
+        break;
+      }
+      value_start.get_or_insert(self.tokens[line.tokens.start].1);
+      value_end = line.content_end;
+      end = line.end;
+      tokens.end = line.tokens.end;
+      self.next += 1;
+    }
+    if let Some(&Token(_, semicolon, _)) = self.tokens[tokens]
+      .iter()
+      .find(|token| token.0 == TokenType::Semicolon)
+    {
+      return Err(PostcssError::ParseError(
+        "unnecessary semicolon".to_string(),
+        semicolon,
+        semicolon + 1,
+      ));
+    }
+    let value_start = value_start.unwrap_or(value_end);
+    Ok(Declaration {
+      prop: Cow::Borrowed(&self.source[start..prop_end]),
+      value: Cow::Borrowed(&self.source[value_start..value_end]),
+      start,
+      end,
+      raws: Raws {
+        between: Cow::Borrowed(&self.source[prop_end..value_start]),
+        after: Cow::Borrowed(&self.source[value_end..end]),
+        ..Raws::default()
+      },
+      is_nested: false,
+      children: vec![],
+    })
+  }
+}
+
+/// Writes a tree as SugarSS. The raws of a tree parsed from SugarSS are kept, nodes from other
+/// syntaxes go on a line of their own, indented by two spaces per level, and lose their braces
+/// and semicolons.
+pub struct Stringifier<W: Write> {
+  writer: W,
+  depth: usize,
+  first: bool,
+}
+
+impl<W: Write> Stringifier<W> {
+  pub fn new(writer: W) -> Self {
+    Self {
+      writer,
+      depth: 0,
+      first: true,
+    }
+  }
+
+  pub fn result(self) -> W {
+    self.writer
+  }
+
+  fn before(&mut self, raws: &Raws) -> fmt::Result {
+    if raws.before.contains('\n') || (self.first && self.depth == 0) {
+      self.writer.write_str(&raws.before)?;
+    } else {
+      write!(self.writer, "\n{}", "  ".repeat(self.depth))?;
+    }
+    self.first = false;
+    Ok(())
+  }
+
+  fn visit_children<'a>(&mut self, children: &[RuleOrAtRuleOrDecl<'a>]) -> fmt::Result {
+    self.depth += 1;
+    for child in children {
+      self.visit_child(child)?;
+    }
+    self.depth -= 1;
+    Ok(())
+  }
+
+  fn visit_child<'a>(&mut self, child: &RuleOrAtRuleOrDecl<'a>) -> fmt::Result {
+    match child {
+      RuleOrAtRuleOrDecl::Rule(rule) => self.visit_rule(rule),
+      RuleOrAtRuleOrDecl::AtRule(at_rule) => self.visit_at_rule(at_rule),
+      RuleOrAtRuleOrDecl::Declaration(decl) => self.visit_declaration(decl),
+    }
+  }
+}
+
+impl<'a, W: Write> Visit<'a, fmt::Result> for Stringifier<W> {
+  fn visit_root(&mut self, root: &Root<'a>) -> fmt::Result {
+    for child in &root.children {
+      self.visit_child(child)?;
+    }
+    self.writer.write_str(&root.raws.after)
+  }
+
+  fn visit_rule(&mut self, rule: &Rule<'a>) -> fmt::Result {
+    self.before(&rule.raws)?;
+    write!(
+      self.writer,
+      "{}{}",
+      rule.selector,
+      rule.raws.between.trim_end()
+    )?;
+    self.visit_children(&rule.children)
+  }
+
+  fn visit_at_rule(&mut self, at_rule: &AtRule<'a>) -> fmt::Result {
+    let raws = &at_rule.raws;
+    self.before(raws)?;
+    write!(
+      self.writer,
+      "@{}{}{}{}",
+      at_rule.name,
+      raws.after_name,
+      at_rule.params,
+      raws.between.trim_end()
+    )?;
+    self.visit_children(&at_rule.children)
+  }
+
+  fn visit_declaration(&mut self, decl: &Declaration<'a>) -> fmt::Result {
+    let raws = &decl.raws;
+    self.before(raws)?;
+    write!(self.writer, "{}{}", decl.prop, raws.between)?;
+    // a `:` without whitespace after it would read as a selector
+    if !raws.between.ends_with(char::is_whitespace) {
+      self.writer.write_char(' ')?;
+    }
+    write!(self.writer, "{}{}", decl.value, raws.after.trim_end())?;
+    self.visit_children(&decl.children)
+  }
+}
+
+/// the SugarSS source of `root`
+pub fn stringify(root: &Root) -> String {
+  let mut stringifier = Stringifier::new(String::with_capacity(root.end));
+  stringifier.visit_root(root).unwrap();
+  stringifier.result()
+}
@@ -0,0 +1,455 @@
+//! A parser for declaration values, like `postcss-value-parser`.
+//!
+//! ```
+//! use recursive_parser::value::{self, Node};
+//!
+//! let mut nodes = value::parse("1px solid rgba(0, 0, 0, .5)");
+//! value::walk_mut(&mut nodes, &mut |node| {
+//!   if let Node::Word { value, .. } = node {
+//!     if let Some((number, "px")) = value::unit(value) {
+//!       let rem = number.parse::<f64>().unwrap() / 16.0;
+//!       *value = format!("{}rem", rem).into();
+//!     }
+//!   }
+//!   true
+//! });
+//! assert_eq!(value::stringify(&nodes), "0.0625rem solid rgba(0, 0, 0, .5)");
+//! ```
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+
+/// A part of a value. `range` is where the node is in the parsed string, including the `before`
+/// and `after` whitespace, so `&input[node.range()]` is the node printed as it was parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node<'a> {
+  /// `12px`, `#fff`, `-webkit-box`, `+`
+  Word {
+    value: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `"a"`, the value is the text between the quotes with its escapes
+  String {
+    value: Cow<'a, str>,
+    quote: char,
+    unclosed: bool,
+    range: Range<usize>,
+  },
+  /// `rgb(0, 0, 0)`, or `(1px + 2px)` with an empty name. The argument of an unquoted `url()`
+  /// is a single word.
+  Function {
+    name: Cow<'a, str>,
+    /// whitespace after `(`
+    before: Cow<'a, str>,
+    nodes: Vec<Node<'a>>,
+    /// whitespace before `)`
+    after: Cow<'a, str>,
+    unclosed: bool,
+    range: Range<usize>,
+  },
+  /// `,`, `/` or `:` with the whitespace around it
+  Div {
+    value: char,
+    before: Cow<'a, str>,
+    after: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// whitespace between two other nodes
+  Space {
+    value: Cow<'a, str>,
+    range: Range<usize>,
+  },
+  /// `/* a */`, the value is the text between `/*` and `*/`
+  Comment {
+    value: Cow<'a, str>,
+    unclosed: bool,
+    range: Range<usize>,
+  },
+  /// `U+0025-00FF`, `u+4??`
+  UnicodeRange {
+    value: Cow<'a, str>,
+    range: Range<usize>,
+  },
+}
+
+impl<'a> Node<'a> {
+  pub fn range(&self) -> Range<usize> {
+    match self {
+      Node::Word { range, .. }
+      | Node::String { range, .. }
+      | Node::Function { range, .. }
+      | Node::Div { range, .. }
+      | Node::Space { range, .. }
+      | Node::Comment { range, .. }
+      | Node::UnicodeRange { range, .. } => range.clone(),
+    }
+  }
+}
+
+impl<'a> fmt::Display for Node<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Node::Word { value, .. } | Node::Space { value, .. } | Node::UnicodeRange { value, .. } => {
+        f.write_str(value)
+      }
+      Node::String {
+        value,
+        quote,
+        unclosed,
+        ..
+      } => {
+        write!(f, "{}{}", quote, value)?;
+        if !unclosed {
+          write!(f, "{}", quote)?;
+        }
+        Ok(())
+      }
+      Node::Function {
+        name,
+        before,
+        nodes,
+        after,
+        unclosed,
+        ..
+      } => {
+        write!(f, "{}({}", name, before)?;
+        for node in nodes {
+          write!(f, "{}", node)?;
+        }
+        f.write_str(after)?;
+        if !unclosed {
+          f.write_str(")")?;
+        }
+        Ok(())
+      }
+      Node::Div {
+        value,
+        before,
+        after,
+        ..
+      } => write!(f, "{}{}{}", before, value, after),
+      Node::Comment {
+        value, unclosed, ..
+      } => {
+        write!(f, "/*{}", value)?;
+        if !unclosed {
+          f.write_str("*/")?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+/// Parse a declaration value into nodes.
+pub fn parse(input: &str) -> Vec<Node<'_>> {
+  let mut parser = ValueParser {
+    input,
+    bytes: input.as_bytes(),
+    pos: 0,
+  };
+  parser.nodes(false)
+}
+
+/// Print `nodes` back into a value.
+pub fn stringify(nodes: &[Node<'_>]) -> String {
+  nodes.iter().map(|node| node.to_string()).collect()
+}
+
+/// Call `f` for every node in depth-first order. When `f` returns `false` for a function,
+/// its nodes are skipped.
+pub fn walk<'a, F: FnMut(&Node<'a>) -> bool>(nodes: &[Node<'a>], f: &mut F) {
+  for node in nodes {
+    if f(node) {
+      if let Node::Function { nodes, .. } = node {
+        walk(nodes, f);
+      }
+    }
+  }
+}
+
+/// [`walk`] with mutable nodes.
+pub fn walk_mut<'a, F: FnMut(&mut Node<'a>) -> bool>(nodes: &mut [Node<'a>], f: &mut F) {
+  for node in nodes {
+    if f(node) {
+      if let Node::Function { nodes, .. } = node {
+        walk_mut(nodes, f);
+      }
+    }
+  }
+}
+
+/// Split a dimension like `-1.5e3px` into its number and unit, `None` when `value` doesn't
+/// start with a number.
+///
+/// ```
+/// use recursive_parser::value::unit;
+///
+/// assert_eq!(unit("10px"), Some(("10", "px")));
+/// assert_eq!(unit(".5"), Some((".5", "")));
+/// assert_eq!(unit("1e3%"), Some(("1e3", "%")));
+/// assert_eq!(unit("2em"), Some(("2", "em")));
+/// assert_eq!(unit("px"), None);
+/// ```
+pub fn unit(value: &str) -> Option<(&str, &str)> {
+  let bytes = value.as_bytes();
+  let digits = |from: usize| {
+    bytes[from..]
+      .iter()
+      .take_while(|b| b.is_ascii_digit())
+      .count()
+  };
+  let mut pos = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+  let integer = digits(pos);
+  pos += integer;
+  let mut fraction = 0;
+  if bytes.get(pos) == Some(&b'.') {
+    fraction = digits(pos + 1);
+    if fraction > 0 {
+      pos += 1 + fraction;
+    }
+  }
+  if integer == 0 && fraction == 0 {
+    return None;
+  }
+  if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+    let sign = usize::from(matches!(bytes.get(pos + 1), Some(b'+' | b'-')));
+    let exponent = digits(pos + 1 + sign);
+    if exponent > 0 {
+      pos += 1 + sign + exponent;
+    }
+  }
+  Some(value.split_at(pos))
+}
+
+struct ValueParser<'a> {
+  input: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> ValueParser<'a> {
+  /// Parse nodes up to the end of the input or, in a function, up to its `)`.
+  fn nodes(&mut self, function: bool) -> Vec<Node<'a>> {
+    let mut nodes = vec![];
+    while let Some(&b) = self.bytes.get(self.pos) {
+      let start = self.pos;
+      let node = match b {
+        b')' if function => break,
+        b'"' | b'\'' => self.string(b),
+        b'/' if self.bytes.get(start + 1) == Some(&b'*') => self.comment(),
+        b',' | b'/' | b':' => self.div(start),
+        b'(' => self.function(start, start),
+        _ if is_space(b) => {
+          let end = self.skip_spaces(start);
+          match self.bytes.get(end) {
+            Some(b',' | b':') => self.div(start),
+            Some(b'/') if self.bytes.get(end + 1) != Some(&b'*') => self.div(start),
+            _ => {
+              self.pos = end;
+              Node::Space {
+                value: Cow::Borrowed(&self.input[start..end]),
+                range: start..end,
+              }
+            }
+          }
+        }
+        _ => {
+          let end = self.word_end(start);
+          if self.bytes.get(end) == Some(&b'(') {
+            self.function(start, end)
+          } else {
+            self.pos = end;
+            let value = &self.input[start..end];
+            if is_unicode_range(value) {
+              Node::UnicodeRange {
+                value: Cow::Borrowed(value),
+                range: start..end,
+              }
+            } else {
+              Node::Word {
+                value: Cow::Borrowed(value),
+                range: start..end,
+              }
+            }
+          }
+        }
+      };
+      nodes.push(node);
+    }
+    nodes
+  }
+
+  fn skip_spaces(&self, mut pos: usize) -> usize {
+    while self.bytes.get(pos).copied().is_some_and(is_space) {
+      pos += 1;
+    }
+    pos
+  }
+
+  /// the end of the word at `pos`, which has at least one character
+  fn word_end(&self, pos: usize) -> usize {
+    let mut chars = self.input[pos..].char_indices();
+    let mut end = self.input.len();
+    let mut first = true;
+    while let Some((i, ch)) = chars.next() {
+      if !first && (ch.is_ascii() && is_word_end(ch as u8)) {
+        end = pos + i;
+        break;
+      }
+      first = false;
+      if ch == '\\' {
+        chars.next();
+      }
+    }
+    end
+  }
+
+  /// a div with the whitespace around it, `start` is where the whitespace before it starts
+  fn div(&mut self, start: usize) -> Node<'a> {
+    let sign = self.skip_spaces(start);
+    let end = self.skip_spaces(sign + 1);
+    self.pos = end;
+    Node::Div {
+      value: self.bytes[sign] as char,
+      before: Cow::Borrowed(&self.input[start..sign]),
+      after: Cow::Borrowed(&self.input[sign + 1..end]),
+      range: start..end,
+    }
+  }
+
+  fn string(&mut self, quote: u8) -> Node<'a> {
+    let start = self.pos;
+    let mut pos = start + 1;
+    let mut unclosed = true;
+    while let Some(&b) = self.bytes.get(pos) {
+      if b == b'\\' {
+        pos += 1 + self.char_len(pos + 1);
+      } else if b == quote {
+        unclosed = false;
+        break;
+      } else {
+        pos += 1;
+      }
+    }
+    let end = pos.min(self.bytes.len());
+    self.pos = if unclosed { end } else { end + 1 };
+    Node::String {
+      value: Cow::Borrowed(&self.input[start + 1..end]),
+      quote: quote as char,
+      unclosed,
+      range: start..self.pos,
+    }
+  }
+
+  fn comment(&mut self) -> Node<'a> {
+    let start = self.pos;
+    let (end, unclosed) = match memchr::memmem::find(&self.bytes[start + 2..], b"*/") {
+      Some(i) => (start + 2 + i, false),
+      None => (self.bytes.len(), true),
+    };
+    self.pos = if unclosed { end } else { end + 2 };
+    Node::Comment {
+      value: Cow::Borrowed(&self.input[start + 2..end]),
+      unclosed,
+      range: start..self.pos,
+    }
+  }
+
+  /// a function whose name starts at `start` and whose `(` is at `open`
+  fn function(&mut self, start: usize, open: usize) -> Node<'a> {
+    let name = &self.input[start..open];
+    let inner = open + 1;
+    let content = self.skip_spaces(inner);
+    let mut nodes;
+    let mut before = Cow::Borrowed("");
+    let mut after = Cow::Borrowed("");
+    if name.eq_ignore_ascii_case("url") && !matches!(self.bytes.get(content), Some(b'"' | b'\'')) {
+      // an unquoted url is a single word, whatever it contains
+      let mut end = content;
+      while let Some(&b) = self.bytes.get(end) {
+        match b {
+          b')' => break,
+          b'\\' => end += 1 + self.char_len(end + 1),
+          _ => end += 1,
+        }
+      }
+      let end = end.min(self.bytes.len());
+      let url = self.input[content..end].trim_end_matches(is_space_char);
+      let url_end = content + url.len();
+      before = Cow::Borrowed(&self.input[inner..content]);
+      after = Cow::Borrowed(&self.input[url_end..end]);
+      nodes = vec![];
+      if !url.is_empty() {
+        nodes.push(Node::Word {
+          value: Cow::Borrowed(url),
+          range: content..url_end,
+        });
+      }
+      self.pos = end;
+    } else {
+      self.pos = inner;
+      nodes = self.nodes(true);
+      if let Some(Node::Space { value, .. }) = nodes.first() {
+        before = value.clone();
+        nodes.remove(0);
+      }
+      if let Some(Node::Space { value, .. }) = nodes.last() {
+        after = value.clone();
+        nodes.pop();
+      }
+    }
+    let unclosed = self.bytes.get(self.pos) != Some(&b')');
+    if !unclosed {
+      self.pos += 1;
+    }
+    Node::Function {
+      name: Cow::Borrowed(name),
+      before,
+      nodes,
+      after,
+      unclosed,
+      range: start..self.pos,
+    }
+  }
+
+  /// the length of the character at `pos`, 0 at the end of the input
+  fn char_len(&self, pos: usize) -> usize {
+    self
+      .input
+      .get(pos..)
+      .and_then(|rest| rest.chars().next())
+      .map_or(0, char::len_utf8)
+  }
+}
+
+fn is_space(b: u8) -> bool {
+  matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')
+}
+
+fn is_space_char(ch: char) -> bool {
+  ch.is_ascii() && is_space(ch as u8)
+}
+
+fn is_word_end(b: u8) -> bool {
+  is_space(b) || matches!(b, b'"' | b'\'' | b'(' | b')' | b',' | b'/' | b':')
+}
+
+/// `U+` followed by up to six hex digits or `?`, optionally `-` and up to six hex digits
+fn is_unicode_range(value: &str) -> bool {
+  let rest = match value.get(..2) {
+    Some(prefix) if prefix.eq_ignore_ascii_case("u+") => &value[2..],
+    _ => return false,
+  };
+  let (start, end) = match rest.split_once('-') {
+    Some((start, end)) => (start, Some(end)),
+    None => (rest, None),
+  };
+  let hex = |part: &str, wildcard: bool| {
+    (1..=6).contains(&part.len())
+      && part
+        .bytes()
+        .all(|b| b.is_ascii_hexdigit() || (wildcard && b == b'?'))
+  };
+  hex(start, end.is_none()) && end.is_none_or(|end| hex(end, false))
+}
@@ -3,6 +3,8 @@ use anyhow::Result;
 mod test_ast {
   use std::fs::read_to_string;
 
+  use recursive_parser::parser::Root;
+  use recursive_parser::stringifier::stringify;
   use recursive_parser::{parser::Parser, AstPrinter};
 
   use super::*;
@@ -40,6 +42,25 @@ mod test_ast {
     Ok(())
   }
 
+  #[test]
+  fn round_trips_css_and_json() -> Result<()> {
+    for file_name in [
+      "simple.css",
+      "utf8.css",
+      "bootstrap.css",
+      "bootstrap.rtl.css",
+    ] {
+      let file = read_to_string(format!("../../assets/{}", file_name))?;
+      let root = Parser::new(&file).parse().unwrap();
+      similar_asserts::assert_str_eq!(stringify(&root), file, "{}", file_name);
+      let json = root.to_json();
+      let copy = Root::from_json(&json.to_string())?;
+      similar_asserts::assert_str_eq!(stringify(&copy), file, "{}", file_name);
+      assert!(copy.to_json() == json, "{} changed in JSON", file_name);
+    }
+    Ok(())
+  }
+
   #[test]
   fn official_integration_test_css() -> Result<()> {
     let file_list = [
@@ -0,0 +1,34 @@
+use recursive_parser::parser::Parser;
+use recursive_parser::stringifier::{stringify, stringify_with_bom};
+use recursive_parser::sugarss;
+use recursive_parser::AstPrinter;
+use tokenizer::Syntax;
+
+#[test]
+fn skips_bom() {
+  let root = recursive_parser::parse("\u{feff}a { color: red }", None);
+  let mut printer = AstPrinter::new(0, String::default());
+  printer.print(&root).unwrap();
+  assert!(printer.result().contains("selector: `a`"));
+}
+
+#[test]
+fn records_bom() {
+  let css = "\u{feff}a { color: red }";
+  let root = Parser::new(css).parse().unwrap();
+  assert!(root.has_bom);
+  assert_eq!(stringify(&root), "a { color: red }");
+  assert_eq!(stringify_with_bom(&root), css);
+
+  let root = Parser::new("a { color: red }").parse().unwrap();
+  assert!(!root.has_bom);
+  assert_eq!(stringify_with_bom(&root), "a { color: red }");
+}
+
+#[test]
+fn records_bom_in_sugarss() {
+  let sss = "\u{feff}a\n  color: red\n";
+  let root = Parser::with_syntax(sss, Syntax::Sugarss).parse().unwrap();
+  assert!(root.has_bom);
+  assert_eq!(sugarss::stringify(&root), &sss[3..]);
+}
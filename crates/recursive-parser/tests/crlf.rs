@@ -0,0 +1,66 @@
+use anyhow::Result;
+use recursive_parser::{parser::Parser, AstPrinter};
+use std::fs::read_to_string;
+use tokenizer::newline::normalize_newlines;
+
+const FILE_LIST: [&str; 4] = [
+  "simple.css",
+  "bootstrap-reboot.css",
+  "bootstrap-grid.css",
+  "bootstrap.css",
+];
+
+fn print_ast(css: &str) -> Result<String> {
+  let root = Parser::new(css).parse().unwrap();
+  let mut printer = AstPrinter::new(0, String::default());
+  printer.print(&root)?;
+  Ok(printer.result())
+}
+
+fn windows(file_name: &str) -> Result<String> {
+  let css = read_to_string(format!("../../assets/{}", file_name))?;
+  Ok(css.replace("\r\n", "\n").replace('\n', "\r\n"))
+}
+
+fn expected_ast(file_name: &str) -> Result<String> {
+  Ok(read_to_string(format!(
+    "./tests/fixtures/{}.ast",
+    file_name.rsplit_once('.').unwrap().0
+  ))?)
+}
+
+/// `Rule@46..90` -> `Rule`
+fn without_offsets(ast: &str) -> String {
+  ast
+    .lines()
+    .map(|line| match line.trim_start().split_once('@') {
+      Some((kind, _)) if !kind.contains(' ') => {
+        &line[..line.len() - line.trim_start().len() + kind.len()]
+      }
+      _ => line,
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[test]
+fn parses_normalized_windows_line_endings() -> Result<()> {
+  for file_name in FILE_LIST {
+    let css = windows(file_name)?;
+    let normalized = normalize_newlines(&css);
+    similar_asserts::assert_eq!(print_ast(&normalized.css)?, expected_ast(file_name)?);
+  }
+  Ok(())
+}
+
+#[test]
+fn parses_windows_line_endings() -> Result<()> {
+  for file_name in FILE_LIST {
+    let ast = print_ast(&windows(file_name)?)?.replace("\r\n", "\n");
+    similar_asserts::assert_eq!(
+      without_offsets(&ast),
+      without_offsets(&expected_ast(file_name)?)
+    );
+  }
+  Ok(())
+}
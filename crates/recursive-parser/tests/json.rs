@@ -0,0 +1,204 @@
+use recursive_parser::json;
+use recursive_parser::parser::{Parser, Root};
+use recursive_parser::stringifier::{stringify, stringify_with_bom};
+use serde_json::json;
+use tokenizer::Syntax;
+
+#[test]
+fn matches_postcss_shape() {
+  let css = "/* a */\n@media print {\n  a { color: red ; margin: 0/* b */1px !IMPORTANT }\n}\n";
+  let json = Parser::new(css).parse().unwrap().to_json();
+  assert_eq!(
+    json,
+    json!({
+      "type": "root",
+      "raws": { "semicolon": false, "after": "\n" },
+      "source": {
+        "inputId": 0,
+        "start": { "offset": 0, "line": 1, "column": 1 },
+        "end": { "offset": 77, "line": 5, "column": 1 },
+      },
+      "inputs": [{ "hasBOM": false, "css": css }],
+      "nodes": [
+        {
+          "type": "comment",
+          "text": "a",
+          "raws": { "before": "", "left": " ", "right": " " },
+          "source": {
+            "inputId": 0,
+            "start": { "offset": 0, "line": 1, "column": 1 },
+            "end": { "offset": 7, "line": 1, "column": 7 },
+          },
+        },
+        {
+          "type": "atrule",
+          "name": "media",
+          "params": "print",
+          "raws": {
+            "before": "\n",
+            "between": " ",
+            "afterName": " ",
+            "semicolon": false,
+            "after": "\n",
+          },
+          "source": {
+            "inputId": 0,
+            "start": { "offset": 8, "line": 2, "column": 1 },
+            "end": { "offset": 76, "line": 4, "column": 1 },
+          },
+          "nodes": [{
+            "type": "rule",
+            "selector": "a",
+            "raws": { "before": "\n  ", "between": " ", "semicolon": false, "after": " " },
+            "source": {
+              "inputId": 0,
+              "start": { "offset": 25, "line": 3, "column": 3 },
+              "end": { "offset": 74, "line": 3, "column": 51 },
+            },
+            "nodes": [
+              {
+                "type": "decl",
+                "prop": "color",
+                "value": "red",
+                "raws": {
+                  "before": " ",
+                  "between": ": ",
+                  "value": { "value": "red", "raw": "red " },
+                },
+                "source": {
+                  "inputId": 0,
+                  "start": { "offset": 29, "line": 3, "column": 7 },
+                  "end": { "offset": 41, "line": 3, "column": 18 },
+                },
+              },
+              {
+                "type": "decl",
+                "prop": "margin",
+                "value": "0/* b */1px",
+                "important": true,
+                "raws": { "before": " ", "between": ": ", "important": " !IMPORTANT" },
+                "source": {
+                  "inputId": 0,
+                  "start": { "offset": 42, "line": 3, "column": 20 },
+                  "end": { "offset": 72, "line": 3, "column": 49 },
+                },
+              },
+            ],
+          }],
+        },
+      ],
+    })
+  );
+}
+
+#[test]
+fn counts_utf16_positions() {
+  let json = Parser::new("a{}\n/* 😀 */b{}").parse().unwrap().to_json();
+  assert_eq!(json["nodes"][1]["text"], "😀");
+  assert_eq!(json["nodes"][1]["source"]["end"]["offset"], 12);
+  assert_eq!(
+    json["nodes"][2]["source"]["start"],
+    json!({ "offset": 12, "line": 2, "column": 9 })
+  );
+}
+
+#[test]
+fn round_trips() {
+  let css = "@import 'a.css';\n/* x */ a, /* y */ b { color: red/* z */ ; --v: 1 }\n@media print{b{top:0/* c */}/* d */}\n/* end */";
+  let root = Parser::new(css).parse().unwrap();
+  let json = serde_json::to_string(&root).unwrap();
+  let copy = Root::from_json(&json).unwrap();
+  assert_eq!(stringify(&copy), css);
+  assert_eq!(copy.to_json(), root.to_json());
+}
+
+#[test]
+fn keeps_the_bom() {
+  let root = Parser::new("\u{feff}a {}").parse().unwrap();
+  let json = root.to_json();
+  assert_eq!(json["inputs"][0], json!({ "hasBOM": true, "css": "a {}" }));
+  let copy = Root::from_json(&json.to_string()).unwrap();
+  assert!(copy.has_bom);
+  assert_eq!(stringify_with_bom(&copy), "\u{feff}a {}");
+}
+
+#[test]
+fn writes_inline_comments_like_postcss_scss() {
+  let scss = "// a\na {\n  //b  \n  color: red; /* c */ //\n}";
+  let root = Parser::with_syntax(scss, Syntax::Scss).parse().unwrap();
+  let json = root.to_json();
+  let comment = |node: &serde_json::Value| (node["text"].clone(), node["raws"].clone());
+  assert_eq!(
+    comment(&json["nodes"][0]),
+    (
+      json!("a"),
+      json!({ "before": "", "left": " ", "right": "", "inline": true })
+    )
+  );
+  assert_eq!(json["nodes"][0]["source"]["end"]["offset"], 4);
+  let rule = &json["nodes"][1]["nodes"];
+  assert_eq!(
+    comment(&rule[0]),
+    (
+      json!("b"),
+      json!({ "before": "\n  ", "left": "", "right": "  ", "inline": true })
+    )
+  );
+  assert_eq!(
+    comment(&rule[2]),
+    (
+      json!("c"),
+      json!({ "before": " ", "left": " ", "right": " " })
+    )
+  );
+  assert_eq!(
+    comment(&rule[3]),
+    (
+      json!(""),
+      json!({ "before": " ", "left": "", "right": "", "inline": true })
+    )
+  );
+  let copy = Root::from_json(&json.to_string()).unwrap();
+  assert_eq!(stringify(&copy), scss);
+}
+
+#[test]
+fn builds_from_minimal_json() {
+  let root = Root::from_json(
+    r#"{
+      "type": "root",
+      "nodes": [
+        { "type": "rule", "selector": "a", "nodes": [
+          { "type": "decl", "prop": "color", "value": "red", "important": true },
+          { "type": "decl", "prop": "top", "value": "0" }
+        ] },
+        { "type": "atrule", "name": "import", "params": "'a.css'" },
+        { "type": "comment", "text": "end" }
+      ]
+    }"#,
+  )
+  .unwrap();
+  assert_eq!(
+    stringify(&root),
+    "a{color:red !important;top:0}@import 'a.css'/* end */"
+  );
+  assert!(Root::from_json(r#"{ "type": "rule", "selector": "a" }"#).is_err());
+}
+
+#[test]
+fn reports_differences_with_paths() {
+  let expected = json!({ "nodes": [{ "type": "decl", "raws": { "between": ": " } }, { "a": 1 }] });
+  let actual = json!({ "nodes": [{ "type": "decl", "raws": { "between": ":" } }], "b": true });
+  let differences = json::diff(&expected, &actual)
+    .iter()
+    .map(|difference| difference.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    differences,
+    [
+      r#"nodes[0].raws.between: expected ": ", found ":""#,
+      r#"nodes[1]: expected {"a":1}, found nothing"#,
+      "b: expected nothing, found true",
+    ]
+  );
+}
@@ -0,0 +1,135 @@
+use recursive_parser::parser::{AtRule, Parser, Root, RuleOrAtRuleOrDecl};
+use recursive_parser::stringifier::stringify;
+use tokenizer::Syntax;
+
+const LESS: &str = r#"@import (reference) "foo.less";
+// variables
+@primary: #333;
+@selector: ~".my-class";
+@detached: { background: red; };
+
+.mixin(@color; @padding: 2px) when (iscolor(@color)) {
+  color: @color;
+  padding: @padding;
+}
+
+.@{name}-box {
+  @{property}-color: @primary;
+  .mixin(#fff; 4px);
+  #namespace > .mixin();
+  .bordered !important;
+  width: ~"calc(100% - @{gutter})";
+  @media (min-width: 768px) { float: left; }
+  &:extend(.b all);
+  @r: { color: red };
+  @detached();
+}
+"#;
+
+fn less(source: &str) -> Root<'_> {
+  Parser::with_syntax(source, Syntax::Less).parse().unwrap()
+}
+
+fn at_rules<'a, 'b>(children: &'b [RuleOrAtRuleOrDecl<'a>]) -> Vec<&'b AtRule<'a>> {
+  children
+    .iter()
+    .filter_map(|child| match child {
+      RuleOrAtRuleOrDecl::AtRule(at_rule) => Some(at_rule),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn round_trips_less() {
+  assert_eq!(stringify(&less(LESS)), LESS);
+}
+
+#[test]
+fn parses_variables() {
+  let root = less(LESS);
+  let variables: Vec<_> = at_rules(&root.children)
+    .into_iter()
+    .map(|at_rule| (at_rule.variable, &*at_rule.name, &*at_rule.params))
+    .collect();
+  assert_eq!(
+    variables,
+    vec![
+      (false, "import", "(reference) \"foo.less\""),
+      (true, "primary", "#333"),
+      (true, "selector", "~\".my-class\""),
+      (true, "detached", ""),
+    ]
+  );
+  let detached = at_rules(&root.children)[3];
+  assert!(detached.has_block);
+  assert_eq!(detached.children.len(), 1);
+}
+
+#[test]
+fn parses_mixins() {
+  let root = less(LESS);
+  let RuleOrAtRuleOrDecl::Rule(definition) = &root.children[4] else {
+    unreachable!()
+  };
+  assert_eq!(
+    definition.selector,
+    ".mixin(@color; @padding: 2px) when (iscolor(@color))"
+  );
+  let RuleOrAtRuleOrDecl::Rule(rule) = &root.children[5] else {
+    unreachable!()
+  };
+  assert_eq!(rule.selector, ".@{name}-box");
+  let calls: Vec<_> = at_rules(&rule.children)
+    .into_iter()
+    .map(|at_rule| {
+      (
+        at_rule.mixin,
+        at_rule.variable,
+        &*at_rule.raws.identifier,
+        &*at_rule.name,
+        &*at_rule.params,
+      )
+    })
+    .collect();
+  assert_eq!(
+    calls,
+    vec![
+      (true, false, ".", "mixin", "(#fff; 4px)"),
+      (true, false, "#", "namespace", "> .mixin()"),
+      (true, false, ".", "bordered", "!important"),
+      (false, false, "", "media", "(min-width: 768px)"),
+      (false, true, "", "r", ""),
+      (false, false, "", "detached", "()"),
+    ]
+  );
+  let decls: Vec<_> = rule
+    .children
+    .iter()
+    .filter_map(|child| match child {
+      RuleOrAtRuleOrDecl::Declaration(decl) => Some((&*decl.prop, &*decl.value)),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    decls,
+    vec![
+      ("@{property}-color", "@primary"),
+      ("width", "~\"calc(100% - @{gutter})\""),
+      ("&", "extend(.b all)"),
+    ]
+  );
+}
+
+#[test]
+fn changes_mixin_calls() {
+  let mut root = less(".a { .m(); }");
+  let RuleOrAtRuleOrDecl::Rule(rule) = &mut root.children[0] else {
+    unreachable!()
+  };
+  let RuleOrAtRuleOrDecl::AtRule(call) = &mut rule.children[0] else {
+    unreachable!()
+  };
+  call.name = "other".into();
+  assert_eq!(stringify(&root), ".a { .other(); }");
+}
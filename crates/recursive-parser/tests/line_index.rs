@@ -0,0 +1,14 @@
+use recursive_parser::line_index::{Encoding, LineCol, LineIndex};
+use recursive_parser::parser::Parser;
+
+#[test]
+fn reports_error_positions() {
+  let css = "/* 😀 */\na { color: red; }\n.b {";
+  let err = Parser::new(css).parse().err().unwrap();
+  let index = LineIndex::new(css);
+  let at = |line, col| LineCol { line, col };
+  assert_eq!(
+    err.line_cols(&index, Encoding::Utf16),
+    Some((at(2, 4), at(2, 4)))
+  );
+}
@@ -0,0 +1,186 @@
+use recursive_parser::media::{
+  self, Bound, Condition, Feature, MediaQuery, MediaValue, Modifier, Viewport,
+};
+
+fn roundtrip(input: &str) -> String {
+  media::stringify(&media::parse(input))
+}
+
+#[test]
+fn parses_types_and_modifiers() {
+  assert_eq!(
+    media::parse("only screen, NOT print and (color)"),
+    vec![
+      MediaQuery {
+        modifier: Some(Modifier::Only),
+        media_type: Some("screen".to_string()),
+        condition: None,
+        invalid: None,
+      },
+      MediaQuery {
+        modifier: Some(Modifier::Not),
+        media_type: Some("print".to_string()),
+        condition: Some(Condition::Feature(Feature::Boolean("color".to_string()))),
+        invalid: None,
+      },
+    ]
+  );
+  assert_eq!(media::parse(""), vec![]);
+}
+
+#[test]
+fn parses_ranges() {
+  let range = |lower: Option<(f64, bool)>, upper: Option<(f64, bool)>| {
+    let bound = |(value, inclusive)| Bound {
+      value: MediaValue::Dimension(value, "px".to_string()),
+      inclusive,
+    };
+    Some(Condition::Feature(Feature::Range {
+      name: "width".to_string(),
+      lower: lower.map(bound),
+      upper: upper.map(bound),
+    }))
+  };
+  let condition = |input: &str| media::parse(input).remove(0).condition;
+  assert_eq!(
+    condition("(400px <= width < 700px)"),
+    range(Some((400.0, true)), Some((700.0, false)))
+  );
+  assert_eq!(
+    condition("(700px > width >= 400px)"),
+    range(Some((400.0, true)), Some((700.0, false)))
+  );
+  assert_eq!(
+    condition("(width > 400px)"),
+    range(Some((400.0, false)), None)
+  );
+  assert_eq!(
+    condition("(400px > width)"),
+    range(None, Some((400.0, false)))
+  );
+  assert_eq!(
+    condition("(width = 400px)"),
+    range(Some((400.0, true)), Some((400.0, true)))
+  );
+}
+
+#[test]
+fn stringifies_normalized() {
+  assert_eq!(
+    roundtrip("SCREEN   and (MIN-WIDTH:400px) ,print"),
+    "screen and (min-width: 400px), print"
+  );
+  assert_eq!(
+    roundtrip("(400px<=width<=700px) and (orientation:landscape)"),
+    "(400px <= width <= 700px) and (orientation: landscape)"
+  );
+  assert_eq!(roundtrip("(400px < width)"), "(width > 400px)");
+  assert_eq!(
+    roundtrip("(aspect-ratio >= 16 / 9)"),
+    "(aspect-ratio >= 16/9)"
+  );
+  assert_eq!(
+    roundtrip("screen /* a, b */ and/* c */(color)"),
+    "screen and (color)"
+  );
+  assert_eq!(
+    roundtrip("(not (color)) and ((hover) or (pointer: fine))"),
+    "(not (color)) and ((hover) or (pointer: fine))"
+  );
+  assert_eq!(roundtrip("not (color)"), "not (color)");
+  assert_eq!(
+    roundtrip("screen and (width > calc(100px + 1em))"),
+    "screen and (width > calc(100px + 1em))"
+  );
+  assert_eq!(roundtrip("(foo bar) or (color)"), "(foo bar) or (color)");
+}
+
+#[test]
+fn keeps_invalid_queries_as_written() {
+  assert_eq!(roundtrip("screen and, PRINT"), "screen and, print");
+  assert_eq!(
+    roundtrip("(color) and (hover) or (grid)"),
+    "(color) and (hover) or (grid)"
+  );
+  assert_eq!(roundtrip("screen or (color)"), "screen or (color)");
+  assert_eq!(roundtrip("and, (color"), "and, (color");
+  assert_eq!(
+    roundtrip("screen and (color) print"),
+    "screen and (color) print"
+  );
+  assert_eq!(
+    roundtrip("not ((color) or (hover)) and (width = 10em)"),
+    "not ((color) or (hover)) and (width = 10em)"
+  );
+  assert_eq!(
+    roundtrip("#{$q}, #{map-get($m, a)}"),
+    "#{$q}, #{map-get($m, a)}"
+  );
+  // but they evaluate as `not all`
+  let queries = media::parse("screen or (color), #{$q}");
+  assert_eq!(queries.len(), 2);
+  assert!(!media::matches(&queries, &Viewport::default()));
+  assert_eq!(
+    MediaQuery {
+      invalid: None,
+      ..queries[0].clone()
+    },
+    MediaQuery::not_all()
+  );
+}
+
+#[test]
+fn converts_ranges_to_min_max() {
+  let min_max = |input: &str| media::stringify(&media::to_min_max(&media::parse(input)));
+  assert_eq!(min_max("(width >= 400px)"), "(min-width: 400px)");
+  assert_eq!(min_max("(width < 400px)"), "(max-width: 399.999px)");
+  assert_eq!(min_max("(width > 40em)"), "(min-width: 40.001em)");
+  assert_eq!(min_max("(width = 400px)"), "(width: 400px)");
+  assert_eq!(
+    min_max("screen and (400px <= width <= 700px) and (hover)"),
+    "screen and (min-width: 400px) and (max-width: 700px) and (hover)"
+  );
+  assert_eq!(
+    min_max("(400px <= width <= 700px) or (color)"),
+    "((min-width: 400px) and (max-width: 700px)) or (color)"
+  );
+  assert_eq!(min_max("(aspect-ratio > 16/9)"), "(aspect-ratio > 16/9)");
+  assert_eq!(min_max("(min-width: 1px)"), "(min-width: 1px)");
+}
+
+#[test]
+fn matches_viewports() {
+  let phone = Viewport {
+    width: 375.0,
+    height: 812.0,
+    resolution: 3.0,
+    ..Default::default()
+  };
+  let desktop = Viewport::default();
+  let print = Viewport {
+    media_type: "print".to_string(),
+    ..Default::default()
+  };
+  let check = |input: &str, viewport: &Viewport| media::matches(&media::parse(input), viewport);
+
+  assert!(check("", &phone));
+  assert!(check("all", &print));
+  assert!(check("screen and (max-width: 400px)", &phone));
+  assert!(!check("screen and (max-width: 400px)", &desktop));
+  assert!(!check("screen and (max-width: 400px)", &print));
+  assert!(check("not print", &phone));
+  assert!(check("not screen and (max-width: 400px)", &desktop));
+  assert!(!check("(400px <= width < 1024px)", &phone));
+  assert!(!check("(400px <= width < 1024px)", &desktop));
+  assert!(check("(400px <= width <= 1024px)", &desktop));
+  assert!(check("(width < 25em)", &phone));
+  assert!(check("(orientation: portrait)", &phone));
+  assert!(check("(orientation: landscape)", &desktop));
+  assert!(check("(min-resolution: 2dppx)", &phone));
+  assert!(check("(resolution >= 192dpi)", &phone));
+  assert!(check("(aspect-ratio > 1/1)", &desktop));
+  assert!(check("(color) and (not (monochrome))", &desktop));
+  assert!(!check("(hover) or (unknown-feature: 1)", &desktop));
+  assert!(check("print, (max-width: 400px)", &phone));
+  assert!(!check("screen and, print", &phone));
+}
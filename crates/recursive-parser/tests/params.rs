@@ -0,0 +1,220 @@
+use recursive_parser::media::{self, Condition, Feature};
+use recursive_parser::params::{self, Container, Import, Params, Raws, Supports};
+use recursive_parser::parser::{Parser, RuleOrAtRuleOrDecl};
+use recursive_parser::stringifier::stringify;
+use std::borrow::Cow;
+
+#[test]
+fn lowers_media_ranges_of_imports() {
+  let css = "@import url(a.css) layer(base) (width >= 600px);\n@layer base, theme;\n@font-face {}";
+  let mut root = Parser::new(css).parse().unwrap();
+  let mut layers = vec![];
+  for child in &mut root.children {
+    if let RuleOrAtRuleOrDecl::AtRule(at_rule) = child {
+      let params = match at_rule.parsed_params() {
+        Some(Params::Import(mut import)) => {
+          import.media = media::to_min_max(&import.media);
+          Params::Import(import).to_string()
+        }
+        Some(Params::Layer { names, .. }) => {
+          layers.extend(names.iter().map(|name| name.to_string()));
+          continue;
+        }
+        Some(params) => panic!("unexpected params {:?}", params),
+        None => continue,
+      };
+      at_rule.params = params.into();
+    }
+  }
+  assert_eq!(layers, ["base", "theme"]);
+  assert_eq!(
+    stringify(&root),
+    "@import url(a.css) layer(base) (min-width: 600px);\n@layer base, theme;\n@font-face {}"
+  );
+}
+
+fn roundtrip(name: &str, input: &str) -> Option<String> {
+  params::parse(name, input).map(|params| params.to_string())
+}
+
+fn raws(value: &str) -> Raws<'_> {
+  Raws {
+    value: Cow::Borrowed(value),
+    ..Raws::default()
+  }
+}
+
+fn declaration<'a>(prop: &'a str, value: &'a str) -> Supports<'a> {
+  Supports::Declaration {
+    prop: Cow::Borrowed(prop),
+    value: Cow::Borrowed(value),
+  }
+}
+
+#[test]
+fn parses_imports() {
+  assert_eq!(
+    params::parse(
+      "import",
+      r#"url("a.css") layer supports(not (display: grid)) screen, print"#
+    ),
+    Some(Params::Import(Import {
+      url: Cow::Borrowed("a.css"),
+      function: true,
+      quote: Some('"'),
+      layer: Some(Cow::Borrowed("")),
+      supports: Some(Supports::Not(Box::new(declaration("display", "grid")))),
+      media: media::parse("screen, print"),
+      raws: raws(r#"url("a.css") layer supports(not (display: grid)) screen, print"#),
+    }))
+  );
+  assert_eq!(
+    roundtrip("IMPORT", "url( a.css )layer( base.x )  screen and (color)"),
+    Some("url( a.css )layer( base.x )  screen and (color)".to_string())
+  );
+  let mut import = match params::parse("import", "url( a.css )layer( base.x ) /* c */") {
+    Some(Params::Import(import)) => import,
+    params => panic!("unexpected params {:?}", params),
+  };
+  import.layer = None;
+  assert_eq!(import.to_string(), "url(a.css) /* c */");
+  assert_eq!(
+    roundtrip(
+      "import",
+      "'a.css' supports((display: grid) and selector(a > b))"
+    ),
+    Some("'a.css' supports((display: grid) and selector(a > b))".to_string())
+  );
+  assert_eq!(roundtrip("import", "a.css"), None);
+  assert_eq!(roundtrip("import", "'a.css' layer()"), None);
+  assert_eq!(roundtrip("import", "url(a.css"), None);
+}
+
+#[test]
+fn parses_supports_conditions() {
+  assert_eq!(
+    params::parse("supports", "(display: grid) or (not selector(:has(a)))"),
+    Some(Params::Supports {
+      condition: Supports::Or(vec![
+        declaration("display", "grid"),
+        Supports::Not(Box::new(Supports::Selector(Cow::Borrowed(":has(a)")))),
+      ]),
+      raws: raws("(display: grid) or (not selector(:has(a)))"),
+    })
+  );
+  assert_eq!(
+    roundtrip(
+      "supports",
+      "((a:b)) AND (--x: {a})  and font-tech(color-colrv1)"
+    ),
+    Some("((a:b)) AND (--x: {a})  and font-tech(color-colrv1)".to_string())
+  );
+  let mut supports = params::parse("supports", "((a:b)) AND /* c */ (--x: {a})").unwrap();
+  if let Params::Supports {
+    condition: Supports::And(conditions),
+    ..
+  } = &mut supports
+  {
+    conditions.pop();
+  }
+  assert_eq!(supports.to_string(), "(a: b)");
+  assert_eq!(
+    params::parse("supports", " (display:grid)/* c */"),
+    Some(Params::Supports {
+      condition: declaration("display", "grid"),
+      raws: Raws {
+        before: Cow::Borrowed(" "),
+        value: Cow::Borrowed("(display:grid)"),
+        after: Cow::Borrowed("/* c */"),
+      },
+    })
+  );
+  assert_eq!(
+    roundtrip("supports", "/* a */(a: b)/* b */or/* c */(c: d)"),
+    Some("/* a */(a: b)/* b */or/* c */(c: d)".to_string())
+  );
+  assert_eq!(
+    roundtrip("supports", "not ((a: b) or (c: d))"),
+    Some("not ((a: b) or (c: d))".to_string())
+  );
+  assert_eq!(
+    roundtrip("supports", "(foo bar) or (a: url(\")\"))"),
+    Some("(foo bar) or (a: url(\")\"))".to_string())
+  );
+  assert_eq!(roundtrip("supports", "(a: b) and (c: d) or (e: f)"), None);
+  assert_eq!(roundtrip("supports", "display: grid"), None);
+}
+
+#[test]
+fn parses_layers_and_keyframes() {
+  assert_eq!(
+    params::parse("layer", "reset ,base.typography"),
+    Some(Params::Layer {
+      names: vec![Cow::Borrowed("reset"), Cow::Borrowed("base.typography")],
+      raws: raws("reset ,base.typography"),
+    })
+  );
+  assert_eq!(
+    params::parse("layer", ""),
+    Some(Params::Layer {
+      names: vec![],
+      raws: raws(""),
+    })
+  );
+  assert_eq!(roundtrip("layer", "a b"), None);
+  assert_eq!(roundtrip("layer", "a,"), None);
+
+  assert_eq!(
+    params::parse("-webkit-keyframes", " fade"),
+    Some(Params::Keyframes {
+      name: Cow::Borrowed("fade"),
+      quote: None,
+      raws: Raws {
+        before: Cow::Borrowed(" "),
+        ..raws("fade")
+      },
+    })
+  );
+  assert_eq!(
+    roundtrip("keyframes", "'fade in'"),
+    Some("'fade in'".to_string())
+  );
+  assert_eq!(roundtrip("keyframes", "1fade"), None);
+  assert_eq!(roundtrip("font-face", ""), None);
+}
+
+#[test]
+fn parses_containers() {
+  assert_eq!(
+    params::parse("container", "sidebar (color)"),
+    Some(Params::Container(Container {
+      name: Some(Cow::Borrowed("sidebar")),
+      condition: Some(Condition::Feature(Feature::Boolean("color".to_string()))),
+      raws: raws("sidebar (color)"),
+    }))
+  );
+  assert_eq!(
+    roundtrip("container", "(400px<width) and style(--dark: 1)"),
+    Some("(400px<width) and style(--dark: 1)".to_string())
+  );
+  assert_eq!(
+    roundtrip("container", "not (width < 400px)"),
+    Some("not (width < 400px)".to_string())
+  );
+  assert_eq!(roundtrip("container", "card"), Some("card".to_string()));
+  assert_eq!(roundtrip("container", "a b"), None);
+  assert_eq!(roundtrip("container", ""), None);
+}
+
+#[test]
+fn parses_media() {
+  assert_eq!(
+    roundtrip("media", "screen  and (min-width:1px)"),
+    Some("screen  and (min-width:1px)".to_string())
+  );
+  let mut media = params::parse("media", "screen  and (width>=1px) /* c */").unwrap();
+  if let Params::Media { queries, .. } = &mut media {
+    *queries = media::to_min_max(queries);
+  }
+  assert_eq!(media.to_string(), "screen and (min-width: 1px) /* c */");
+}
@@ -0,0 +1,88 @@
+//! Compares the JSON of the recursive parser with the `root.toJSON()` of postcss for every case
+//! in `tests/official-cases`. The `.json` next to each `.css` is written by
+//! `adapter/to_json.js`, a case without one fails. Until those are committed the test is
+//! ignored, run it with `cargo test --test postcss -- --ignored`; `tests/basic.rs` keeps
+//! comparing the cases with their `.ast` snapshots meanwhile.
+use recursive_parser::json;
+use recursive_parser::parser::Parser;
+use serde_json::Value;
+use std::fs::read_to_string;
+
+/// The cases that don't match postcss yet. A case that matches must be removed, so the list
+/// tracks parity.
+const KNOWN_DIFFERENCES: &[&str] = &[];
+
+/// Drop what depends on how postcss was called rather than on the css.
+fn normalize(json: &mut Value) {
+  if let Some(Value::Array(inputs)) = json.get_mut("inputs") {
+    for input in inputs.iter_mut().filter_map(Value::as_object_mut) {
+      input.remove("id");
+      input.remove("file");
+    }
+  }
+}
+
+#[test]
+#[ignore = "needs the postcss JSON written by adapter/to_json.js"]
+fn matches_postcss() -> anyhow::Result<()> {
+  let mut paths = std::fs::read_dir("./tests/official-cases")?
+    .map(|entry| entry.map(|entry| entry.path()))
+    .collect::<Result<Vec<_>, _>>()?;
+  paths.sort();
+  let mut report = String::new();
+  let mut unexpected = vec![];
+  let mut fixed = vec![];
+  let mut missing = vec![];
+  for path in paths {
+    if path.extension().is_none_or(|ext| ext != "css") {
+      continue;
+    }
+    let name = path.file_stem().unwrap().to_string_lossy().to_string();
+    let css = read_to_string(&path)?;
+    let mut expected: Value = match read_to_string(path.with_extension("json")) {
+      Ok(expected) => serde_json::from_str(&expected)?,
+      Err(_) => {
+        missing.push(name);
+        continue;
+      }
+    };
+    normalize(&mut expected);
+    let differences = match Parser::new(&css).parse() {
+      Ok(root) => {
+        let mut actual = root.to_json();
+        normalize(&mut actual);
+        json::diff(&expected, &actual)
+          .iter()
+          .map(ToString::to_string)
+          .collect::<Vec<_>>()
+      }
+      Err(err) => vec![format!("parse error: {}", err)],
+    };
+    let known = KNOWN_DIFFERENCES.contains(&name.as_str());
+    if !differences.is_empty() && !known {
+      for difference in &differences {
+        report.push_str(&format!("\n  {}: {}", name, difference));
+      }
+      unexpected.push(name);
+    } else if differences.is_empty() && known {
+      fixed.push(name);
+    }
+  }
+  assert!(
+    missing.is_empty(),
+    "no postcss JSON for {:?}, run `npm install && node to_json.js` in `adapter`",
+    missing
+  );
+  assert!(
+    unexpected.is_empty(),
+    "differ from postcss: {:?}{}",
+    unexpected,
+    report
+  );
+  assert!(
+    fixed.is_empty(),
+    "match postcss, remove them from KNOWN_DIFFERENCES: {:?}",
+    fixed
+  );
+  Ok(())
+}
@@ -0,0 +1,149 @@
+use recursive_parser::parser::{Parser, RuleOrAtRuleOrDecl};
+use recursive_parser::pretty_print_ast;
+use recursive_parser::stringifier::stringify;
+use tokenizer::Syntax;
+
+const SCSS: &str = r##"@use "sass:math";
+// variables
+$primary: #333 !default;
+$map: (key: value, other: (1, 2));
+
+%placeholder { color: $primary; }
+
+@mixin theme($theme: DarkGray) {
+  background: $theme;
+  &:hover { background: darken($theme, 10%); }
+}
+
+.a-#{$name} {
+  @extend %placeholder;
+  @include theme; // trailing
+  #{$prop}-top: 1px;
+  content: "#{$a + "}"} b";
+  width: math.div(100%, 3);
+  font: bold {
+    family: serif;
+    size: 12px;
+  }
+  margin: {
+    left: 0
+  }
+  .b & { color: red }
+  @if $a == 1 { top: 0 } @else if $a { top: 1px } @else { top: 2px }
+  @each $key, $value in $map {
+    .icon-#{$key} { content: $value; }
+  }
+}
+"##;
+
+fn scss(source: &str) -> recursive_parser::parser::Root<'_> {
+  Parser::with_syntax(source, Syntax::Scss).parse().unwrap()
+}
+
+#[test]
+fn round_trips_scss() {
+  assert_eq!(stringify(&scss(SCSS)), SCSS);
+}
+
+#[test]
+fn parses_scss() {
+  let root = scss(SCSS);
+  let kinds: Vec<_> = root
+    .children
+    .iter()
+    .map(|child| match child {
+      RuleOrAtRuleOrDecl::Rule(rule) => format!("rule {}", rule.selector),
+      RuleOrAtRuleOrDecl::AtRule(at_rule) => format!("at-rule {}", at_rule.name),
+      RuleOrAtRuleOrDecl::Declaration(decl) => format!("decl {}", decl.prop),
+    })
+    .collect();
+  assert_eq!(
+    kinds,
+    vec![
+      "at-rule use",
+      "decl $primary",
+      "decl $map",
+      "rule %placeholder",
+      "at-rule mixin",
+      "rule .a-#{$name}",
+    ]
+  );
+  similar_asserts::assert_eq!(
+    pretty_print_ast(&root)
+      .lines()
+      .skip(26)
+      .take(26)
+      .collect::<Vec<_>>()
+      .join("\n"),
+    r##"  Rule@232..625
+    selector: `.a-#{$name}`
+    AtRule@248..269
+      name: `extend`
+      params: `%placeholder`
+    AtRule@272..287
+      name: `include`
+      params: `theme`
+    Declaration@302..320
+      prop: `#{$prop}-top`
+      value: `1px`
+    Declaration@323..348
+      prop: `content`
+      value: `"#{$a + "}"} b"`
+    Declaration@351..376
+      prop: `width`
+      value: `math.div(100%, 3)`
+    Declaration@379..430
+      prop: `font`
+      value: `bold`
+      Declaration@396..410
+        prop: `family`
+        value: `serif`
+      Declaration@415..426
+        prop: `size`
+        value: `12px`"##
+  );
+}
+
+#[test]
+fn tells_nested_properties_from_rules() {
+  let root = scss("a { font: 12px { family: x } margin: { left: 0 } b:hover { c: d } }");
+  let RuleOrAtRuleOrDecl::Rule(rule) = &root.children[0] else {
+    unreachable!()
+  };
+  let nested: Vec<_> = rule
+    .children
+    .iter()
+    .map(|child| match child {
+      RuleOrAtRuleOrDecl::Declaration(decl) => (decl.prop.to_string(), decl.is_nested),
+      RuleOrAtRuleOrDecl::Rule(rule) => (rule.selector.to_string(), false),
+      RuleOrAtRuleOrDecl::AtRule(_) => unreachable!(),
+    })
+    .collect();
+  assert_eq!(
+    nested,
+    vec![
+      ("font".to_string(), true),
+      ("margin:".to_string(), false),
+      ("b:hover".to_string(), false),
+    ]
+  );
+}
+
+#[test]
+fn ends_values_at_inline_comments() {
+  let source = "a { color: red// c\n; background: url(//a.png) url( //b.png ) }";
+  let root = scss(source);
+  let RuleOrAtRuleOrDecl::Rule(rule) = &root.children[0] else {
+    unreachable!()
+  };
+  let values: Vec<_> = rule
+    .children
+    .iter()
+    .map(|child| match child {
+      RuleOrAtRuleOrDecl::Declaration(decl) => decl.value.to_string(),
+      _ => unreachable!(),
+    })
+    .collect();
+  assert_eq!(values, vec!["red", "url(//a.png) url( //b.png )"]);
+  assert_eq!(stringify(&root), source);
+}
@@ -0,0 +1,236 @@
+use recursive_parser::parser::{Parser, RuleOrAtRuleOrDecl};
+use recursive_parser::selector::{self, Node, PseudoArgs, Specificity};
+use recursive_parser::stringifier::stringify;
+use std::borrow::Cow;
+
+#[test]
+fn scopes_class_names() {
+  let css = ".a, .b:not(.c) > div {}\n:global(.d) .e {}";
+  let mut root = Parser::new(css).parse().unwrap();
+  for child in &mut root.children {
+    if let RuleOrAtRuleOrDecl::Rule(rule) = child {
+      let mut selectors = rule.parsed_selector();
+      selector::walk_mut(&mut selectors, &mut |node| match node {
+        Node::Class { name, .. } => {
+          *name = format!("{}_x1", name).into();
+          true
+        }
+        Node::Pseudo { value, .. } => value != ":global",
+        _ => true,
+      });
+      rule.selector = selector::stringify(&selectors).into();
+    }
+  }
+  assert_eq!(
+    stringify(&root),
+    ".a_x1, .b_x1:not(.c_x1) > div {}\n:global(.d) .e_x1 {}"
+  );
+}
+
+fn kinds(input: &str) -> Vec<String> {
+  selector::parse(input)
+    .iter()
+    .map(|selector| {
+      selector
+        .nodes
+        .iter()
+        .map(|node| match node {
+          Node::Tag { name, .. } => format!("tag({})", name),
+          Node::Class { name, .. } => format!("class({})", name),
+          Node::Id { name, .. } => format!("id({})", name),
+          Node::Attribute { attribute, .. } => format!("attribute({})", attribute),
+          Node::Pseudo { value, .. } => format!("pseudo({})", value),
+          Node::Combinator { value, .. } => format!("combinator({})", value),
+          Node::Nesting { .. } => "nesting".to_string(),
+          Node::Universal { value, .. } => format!("universal({})", value),
+          Node::Comment { value, .. } => format!("comment({})", value),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+    })
+    .collect()
+}
+
+#[test]
+fn parses_compound_and_complex_selectors() {
+  assert_eq!(
+    kinds("a.b#c[d]:hover::before, * > & + svg|g ~ ns|*  .e\\:f"),
+    vec![
+      "tag(a) class(b) id(c) attribute(d) pseudo(:hover) pseudo(::before)",
+      "universal(*) combinator(>) nesting combinator(+) tag(svg|g) combinator(~) universal(ns|*) \
+       combinator(  ) class(e\\:f)",
+    ]
+  );
+  assert_eq!(
+    kinds("a/* x */b || c"),
+    vec!["tag(a) comment( x ) tag(b) combinator(||) tag(c)"]
+  );
+  assert_eq!(kinds("from, 50%"), vec!["tag(from)", "tag(50%)"]);
+}
+
+#[test]
+fn parses_hex_escapes_with_their_whitespace() {
+  assert_eq!(
+    kinds("#\\31 23 .x"),
+    vec!["id(\\31 23) combinator( ) class(x)"]
+  );
+  assert_eq!(kinds("\\31 a"), vec!["tag(\\31 a)"]);
+  assert_eq!(
+    kinds("[\\31 a=\\000031 b]\\1234567 c"),
+    vec!["attribute(\\31 a) tag(\\1234567) combinator( ) tag(c)"]
+  );
+  assert_eq!(
+    selector::parse("#\\31 23 .x")[0].specificity(),
+    Specificity(1, 1, 0)
+  );
+  assert_eq!(
+    selector::parse("\\31 a")[0].specificity(),
+    Specificity(0, 0, 1)
+  );
+}
+
+#[test]
+fn parses_attributes() {
+  let selectors = selector::parse(r#"[ data-a |= "x y" i ][b=c][lang]"#);
+  let nodes = &selectors[0].nodes;
+  match &nodes[0] {
+    Node::Attribute {
+      attribute,
+      op,
+      value,
+      quote,
+      insensitive,
+      raws,
+      range,
+    } => {
+      assert_eq!(attribute, "data-a");
+      assert_eq!(op.as_deref(), Some("|="));
+      assert_eq!(value.as_deref(), Some("x y"));
+      assert_eq!(*quote, Some('"'));
+      assert!(insensitive);
+      assert_eq!(raws.after_open, " ");
+      assert_eq!(raws.before_close, " ");
+      assert_eq!(range, &(0..21));
+    }
+    node => panic!("expected an attribute, found {:?}", node),
+  }
+  assert!(matches!(
+    &nodes[1],
+    Node::Attribute { value: Some(value), quote: None, insensitive: false, .. } if value == "c"
+  ));
+  assert!(matches!(
+    &nodes[2],
+    Node::Attribute {
+      op: None,
+      value: None,
+      ..
+    }
+  ));
+}
+
+#[test]
+fn parses_pseudo_arguments() {
+  let selectors = selector::parse(":not( .a , b ):nth-child(2n + 1 of (x)) :lang(en)");
+  match &selectors[0].nodes[0] {
+    Node::Pseudo {
+      args: Some(PseudoArgs::Selectors(args)),
+      ..
+    } => {
+      assert_eq!(args.len(), 2);
+      assert_eq!(args[0].before, " ");
+      assert_eq!(args[0].after, " ");
+      assert_eq!(args[1].to_string(), " b ");
+    }
+    node => panic!("expected :not(), found {:?}", node),
+  }
+  assert_eq!(
+    selectors[0].nodes[1],
+    Node::Pseudo {
+      value: Cow::Borrowed(":nth-child"),
+      args: Some(PseudoArgs::Raw(Cow::Borrowed("2n + 1 of (x)"))),
+      range: 14..39,
+    }
+  );
+}
+
+#[test]
+fn stringifies_what_it_parsed() {
+  for input in [
+    "",
+    " a ,b, c ",
+    "a>b + c~d  e",
+    "[ a = 'b' s ] [c$=d] [e i]",
+    ":is(a, :not(.b)) ::slotted( span )",
+    "a:nth-last-child( -n+3 )::after",
+    "#\\31 23 .你好",
+    "a /* c */ > b",
+  ] {
+    let selectors = selector::parse(input);
+    assert_eq!(selector::stringify(&selectors), input);
+    for selector in &selectors {
+      assert_eq!(&input[selector.range.clone()], selector.to_string());
+      for node in &selector.nodes {
+        assert_eq!(&input[node.range()], node.to_string());
+      }
+    }
+  }
+}
+
+#[test]
+fn mutates_attributes() {
+  let mut selectors = selector::parse("[a=b][c='d' i]");
+  selector::walk_mut(&mut selectors, &mut |node| {
+    if let Node::Attribute {
+      insensitive, value, ..
+    } = node
+    {
+      *insensitive = !*insensitive;
+      *value = Some("x".into());
+    }
+    true
+  });
+  assert_eq!(selector::stringify(&selectors), "[a=x i][c='x' ]");
+}
+
+#[test]
+fn walk_visits_pseudo_arguments() {
+  let selectors = selector::parse(".a:not(.b, :where(.c)), .d:global(.e)");
+  let mut classes = vec![];
+  selector::walk(&selectors, &mut |node| match node {
+    Node::Class { name, .. } => {
+      classes.push(name.to_string());
+      true
+    }
+    Node::Pseudo { value, .. } => value != ":global",
+    _ => true,
+  });
+  assert_eq!(classes, vec!["a", "b", "c", "d"]);
+}
+
+#[test]
+fn calculates_specificity() {
+  let specificity = |input: &str| selector::parse(input)[0].specificity();
+  assert_eq!(specificity("*"), Specificity(0, 0, 0));
+  assert_eq!(specificity("li"), Specificity(0, 0, 1));
+  assert_eq!(specificity("ul li::before"), Specificity(0, 0, 3));
+  assert_eq!(specificity("a:before"), Specificity(0, 0, 2));
+  assert_eq!(specificity("ul ol+li"), Specificity(0, 0, 3));
+  assert_eq!(specificity("h1 + *[rel=up]"), Specificity(0, 1, 1));
+  assert_eq!(specificity("ul ol li.red"), Specificity(0, 1, 3));
+  assert_eq!(specificity("li.red.level"), Specificity(0, 2, 1));
+  assert_eq!(specificity("#x34y"), Specificity(1, 0, 0));
+  assert_eq!(specificity("#s12:not(FOO)"), Specificity(1, 0, 1));
+  assert_eq!(specificity(".foo :is(.bar, #baz)"), Specificity(1, 1, 0));
+  assert_eq!(specificity(":where(#a) a:hover"), Specificity(0, 1, 1));
+  assert_eq!(specificity("& > .a"), Specificity(0, 1, 0));
+  assert_eq!(specificity(":nth-child(2n+1)"), Specificity(0, 1, 0));
+  assert_eq!(
+    specificity(":nth-child(2n+1 of .a, #b)"),
+    Specificity(1, 1, 0)
+  );
+  assert_eq!(
+    specificity("li:nth-last-child(odd OF li.x)"),
+    Specificity(0, 2, 2)
+  );
+  assert!(specificity("#a") > specificity(".a.b.c.d.e.f.g.h.i.j.k"));
+}
@@ -0,0 +1,48 @@
+use recursive_parser::parser::Parser;
+use recursive_parser::stringifier::stringify;
+use std::fs::read_to_string;
+
+#[test]
+fn round_trips_css() -> anyhow::Result<()> {
+  for file_name in [
+    "bootstrap-reboot.css",
+    "bootstrap-grid.css",
+    "bootstrap.css",
+    "utf8.css",
+  ] {
+    let css = read_to_string(format!("../../assets/{}", file_name))?;
+    let root = Parser::new(&css).parse().unwrap();
+    similar_asserts::assert_eq!(stringify(&root), css, "{}", file_name);
+  }
+  for entry in std::fs::read_dir("./tests/official-cases")? {
+    let path = entry?.path();
+    if path.extension().is_some_and(|ext| ext == "css") {
+      let css = read_to_string(&path)?;
+      if let Ok(root) = Parser::new(&css).parse() {
+        similar_asserts::assert_eq!(stringify(&root), css, "{:?}", path);
+      }
+    }
+  }
+  Ok(())
+}
+
+#[test]
+fn writes_changed_fields_with_the_raws() {
+  let css = "/* a */\na { color : red ; }\n@media  print{b{top:0}}";
+  let mut root = Parser::new(css).parse().unwrap();
+  for child in root.children.iter_mut() {
+    match child {
+      recursive_parser::parser::RuleOrAtRuleOrDecl::Rule(rule) => {
+        rule.selector = "a:hover".into();
+      }
+      recursive_parser::parser::RuleOrAtRuleOrDecl::AtRule(at_rule) => {
+        at_rule.params = "screen".into();
+      }
+      _ => unreachable!(),
+    }
+  }
+  assert_eq!(
+    stringify(&root),
+    "/* a */\na:hover { color : red ; }\n@media  screen{b{top:0}}"
+  );
+}
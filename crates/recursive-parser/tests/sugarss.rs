@@ -0,0 +1,137 @@
+use recursive_parser::error::PostcssError;
+use recursive_parser::parser::Parser;
+use recursive_parser::pretty_print_ast;
+use recursive_parser::stringifier::stringify;
+use recursive_parser::sugarss;
+use tokenizer::Syntax;
+
+const SSS: &str = "@import \"base.css\"
+
+// a comment
+a,
+b
+  color: red
+  &:hover
+    color: blue  /* trailing */
+
+  background:
+    url(a.png)
+    no-repeat
+@media (max-width: 100px)
+  .c
+    margin: 0 auto
+    grid-template-areas: \"a b\"
+      \"c d\"
+";
+
+/// the tree without the offsets
+fn shape(root: &recursive_parser::parser::Root) -> String {
+  pretty_print_ast(root)
+    .lines()
+    .map(|line| line.split('@').next().unwrap())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[test]
+fn round_trips_sugarss() {
+  let root = Parser::with_syntax(SSS, Syntax::Sugarss).parse().unwrap();
+  assert_eq!(sugarss::stringify(&root), SSS);
+}
+
+#[test]
+fn builds_the_css_tree() {
+  let root = Parser::with_syntax(SSS, Syntax::Sugarss).parse().unwrap();
+  similar_asserts::assert_eq!(
+    shape(&root),
+    "Root
+  AtRule
+    name: `import`
+    params: `\"base.css\"`
+  Rule
+    selector: `a,
+b`
+    Declaration
+      prop: `color`
+      value: `red`
+    Rule
+      selector: `&:hover`
+      Declaration
+        prop: `color`
+        value: `blue`
+    Declaration
+      prop: `background`
+      value: `url(a.png)
+    no-repeat`
+  AtRule
+    name: `media`
+    params: `(max-width: 100px)`
+    Rule
+      selector: `.c`
+      Declaration
+        prop: `margin`
+        value: `0 auto`
+      Declaration
+        prop: `grid-template-areas`
+        value: `\"a b\"
+      \"c d\"`"
+  );
+  let css = "a { color: red; &:hover { color: blue } }";
+  let css_root = Parser::with_syntax(css, Syntax::Scss).parse().unwrap();
+  let sss_root = Parser::with_syntax(
+    "a\n  color: red\n  &:hover\n    color: blue",
+    Syntax::Sugarss,
+  )
+  .parse()
+  .unwrap();
+  assert_eq!(shape(&css_root), shape(&sss_root));
+}
+
+#[test]
+fn writes_css_trees_as_sugarss() {
+  let css = "@media print {\n  a, b { color:red; top: 0 }\n}\n.c{}";
+  let root = Parser::new(css).parse().unwrap();
+  let sss = sugarss::stringify(&root);
+  assert_eq!(sss, "@media print\n  a, b\n    color: red\n    top: 0\n.c");
+  let reparsed = Parser::with_syntax(&sss, Syntax::Sugarss).parse().unwrap();
+  assert_eq!(shape(&reparsed), shape(&root));
+}
+
+#[test]
+fn writes_sugarss_trees_as_css() {
+  // the CSS parser keeps a comment after a value in the value
+  let sss = SSS.replace("  /* trailing */", "");
+  let root = Parser::with_syntax(&sss, Syntax::Sugarss).parse().unwrap();
+  let css = stringify(&root);
+  // SCSS reads the `//` comment and the nested rule
+  let reparsed = Parser::with_syntax(&css, Syntax::Scss).parse().unwrap();
+  assert_eq!(shape(&reparsed), shape(&root), "{}", css);
+}
+
+#[test]
+fn rejects_what_sugarss_rejects() {
+  let error = |sss: &str| match Parser::with_syntax(sss, Syntax::Sugarss).parse() {
+    Err(PostcssError::ParseError(message, start, end)) => (message, start..end),
+    result => panic!("{:?} parsed: {:?}", sss, result.map(|root| shape(&root))),
+  };
+  assert_eq!(
+    error("a\n\tb: c\n  d: e\n"),
+    ("mixed tabs and spaces are not allowed".to_string(), 8..10)
+  );
+  assert_eq!(
+    error("a\n \tb: c"),
+    ("mixed tabs and spaces are not allowed".to_string(), 2..4)
+  );
+  assert_eq!(
+    error("a\n  b: c\n d: e"),
+    ("expected an indentation of 2, found 1".to_string(), 9..10)
+  );
+  assert_eq!(
+    error("a\n  b: c;"),
+    ("unnecessary semicolon".to_string(), 8..9)
+  );
+  assert_eq!(
+    error("a\n  b: c\n    d;"),
+    ("unnecessary semicolon".to_string(), 14..15)
+  );
+}
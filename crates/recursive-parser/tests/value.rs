@@ -0,0 +1,237 @@
+use recursive_parser::parser::{Parser, RuleOrAtRuleOrDecl};
+use recursive_parser::stringifier::stringify;
+use recursive_parser::value::{self, Node};
+use std::borrow::Cow;
+
+#[test]
+fn rewrites_declaration_values() {
+  let css = "a { margin: 0 16px; background: url(16px.png) }";
+  let mut root = Parser::new(css).parse().unwrap();
+  let rule = match &mut root.children[0] {
+    RuleOrAtRuleOrDecl::Rule(rule) => rule,
+    _ => unreachable!(),
+  };
+  for child in &mut rule.children {
+    if let RuleOrAtRuleOrDecl::Declaration(decl) = child {
+      let mut nodes = decl.parsed_value();
+      value::walk_mut(&mut nodes, &mut |node| {
+        if let Node::Word { value, .. } = node {
+          if let Some((number, "px")) = value::unit(value) {
+            *value = format!("{}rem", number.parse::<f64>().unwrap() / 16.0).into();
+          }
+        }
+        true
+      });
+      decl.value = value::stringify(&nodes).into();
+    }
+  }
+  assert_eq!(
+    stringify(&root),
+    "a { margin: 0 1rem; background: url(16px.png) }"
+  );
+}
+
+fn word(value: &str, start: usize) -> Node<'_> {
+  Node::Word {
+    value: Cow::Borrowed(value),
+    range: start..start + value.len(),
+  }
+}
+
+fn space(value: &str, start: usize) -> Node<'_> {
+  Node::Space {
+    value: Cow::Borrowed(value),
+    range: start..start + value.len(),
+  }
+}
+
+#[test]
+fn parses_words_and_spaces() {
+  assert_eq!(
+    value::parse("1px  solid\t#fff"),
+    vec![
+      word("1px", 0),
+      space("  ", 3),
+      word("solid", 5),
+      space("\t", 10),
+      word("#fff", 11),
+    ]
+  );
+}
+
+#[test]
+fn parses_functions_with_divs() {
+  let input = "rgba( 0 , 0,0 / .5 ) var(--a)";
+  let nodes = value::parse(input);
+  assert_eq!(nodes.len(), 3);
+  match &nodes[0] {
+    Node::Function {
+      name,
+      before,
+      nodes,
+      after,
+      unclosed,
+      range,
+    } => {
+      assert_eq!(name, "rgba");
+      assert_eq!(before, " ");
+      assert_eq!(after, " ");
+      assert!(!unclosed);
+      assert_eq!(&input[range.clone()], "rgba( 0 , 0,0 / .5 )");
+      assert_eq!(
+        nodes
+          .iter()
+          .map(|node| match node {
+            Node::Div {
+              value,
+              before,
+              after,
+              ..
+            } => format!("div `{}{}{}`", before, value, after),
+            node => format!("{}", node),
+          })
+          .collect::<Vec<_>>(),
+        vec!["0", "div ` , `", "0", "div `,`", "0", "div ` / `", ".5"]
+      );
+    }
+    node => panic!("expected a function, found {:?}", node),
+  }
+  assert!(matches!(&nodes[2], Node::Function { name, .. } if name == "var"));
+}
+
+#[test]
+fn parses_strings_comments_and_unicode_ranges() {
+  let nodes = value::parse(r#""a \" b" 'c' /* d */ U+0025-00FF u+4??"#);
+  assert_eq!(
+    nodes
+      .iter()
+      .filter(|node| !matches!(node, Node::Space { .. }))
+      .cloned()
+      .collect::<Vec<_>>(),
+    vec![
+      Node::String {
+        value: Cow::Borrowed(r#"a \" b"#),
+        quote: '"',
+        unclosed: false,
+        range: 0..8,
+      },
+      Node::String {
+        value: Cow::Borrowed("c"),
+        quote: '\'',
+        unclosed: false,
+        range: 9..12,
+      },
+      Node::Comment {
+        value: Cow::Borrowed(" d "),
+        unclosed: false,
+        range: 13..20,
+      },
+      Node::UnicodeRange {
+        value: Cow::Borrowed("U+0025-00FF"),
+        range: 21..32,
+      },
+      Node::UnicodeRange {
+        value: Cow::Borrowed("u+4??"),
+        range: 33..38,
+      },
+    ]
+  );
+}
+
+#[test]
+fn keeps_unquoted_urls_as_one_word() {
+  let nodes = value::parse("url( a/b,c.png ) url('d.png')");
+  match &nodes[0] {
+    Node::Function {
+      before,
+      nodes,
+      after,
+      ..
+    } => {
+      assert_eq!((before.as_ref(), after.as_ref()), (" ", " "));
+      assert_eq!(nodes, &vec![word("a/b,c.png", 5)]);
+    }
+    node => panic!("expected a function, found {:?}", node),
+  }
+  match &nodes[2] {
+    Node::Function { nodes, .. } => {
+      assert!(matches!(&nodes[0], Node::String { value, .. } if value == "d.png"))
+    }
+    node => panic!("expected a function, found {:?}", node),
+  }
+}
+
+#[test]
+fn parses_unclosed_nodes() {
+  let nodes = value::parse("a(b 'c");
+  assert_eq!(value::stringify(&nodes), "a(b 'c");
+  match &nodes[0] {
+    Node::Function {
+      unclosed, nodes, ..
+    } => {
+      assert!(unclosed);
+      assert!(matches!(&nodes[2], Node::String { unclosed: true, .. }));
+    }
+    node => panic!("expected a function, found {:?}", node),
+  }
+}
+
+#[test]
+fn stringifies_what_it_parsed() {
+  for input in [
+    "",
+    "  a  ",
+    "calc( (100% - 2px) /3 )",
+    "a,b , c:d",
+    "url(\"x\") url( y )  /**/ \"unclosed",
+    "\\(a\\) ) f(",
+    "rgb(0 0 0 / 50%)",
+    "你好 'ü'",
+  ] {
+    let nodes = value::parse(input);
+    assert_eq!(value::stringify(&nodes), input);
+    for node in &nodes {
+      assert_eq!(&input[node.range()], node.to_string());
+    }
+  }
+}
+
+#[test]
+fn walk_visits_nested_nodes() {
+  let nodes = value::parse("a(b(c) d) e(f)");
+  let mut words = vec![];
+  value::walk(&nodes, &mut |node| match node {
+    Node::Word { value, .. } => {
+      words.push(value.to_string());
+      true
+    }
+    Node::Function { name, .. } => name != "e",
+    _ => true,
+  });
+  assert_eq!(words, vec!["c", "d"]);
+}
+
+#[test]
+fn walk_mut_rewrites_values() {
+  let mut nodes = value::parse("margin: 16px calc(32px + 1em)");
+  value::walk_mut(&mut nodes, &mut |node| {
+    if let Node::Word { value, .. } = node {
+      if let Some((number, "px")) = value::unit(value) {
+        *value = format!("{}rem", number.parse::<f64>().unwrap() / 16.0).into();
+      }
+    }
+    true
+  });
+  assert_eq!(value::stringify(&nodes), "margin: 1rem calc(2rem + 1em)");
+}
+
+#[test]
+fn unit_splits_numbers() {
+  assert_eq!(value::unit("-1.5e3px"), Some(("-1.5e3", "px")));
+  assert_eq!(value::unit("+.5%"), Some(("+.5", "%")));
+  assert_eq!(value::unit("1.em"), Some(("1", ".em")));
+  assert_eq!(value::unit("2e"), Some(("2", "e")));
+  assert_eq!(value::unit("-"), None);
+  assert_eq!(value::unit("."), None);
+  assert_eq!(value::unit("#1"), None);
+}
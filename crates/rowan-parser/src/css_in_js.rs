@@ -0,0 +1,337 @@
+//! Stylesheets in tagged template literals of `.js`, `.jsx`, `.ts` and `.tsx` files, as written
+//! with styled-components and emotion: `` styled.div`...` ``, `` styled(Link)`...` ``,
+//! `` css`...` ``, `` keyframes`...` `` and `` createGlobalStyle`...` ``.
+//!
+//! [`parse`] builds a `Document` like [`crate::document::parse`], with a `Root` for every
+//! template. The content is parsed with [`Parser::parse_nested`], so declarations and nested
+//! rules can be mixed. A `${expr}` interpolation is opaque: it is parsed as a comment where
+//! a statement can start, e.g. a mixin on its own line, and as a word everywhere else, but the
+//! tree keeps its original text, so ranges stay offsets into the host file. A template with a
+//! syntax error stays text, like a block of [`crate::document::parse_with_errors`].
+use crate::document::{self, range};
+use crate::parser::{ParseError, Parser};
+use crate::syntax::SyntaxNode;
+use rowan::{GreenNode, GreenNodeData, GreenToken, NodeOrToken, TextRange};
+
+/// A tagged template literal whose content is CSS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<'a> {
+  /// the tag expression in front of the template, e.g. `styled.button.attrs({})`
+  pub tag: &'a str,
+  /// where the CSS is in the host file, without the backticks around it
+  pub range: TextRange,
+  /// the `${expr}` interpolations in the template, including the `${` and `}`
+  pub interpolations: Vec<TextRange>,
+}
+
+/// Find every CSS template literal in `source`, in source order.
+///
+/// This is a scanner rather than a JavaScript parser: strings, comments, regular expressions and
+/// nested templates are skipped, and templates inside the interpolations of a CSS template are
+/// part of that template.
+pub fn extract(source: &str) -> Vec<Template<'_>> {
+  let mut scanner = Scanner {
+    source,
+    bytes: source.as_bytes(),
+    pos: 0,
+    templates: vec![],
+  };
+  scanner.script(false, true);
+  scanner.templates
+}
+
+/// Parse `source` into a `Document` with a `Root` for every CSS [`Template`].
+pub fn parse(source: &str) -> SyntaxNode {
+  parse_with_errors(source).0
+}
+
+/// Like [`parse`], along with the error of every template that stays text, at offsets into
+/// `source`.
+pub fn parse_with_errors(source: &str) -> (SyntaxNode, Vec<ParseError>) {
+  document::build(
+    source,
+    extract(source).into_iter().map(|template| {
+      let css = &source[template.range];
+      let start = usize::from(template.range.start());
+      let mut text = css.to_string();
+      let mut statement_end = None;
+      for interpolation in &template.interpolations {
+        let from = usize::from(interpolation.start()) - start;
+        let to = usize::from(interpolation.end()) - start;
+        let placeholder = if to - from >= 4 && stands_as_statement(&text, from, to, statement_end) {
+          statement_end = Some(to);
+          format!("/*{}*/", "_".repeat(to - from - 4))
+        } else {
+          "_".repeat(to - from)
+        };
+        text.replace_range(from..to, &placeholder);
+      }
+      // the placeholders keep the length of the interpolations, so error offsets stay right
+      let root = Parser::new(&text).try_parse_nested();
+      (
+        template.range,
+        root.map(|root| restore(&root.green(), css, &mut 0)),
+      )
+    }),
+  )
+}
+
+/// Print `document` with every root replaced by what `f` returns for it.
+///
+/// Backticks in the returned CSS are escaped, so the template still ends where it used to.
+pub fn stringify(document: &SyntaxNode, mut f: impl FnMut(&SyntaxNode) -> String) -> String {
+  let mut result = String::with_capacity(document.text_range().len().into());
+  for child in document.children_with_tokens() {
+    match child {
+      NodeOrToken::Token(token) => result.push_str(token.text()),
+      NodeOrToken::Node(root) => {
+        let mut escaped = false;
+        for ch in f(&root).chars() {
+          if ch == '`' && !escaped {
+            result.push('\\');
+          }
+          escaped = ch == '\\' && !escaped;
+          result.push(ch);
+        }
+      }
+    }
+  }
+  result
+}
+
+/// Whether the interpolation at `from..to` of `css` stands where a statement can, between a
+/// `{`, `}`, `;` or another statement and the end of its line or another interpolation.
+fn stands_as_statement(css: &str, from: usize, to: usize, statement_end: Option<usize>) -> bool {
+  let before = css[..from].trim_end();
+  let starts =
+    before.is_empty() || before.ends_with(['{', '}', ';']) || statement_end == Some(before.len());
+  let after = css[to..].trim_start_matches([' ', '\t']);
+  starts
+    && (after.is_empty() || after.starts_with(['\n', '\r', ';', '}']) || after.starts_with("${"))
+}
+
+/// A copy of `node` whose tokens have the text of `source` at their offset, which puts the
+/// interpolations back in place of their placeholders of the same length.
+fn restore(node: &GreenNodeData, source: &str, offset: &mut usize) -> GreenNode {
+  let children = node
+    .children()
+    .map(|child| match child {
+      NodeOrToken::Node(node) => NodeOrToken::Node(restore(node, source, offset)),
+      NodeOrToken::Token(token) => {
+        let start = *offset;
+        *offset += token.text().len();
+        NodeOrToken::Token(GreenToken::new(token.kind(), &source[start..*offset]))
+      }
+    })
+    .collect::<Vec<_>>();
+  GreenNode::new(node.kind(), children)
+}
+
+fn is_css_tag(tag: &str) -> bool {
+  let name = tag.split(['.', '(', '<']).next().unwrap_or_default();
+  match name {
+    "styled" => tag.len() > name.len(),
+    "css" | "keyframes" | "createGlobalStyle" => tag.len() == name.len(),
+    _ => false,
+  }
+}
+
+/// The keywords after which a `/` starts a regular expression rather than a division.
+const KEYWORDS_BEFORE_EXPRESSION: &[&str] = &[
+  "await",
+  "case",
+  "delete",
+  "do",
+  "else",
+  "in",
+  "instanceof",
+  "new",
+  "of",
+  "return",
+  "throw",
+  "typeof",
+  "void",
+  "yield",
+];
+
+fn is_identifier(b: u8) -> bool {
+  b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+}
+
+/// The tag expression that ends at `end`: identifiers, `.` and balanced `(...)` and `<...>`.
+fn tag_before(source: &str, end: usize) -> &str {
+  let bytes = source.as_bytes();
+  let mut start = end;
+  while start > 0 {
+    let b = bytes[start - 1];
+    if is_identifier(b) || b == b'.' {
+      start -= 1;
+      continue;
+    }
+    let open = match b {
+      b')' => b'(',
+      b'>' => b'<',
+      _ => break,
+    };
+    let mut depth = 0;
+    let matching = (0..start).rev().find(|&i| {
+      if bytes[i] == b {
+        depth += 1;
+      } else if bytes[i] == open {
+        depth -= 1;
+      }
+      depth == 0
+    });
+    match matching {
+      Some(i) => start = i,
+      None => break,
+    }
+  }
+  &source[start..end]
+}
+
+struct Scanner<'a> {
+  source: &'a str,
+  bytes: &'a [u8],
+  pos: usize,
+  templates: Vec<Template<'a>>,
+}
+
+impl<'a> Scanner<'a> {
+  /// Skip JavaScript up to the end of the source, or past the `}` that closes an interpolation.
+  fn script(&mut self, interpolation: bool, collect: bool) {
+    let mut depth = 0usize;
+    let mut regex_allowed = true;
+    while let Some(&b) = self.bytes.get(self.pos) {
+      match b {
+        b'/' if self.bytes.get(self.pos + 1) == Some(&b'/') => {
+          self.pos = memchr::memchr(b'\n', &self.bytes[self.pos..])
+            .map_or(self.bytes.len(), |i| self.pos + i);
+          continue;
+        }
+        b'/' if self.bytes.get(self.pos + 1) == Some(&b'*') => {
+          self.pos = memchr::memmem::find(&self.bytes[self.pos + 2..], b"*/")
+            .map_or(self.bytes.len(), |i| self.pos + 2 + i + 2);
+          continue;
+        }
+        b'/' if regex_allowed => {
+          self.regex();
+          regex_allowed = false;
+          continue;
+        }
+        b'\'' | b'"' => {
+          self.string(b);
+          regex_allowed = false;
+          continue;
+        }
+        b'`' => {
+          let tag = tag_before(self.source, self.pos);
+          self.template(tag, collect, collect && is_css_tag(tag));
+          regex_allowed = false;
+          continue;
+        }
+        b'{' => depth += 1,
+        b'}' if depth == 0 && interpolation => {
+          self.pos += 1;
+          return;
+        }
+        b'}' => depth = depth.saturating_sub(1),
+        _ if is_identifier(b) => {
+          let start = self.pos;
+          while self.bytes.get(self.pos).copied().is_some_and(is_identifier) {
+            self.pos += 1;
+          }
+          // `return /a/` divides nothing, `x.return / 2` does
+          regex_allowed = !self.source[..start].ends_with('.')
+            && KEYWORDS_BEFORE_EXPRESSION.contains(&&self.source[start..self.pos]);
+          continue;
+        }
+        _ => {}
+      }
+      if !b.is_ascii_whitespace() {
+        regex_allowed = matches!(
+          b,
+          b'(' | b',' | b'=' | b':' | b'[' | b'!' | b'&' | b'|' | b'?' | b'{' | b'}' | b';'
+        );
+      }
+      self.pos += 1;
+    }
+  }
+
+  /// Skip the template literal at the current backtick, and remember it if `css` is set.
+  /// Nothing is remembered inside it unless `collect` is set and it isn't CSS itself.
+  fn template(&mut self, tag: &'a str, collect: bool, css: bool) {
+    self.pos += 1;
+    let start = self.pos;
+    let mut interpolations = vec![];
+    while let Some(&b) = self.bytes.get(self.pos) {
+      match b {
+        b'\\' => self.pos += 2,
+        b'`' => {
+          if css {
+            self.templates.push(Template {
+              tag,
+              range: range(start, self.pos),
+              interpolations,
+            });
+          }
+          self.pos += 1;
+          return;
+        }
+        b'$' if self.bytes.get(self.pos + 1) == Some(&b'{') => {
+          let interpolation = self.pos;
+          self.pos += 2;
+          // templates in the interpolations of a CSS template are part of its placeholder
+          self.script(true, collect && !css);
+          interpolations.push(range(interpolation, self.pos.min(self.bytes.len())));
+        }
+        _ => self.pos += 1,
+      }
+    }
+    // unterminated template
+    self.pos = self.bytes.len();
+  }
+
+  fn string(&mut self, quote: u8) {
+    self.pos += 1;
+    while let Some(&b) = self.bytes.get(self.pos) {
+      match b {
+        b'\\' => self.pos += 2,
+        // an unterminated string ends at the line break
+        b'\n' => return,
+        _ if b == quote => {
+          self.pos += 1;
+          return;
+        }
+        _ => self.pos += 1,
+      }
+    }
+  }
+
+  fn regex(&mut self) {
+    self.pos += 1;
+    let mut class = false;
+    while let Some(&b) = self.bytes.get(self.pos) {
+      match b {
+        b'\\' => self.pos += 2,
+        b'\n' => return,
+        b'/' if !class => {
+          self.pos += 1;
+          // flags
+          while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_alphabetic)
+          {
+            self.pos += 1;
+          }
+          return;
+        }
+        _ => {
+          class = (class || b == b'[') && b != b']';
+          self.pos += 1;
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,289 @@
+//! Stylesheets embedded in HTML, Vue and Svelte files.
+//!
+//! [`parse`] builds a `Document` node over the whole host file: every `<style>` block and every
+//! `style` attribute becomes a `Root`, everything else is kept as `Unknown` tokens. Ranges of the
+//! tree are therefore offsets into the host file, and `document.to_string()` gives it back
+//! byte for byte, including edits made with [`crate::ted`]. [`stringify`] splices CSS produced
+//! some other way, e.g. by the formatter, back into the document. A `<style lang="scss">` or
+//! `<style lang="less">` block is parsed with nested rules and the tokenizer mode of its
+//! language, like the fences of [`crate::markdown`]. A block with a syntax error or in another
+//! language stays `Unknown` text, [`parse_with_errors`] reports why.
+use crate::parser::{ParseError, Parser};
+use crate::syntax::{Lang, SyntaxKind, SyntaxNode};
+use rowan::{GreenNode, GreenToken, Language, NodeOrToken, TextRange, TextSize};
+use tokenizer::Syntax;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+  /// the content of a `<style>` element, a stylesheet
+  Style,
+  /// the value of a `style` attribute, a declaration list
+  Attribute,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block<'a> {
+  pub kind: BlockKind,
+  /// where the CSS is in the host file, without the tags or quotes around it
+  pub range: TextRange,
+  /// the `lang` attribute of a `<style>` element, e.g. `scss` in Vue single-file components
+  pub lang: Option<&'a str>,
+}
+
+impl<'a> Block<'a> {
+  /// Whether the block is plain CSS.
+  pub fn is_css(&self) -> bool {
+    self.syntax() == Some(Syntax::Css)
+  }
+
+  /// The syntax of the block, `None` for a language [`parse`] doesn't read, like Stylus.
+  pub fn syntax(&self) -> Option<Syntax> {
+    match self.lang.map(str::to_ascii_lowercase).as_deref() {
+      None | Some("css" | "postcss") => Some(Syntax::Css),
+      Some("scss") => Some(Syntax::Scss),
+      Some("less") => Some(Syntax::Less),
+      Some(_) => None,
+    }
+  }
+}
+
+/// Find every `<style>` element and `style` attribute in `source`, in document order.
+///
+/// Comments and the content of `<script>`, `<textarea>` and `<title>` are skipped.
+/// Character references in attribute values are not decoded.
+pub fn extract(source: &str) -> Vec<Block<'_>> {
+  let bytes = source.as_bytes();
+  let mut blocks = vec![];
+  let mut pos = 0;
+  while let Some(i) = memchr::memchr(b'<', &bytes[pos..]) {
+    let start = pos + i;
+    if source[start..].starts_with("<!--") {
+      pos = memchr::memmem::find(&bytes[start + 4..], b"-->")
+        .map_or(bytes.len(), |end| start + 4 + end + 3);
+      continue;
+    }
+    if !bytes.get(start + 1).is_some_and(u8::is_ascii_alphabetic) {
+      // closing tags, `<!doctype>` and a `<` in text
+      pos = start + 1;
+      continue;
+    }
+    let name_end = scan(bytes, start + 1, |b| {
+      b.is_ascii_alphanumeric() || b == b'-' || b == b':'
+    });
+    let name = &source[start + 1..name_end];
+    let (attributes, tag_end) = attributes(source, name_end);
+
+    let mut lang = None;
+    for (attribute, value) in attributes {
+      if attribute.eq_ignore_ascii_case("style") {
+        if let Some(range) = value {
+          blocks.push(Block {
+            kind: BlockKind::Attribute,
+            range,
+            lang: None,
+          });
+        }
+      } else if attribute.eq_ignore_ascii_case("lang") {
+        lang = value.map(|range| &source[range]);
+      }
+    }
+
+    pos = tag_end;
+    if ["style", "script", "textarea", "title"]
+      .iter()
+      .any(|raw| name.eq_ignore_ascii_case(raw))
+    {
+      let end = find_closing_tag(source, tag_end, name);
+      if name.eq_ignore_ascii_case("style") {
+        blocks.push(Block {
+          kind: BlockKind::Style,
+          range: range(tag_end, end),
+          lang,
+        });
+      }
+      pos = end;
+    }
+  }
+  blocks
+}
+
+/// Parse `source` into a `Document` with a `Root` for every CSS, SCSS and Less [`Block`].
+pub fn parse(source: &str) -> SyntaxNode {
+  parse_with_errors(source).0
+}
+
+/// Like [`parse`], along with the error of every block that stays text, at offsets into
+/// `source`.
+pub fn parse_with_errors(source: &str) -> (SyntaxNode, Vec<ParseError>) {
+  build(
+    source,
+    extract(source).into_iter().map(|block| {
+      let css = &source[block.range];
+      let root = match (block.kind, block.syntax()) {
+        (BlockKind::Attribute, _) => Parser::new(css).try_parse_declarations(),
+        (BlockKind::Style, Some(Syntax::Css)) => Parser::new(css).try_parse(),
+        (BlockKind::Style, Some(syntax)) => Parser::with_syntax(css, syntax).try_parse_nested(),
+        (BlockKind::Style, None) => Err(ParseError {
+          message: format!("unsupported style language `{}`", block.lang.unwrap_or("")),
+          offset: 0,
+        }),
+      };
+      (block.range, root.map(|root| root.green().into_owned()))
+    }),
+  )
+}
+
+/// A `Document` of `roots` at their ranges in `source`, the text between them becomes
+/// `Unknown` tokens, and so does a root that failed to parse, whose error is returned at its
+/// offset in `source`. The ranges must be sorted and must not overlap.
+pub(crate) fn build(
+  source: &str,
+  roots: impl IntoIterator<Item = (TextRange, Result<GreenNode, ParseError>)>,
+) -> (SyntaxNode, Vec<ParseError>) {
+  let mut children = vec![];
+  let mut errors = vec![];
+  let mut pos = 0;
+  for (range, root) in roots {
+    let start = usize::from(range.start());
+    let root = match root {
+      Ok(root) => root,
+      Err(err) => {
+        errors.push(ParseError {
+          offset: start + err.offset,
+          ..err
+        });
+        continue;
+      }
+    };
+    if pos < start {
+      children.push(NodeOrToken::Token(unknown(&source[pos..start])));
+    }
+    children.push(NodeOrToken::Node(root));
+    pos = range.end().into();
+  }
+  if pos < source.len() {
+    children.push(NodeOrToken::Token(unknown(&source[pos..])));
+  }
+  let document = SyntaxNode::new_root(GreenNode::new(
+    Lang::kind_to_raw(SyntaxKind::Document),
+    children,
+  ));
+  (document, errors)
+}
+
+/// The CSS roots of a document, in document order.
+pub fn roots(document: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> {
+  document
+    .children()
+    .filter(|node| node.kind() == SyntaxKind::Root)
+}
+
+/// Whether `root` came from a `<style>` element or a `style` attribute.
+pub fn block_kind(root: &SyntaxNode) -> BlockKind {
+  // a `<style>` element's content always follows the `>` of its start tag
+  match root.prev_sibling_or_token() {
+    Some(NodeOrToken::Token(token)) if !token.text().ends_with('>') => BlockKind::Attribute,
+    _ => BlockKind::Style,
+  }
+}
+
+/// Print `document` with every root replaced by what `f` returns for it.
+///
+/// Quotes in the CSS of a quoted `style` attribute are escaped as character references,
+/// so the attribute still ends where it used to.
+pub fn stringify(document: &SyntaxNode, mut f: impl FnMut(&SyntaxNode) -> String) -> String {
+  let mut result = String::with_capacity(document.text_range().len().into());
+  for child in document.children_with_tokens() {
+    match child {
+      NodeOrToken::Token(token) => result.push_str(token.text()),
+      NodeOrToken::Node(root) => {
+        let css = f(&root);
+        if block_kind(&root) == BlockKind::Attribute {
+          match result.chars().last() {
+            Some('"') => result.push_str(&css.replace('"', "&quot;")),
+            Some('\'') => result.push_str(&css.replace('\'', "&#39;")),
+            _ => result.push_str(&css),
+          }
+        } else {
+          result.push_str(&css);
+        }
+      }
+    }
+  }
+  result
+}
+
+fn unknown(text: &str) -> GreenToken {
+  GreenToken::new(Lang::kind_to_raw(SyntaxKind::Unknown), text)
+}
+
+pub(crate) fn range(start: usize, end: usize) -> TextRange {
+  TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
+
+fn scan(bytes: &[u8], mut pos: usize, f: impl Fn(u8) -> bool) -> usize {
+  while pos < bytes.len() && f(bytes[pos]) {
+    pos += 1;
+  }
+  pos
+}
+
+type Attribute<'a> = (&'a str, Option<TextRange>);
+
+/// The attributes of the start tag whose name ends at `pos`, and the offset after its `>`.
+fn attributes(source: &str, mut pos: usize) -> (Vec<Attribute<'_>>, usize) {
+  let bytes = source.as_bytes();
+  let mut attributes = vec![];
+  loop {
+    pos = scan(bytes, pos, |b| b.is_ascii_whitespace() || b == b'/');
+    match bytes.get(pos) {
+      None => return (attributes, pos),
+      Some(b'>') => return (attributes, pos + 1),
+      _ => {}
+    }
+    let name_start = pos;
+    pos = scan(bytes, pos + 1, |b| {
+      !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/')
+    });
+    let name = &source[name_start..pos];
+    let after_name = scan(bytes, pos, |b| b.is_ascii_whitespace());
+    if bytes.get(after_name) != Some(&b'=') {
+      attributes.push((name, None));
+      continue;
+    }
+    pos = scan(bytes, after_name + 1, |b| b.is_ascii_whitespace());
+    let value = match bytes.get(pos) {
+      Some(&quote) if quote == b'"' || quote == b'\'' => {
+        let end = memchr::memchr(quote, &bytes[pos + 1..]).map_or(bytes.len(), |i| pos + 1 + i);
+        let value = range(pos + 1, end);
+        pos = (end + 1).min(bytes.len());
+        value
+      }
+      _ => {
+        let start = pos;
+        pos = scan(bytes, pos, |b| !b.is_ascii_whitespace() && b != b'>');
+        range(start, pos)
+      }
+    };
+    attributes.push((name, Some(value)));
+  }
+}
+
+/// The offset of the `</name` that closes a raw text element, or the end of `source`.
+fn find_closing_tag(source: &str, mut pos: usize, name: &str) -> usize {
+  let bytes = source.as_bytes();
+  while let Some(i) = memchr::memmem::find(&bytes[pos..], b"</") {
+    let start = pos + i;
+    let end = start + 2 + name.len();
+    if end <= bytes.len()
+      && bytes[start + 2..end].eq_ignore_ascii_case(name.as_bytes())
+      && !bytes
+        .get(end)
+        .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'-')
+    {
+      return start;
+    }
+    pos = start + 2;
+  }
+  bytes.len()
+}
@@ -0,0 +1,724 @@
+//! An opinionated, prettier-like formatter working on the lossless syntax tree.
+//!
+//! Statements (rules, at-rules, declarations and comments) are laid out one per line, while
+//! the tokens of selectors, values and at-rule params are normalized one by one, words split
+//! further with the [`SpecTokenizer`], so comments anywhere in the tree are kept where they
+//! were. Formatting is idempotent:
+//! `format(parse(format(x))) == format(x)`.
+//!
+//! [`format_range`] and [`format_on_type`] only touch the statements around a range and return
+//! minimal [`TextEdit`]s, which is what editors ask for with "format selection".
+use crate::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use crate::ted::semicolon_after;
+use rowan::{NodeOrToken, TextRange, TextSize};
+use tokenizer::list::comma_spans;
+use tokenizer::spec::{SpecTokenType, SpecTokenizer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+  Tab,
+  /// the number of spaces per level
+  Space(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+  Double,
+  Single,
+  /// keep every string as written
+  Preserve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+  pub indent_style: IndentStyle,
+  /// declarations longer than this with a comma separated value get one item per line
+  pub line_width: usize,
+  /// strings are only requoted when that doesn't need extra escaping
+  pub quote_style: QuoteStyle,
+  /// keep (at most one) blank line where the source had one or more
+  pub preserve_blank_lines: bool,
+  /// put every selector of a selector list on its own line
+  pub selector_per_line: bool,
+  /// `#FFF` → `#fff` in values
+  pub lowercase_hex: bool,
+  /// `(min-width:480px)` → `(min-width: 480px)`, collapse whitespace in at-rule params
+  pub normalize_at_rule_params: bool,
+}
+
+impl Default for FormatOptions {
+  fn default() -> Self {
+    Self {
+      indent_style: IndentStyle::Space(2),
+      line_width: 80,
+      quote_style: QuoteStyle::Double,
+      preserve_blank_lines: true,
+      selector_per_line: true,
+      lowercase_hex: true,
+      normalize_at_rule_params: true,
+    }
+  }
+}
+
+/// Format `node`, usually a `Root`. Any other statement is formatted as if it was at the top level.
+pub fn format(node: &SyntaxNode, options: FormatOptions) -> String {
+  let mut formatter = Formatter::new(options);
+  if node.kind() == SyntaxKind::Root {
+    formatter.statements(node, None);
+  } else {
+    formatter.statement(node);
+  }
+  formatter.out
+}
+
+/// Replace `range` of the original text with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+  pub range: TextRange,
+  pub new_text: String,
+}
+
+impl TextEdit {
+  /// Apply non-overlapping `edits` to `text`.
+  pub fn apply(text: &str, edits: &[TextEdit]) -> String {
+    let mut edits = edits.iter().collect::<Vec<_>>();
+    edits.sort_by_key(|edit| edit.range.start());
+    let mut result = text.to_string();
+    for edit in edits.into_iter().rev() {
+      result.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.new_text);
+    }
+    result
+  }
+}
+
+/// Format the smallest set of statements covering `range`.
+///
+/// A range inside a single declaration or selector formats that declaration or rule, a range
+/// spanning several statements of a block formats just those, never their siblings.
+pub fn format_range(root: &SyntaxNode, range: TextRange, options: FormatOptions) -> Vec<TextEdit> {
+  match covering_statements(root, range) {
+    Some((parent, statements)) => format_statements(root, &parent, &statements, options),
+    None => vec![],
+  }
+}
+
+/// Format the statement finished by typing `ch` (`}` or `;`) right before `offset`.
+pub fn format_on_type(
+  root: &SyntaxNode,
+  offset: TextSize,
+  ch: char,
+  options: FormatOptions,
+) -> Vec<TextEdit> {
+  let kind = match ch {
+    '}' => SyntaxKind::CloseCurly,
+    ';' => SyntaxKind::Semicolon,
+    _ => return vec![],
+  };
+  let token = match root
+    .token_at_offset(offset)
+    .find(|token| token.kind() == kind && token.text_range().end() == offset)
+  {
+    Some(token) => token,
+    None => return vec![],
+  };
+  let parent = match token.parent() {
+    Some(parent) => parent,
+    None => return vec![],
+  };
+  let is_block = parent
+    .children_with_tokens()
+    .any(|element| element.kind() == SyntaxKind::OpenCurly);
+  let statement = match kind {
+    SyntaxKind::CloseCurly => Some(parent),
+    // `@import "a.css";` and alike
+    _ if parent.kind() == SyntaxKind::AtRule && !is_block => Some(parent),
+    _ => previous_declaration(&token),
+  };
+  match statement {
+    Some(statement) => format_range(root, statement.text_range(), options),
+    None => vec![],
+  }
+}
+
+fn previous_declaration(semicolon: &SyntaxToken) -> Option<SyntaxNode> {
+  let mut prev = semicolon.prev_sibling_or_token();
+  while let Some(element) = prev {
+    match element {
+      NodeOrToken::Node(node) if node.kind() == SyntaxKind::Declaration => return Some(node),
+      NodeOrToken::Token(token) if token.kind() == SyntaxKind::Space => {
+        prev = token.prev_sibling_or_token();
+      }
+      _ => return None,
+    }
+  }
+  None
+}
+
+/// the statements of a block (or the root) which `range` touches
+fn statements_in(parent: &SyntaxNode, range: TextRange) -> Vec<SyntaxNode> {
+  parent
+    .children()
+    .filter(|node| node.kind().is_statement())
+    .filter(|node| node.text_range().intersect(range).is_some())
+    .collect()
+}
+
+fn covering_statements(
+  root: &SyntaxNode,
+  range: TextRange,
+) -> Option<(SyntaxNode, Vec<SyntaxNode>)> {
+  let range = range.intersect(root.text_range())?;
+  let mut node = match root.covering_element(range) {
+    NodeOrToken::Node(node) => node,
+    NodeOrToken::Token(token) => token.parent()?,
+  };
+  loop {
+    match node.kind() {
+      SyntaxKind::Root => {
+        let statements = statements_in(&node, range);
+        return if statements.is_empty() {
+          None
+        } else {
+          Some((node, statements))
+        };
+      }
+      SyntaxKind::Rule | SyntaxKind::AtRule => {
+        let open = node
+          .children_with_tokens()
+          .find(|element| element.kind() == SyntaxKind::OpenCurly);
+        if let Some(open) = open {
+          if range.start() >= open.text_range().end() {
+            let statements = statements_in(&node, range);
+            if !statements.is_empty() {
+              return Some((node, statements));
+            }
+          }
+        }
+        return Some((node.parent()?, vec![node]));
+      }
+      SyntaxKind::Declaration | SyntaxKind::Comment => return Some((node.parent()?, vec![node])),
+      _ => node = node.parent()?,
+    }
+  }
+}
+
+fn format_statements(
+  root: &SyntaxNode,
+  parent: &SyntaxNode,
+  statements: &[SyntaxNode],
+  options: FormatOptions,
+) -> Vec<TextEdit> {
+  let first = &statements[0];
+  let last = &statements[statements.len() - 1];
+  let mut start = first.text_range().start();
+  let end = match semicolon_after(last) {
+    Some(semicolon) if last.kind() == SyntaxKind::Declaration => semicolon.text_range().end(),
+    _ => last.text_range().end(),
+  };
+
+  let mut formatter = Formatter::new(options);
+  formatter.level = first
+    .ancestors()
+    .skip(1)
+    .filter(|node| matches!(node.kind(), SyntaxKind::Rule | SyntaxKind::AtRule))
+    .count();
+  formatter.statements(parent, Some(TextRange::new(start, end)));
+  let indent = formatter.indent(formatter.level);
+  let mut new_text = formatter.out;
+  new_text.pop();
+
+  match first.prev_sibling_or_token() {
+    // the statement starts its line, so its indentation is formatted too
+    Some(NodeOrToken::Token(space))
+      if space.kind() == SyntaxKind::Space && space.text().contains('\n') =>
+    {
+      let newline = space.text().rfind('\n').unwrap() + 1;
+      start = space.text_range().start() + TextSize::from(newline as u32);
+    }
+    _ => {
+      new_text = new_text[indent.len()..].to_string();
+    }
+  }
+
+  let old_text = root.text().slice(TextRange::new(start, end)).to_string();
+  diff(start, &old_text, &new_text)
+}
+
+/// a line based diff of `old` (found at `offset`) and `new`, every hunk trimmed to the characters
+/// which really differ
+fn diff(offset: TextSize, old: &str, new: &str) -> Vec<TextEdit> {
+  // bigger inputs are compared as a whole instead of line by line
+  const MAX_TABLE_SIZE: usize = 1 << 22;
+  let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+  let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+  let (n, m) = (old_lines.len(), new_lines.len());
+
+  // (old lines, new lines) pairs of changed hunks
+  let mut hunks: Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> = vec![];
+  if (n + 1) * (m + 1) > MAX_TABLE_SIZE {
+    hunks.push((0..n, 0..m));
+  } else {
+    // lcs[i][j] is the length of the longest common subsequence of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+      for j in (0..m).rev() {
+        lcs[i][j] = if old_lines[i] == new_lines[j] {
+          lcs[i + 1][j + 1] + 1
+        } else {
+          lcs[i + 1][j].max(lcs[i][j + 1])
+        };
+      }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut hunk: Option<(usize, usize)> = None;
+    while i < n || j < m {
+      if i < n && j < m && old_lines[i] == new_lines[j] {
+        if let Some((old_start, new_start)) = hunk.take() {
+          hunks.push((old_start..i, new_start..j));
+        }
+        i += 1;
+        j += 1;
+        continue;
+      }
+      hunk.get_or_insert((i, j));
+      if j >= m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+        i += 1;
+      } else {
+        j += 1;
+      }
+    }
+    if let Some((old_start, new_start)) = hunk {
+      hunks.push((old_start..n, new_start..m));
+    }
+  }
+
+  let mut line_offsets = vec![0usize];
+  for line in &old_lines {
+    line_offsets.push(line_offsets[line_offsets.len() - 1] + line.len());
+  }
+  hunks
+    .into_iter()
+    .filter_map(|(old_range, new_range)| {
+      let old_start = line_offsets[old_range.start];
+      let old_text = &old[old_start..line_offsets[old_range.end]];
+      let new_text = new_lines[new_range].concat();
+      let prefix = old_text
+        .char_indices()
+        .zip(new_text.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| old_text.len().min(new_text.len()));
+      let suffix = old_text[prefix..]
+        .chars()
+        .rev()
+        .zip(new_text[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+      if old_text.len() == new_text.len() && prefix == old_text.len() {
+        return None;
+      }
+      let start = offset + TextSize::from((old_start + prefix) as u32);
+      let end = offset + TextSize::from((old_start + old_text.len() - suffix) as u32);
+      Some(TextEdit {
+        range: TextRange::new(start, end),
+        new_text: new_text[prefix..new_text.len() - suffix].to_string(),
+      })
+    })
+    .collect()
+}
+
+struct Formatter {
+  options: FormatOptions,
+  out: String,
+  level: usize,
+}
+
+impl Formatter {
+  fn new(options: FormatOptions) -> Self {
+    Self {
+      options,
+      out: String::new(),
+      level: 0,
+    }
+  }
+
+  fn indent(&self, level: usize) -> String {
+    match self.options.indent_style {
+      IndentStyle::Tab => "\t".repeat(level),
+      IndentStyle::Space(width) => " ".repeat(width * level),
+    }
+  }
+
+  fn line(&mut self, text: &str) {
+    let indent = self.indent(self.level);
+    self.out.push_str(&indent);
+    self.out.push_str(text);
+    self.out.push('\n');
+  }
+
+  /// format the statements of a `Root`, or those between the curly brackets of a `Rule`/`AtRule`,
+  /// optionally only the ones inside of `range`
+  fn statements(&mut self, parent: &SyntaxNode, range: Option<TextRange>) {
+    let in_block = parent.kind() != SyntaxKind::Root;
+    let mut inside = !in_block;
+    let mut first = true;
+    let mut newlines = 0;
+    for child in parent.children_with_tokens() {
+      match child {
+        NodeOrToken::Token(token) => match token.kind() {
+          SyntaxKind::OpenCurly => inside = true,
+          SyntaxKind::CloseCurly => inside = false,
+          SyntaxKind::Space if inside => newlines += token.text().matches('\n').count(),
+          _ => {}
+        },
+        NodeOrToken::Node(node) if inside => {
+          if matches!(range, Some(range) if !range.contains_range(node.text_range())) {
+            continue;
+          }
+          if node.kind() == SyntaxKind::Comment && !first && newlines == 0 {
+            // a comment trailing on the line of the previous statement stays there
+            self.out.pop();
+            self.out.push(' ');
+            self.out.push_str(&node.to_string());
+            self.out.push('\n');
+          } else {
+            if !first && newlines > 1 && self.options.preserve_blank_lines {
+              self.out.push('\n');
+            }
+            self.statement(&node);
+          }
+          first = false;
+          newlines = 0;
+        }
+        NodeOrToken::Node(_) => {}
+      }
+    }
+  }
+
+  fn statement(&mut self, node: &SyntaxNode) {
+    match node.kind() {
+      SyntaxKind::Rule => self.rule(node),
+      SyntaxKind::AtRule => self.at_rule(node),
+      SyntaxKind::Declaration => self.declaration(node),
+      _ => self.line(node.to_string().trim()),
+    }
+  }
+
+  fn block(&mut self, node: &SyntaxNode, head: &str) {
+    self.line(&format!("{} {{", head));
+    self.level += 1;
+    self.statements(node, None);
+    self.level -= 1;
+    self.line("}");
+  }
+
+  fn rule(&mut self, node: &SyntaxNode) {
+    let selector = node
+      .children()
+      .find(|child| child.kind() == SyntaxKind::Selector)
+      .map(|selector| normalize(&selector, Context::Selector, &self.options))
+      .unwrap_or_default();
+    let head = if self.options.selector_per_line {
+      comma_spans(&selector)
+        .map(|(selector, _)| selector)
+        .collect::<Vec<_>>()
+        .join(&format!(",\n{}", self.indent(self.level)))
+    } else {
+      selector
+    };
+    self.block(node, &head);
+  }
+
+  fn at_rule(&mut self, node: &SyntaxNode) {
+    let mut head = String::new();
+    let mut has_block = false;
+    for child in node.children_with_tokens() {
+      match child {
+        NodeOrToken::Token(token) => match token.kind() {
+          SyntaxKind::AtWord => head.push_str(token.text()),
+          SyntaxKind::Comment => {
+            head.push(' ');
+            head.push_str(token.text());
+          }
+          SyntaxKind::OpenCurly => has_block = true,
+          _ => {}
+        },
+        NodeOrToken::Node(params) if params.kind() == SyntaxKind::Params => {
+          let params = if self.options.normalize_at_rule_params {
+            normalize(&params, Context::Params, &self.options)
+          } else {
+            params.to_string().trim().to_string()
+          };
+          if !params.is_empty() {
+            head.push(' ');
+            head.push_str(&params);
+          }
+        }
+        NodeOrToken::Node(_) => {}
+      }
+      if has_block {
+        break;
+      }
+    }
+    if has_block {
+      self.block(node, &head);
+    } else {
+      self.line(&format!("{};", head));
+    }
+  }
+
+  fn declaration(&mut self, node: &SyntaxNode) {
+    let mut head = String::new();
+    let mut value = None;
+    for child in node.children_with_tokens() {
+      match child {
+        NodeOrToken::Token(token) => match token.kind() {
+          SyntaxKind::Colon => head.push(':'),
+          SyntaxKind::Comment => {
+            head.push(' ');
+            head.push_str(token.text());
+          }
+          _ => {}
+        },
+        NodeOrToken::Node(child) => match child.kind() {
+          SyntaxKind::Prop => head.push_str(child.to_string().trim()),
+          SyntaxKind::Value => value = Some(child),
+          _ => {}
+        },
+      }
+    }
+    let value = match value {
+      // custom properties may hold anything, leave them as written
+      Some(value) if head.starts_with("--") => value.to_string().trim().to_string(),
+      Some(value) => normalize(&value, Context::Value, &self.options),
+      None => String::new(),
+    };
+
+    let width =
+      self.indent(self.level).chars().count() + head.chars().count() + value.chars().count() + 2;
+    let items = comma_spans(&value)
+      .map(|(item, _)| item)
+      .collect::<Vec<_>>();
+    if width > self.options.line_width && items.len() > 1 && !head.starts_with("--") {
+      self.line(&head);
+      self.level += 1;
+      let last = items.len() - 1;
+      for (i, item) in items.into_iter().enumerate() {
+        self.line(&format!("{}{}", item, if i == last { ";" } else { "," }));
+      }
+      self.level -= 1;
+    } else if value.is_empty() {
+      self.line(&format!("{};", head));
+    } else {
+      self.line(&format!("{} {};", head, value));
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+  Selector,
+  Value,
+  Params,
+}
+
+fn requote(string: &str, style: QuoteStyle) -> String {
+  let quote = match style {
+    QuoteStyle::Double => '"',
+    QuoteStyle::Single => '\'',
+    QuoteStyle::Preserve => return string.to_string(),
+  };
+  let original = string.chars().next().unwrap();
+  if original == quote || string.len() < 2 || !string.ends_with(original) {
+    return string.to_string();
+  }
+  let content = &string[1..string.len() - 1];
+  if content.contains(quote) {
+    return string.to_string();
+  }
+  format!("{}{}{}", quote, content, quote)
+}
+
+/// Collapse whitespace and normalize the spacing of a selector list, a value or at-rule params.
+/// Strings, comments and `url()` contents are copied (apart from requoting) as they are.
+fn normalize(node: &SyntaxNode, context: Context, options: &FormatOptions) -> String {
+  let mut normalizer = Normalizer {
+    context,
+    options,
+    out: String::new(),
+    space: false,
+    functions: vec![],
+    square: 0,
+    ident: None,
+    important: false,
+  };
+  for token in node
+    .descendants_with_tokens()
+    .filter_map(NodeOrToken::into_token)
+  {
+    normalizer.token(&token);
+  }
+  normalizer.out
+}
+
+struct Normalizer<'a> {
+  context: Context,
+  options: &'a FormatOptions,
+  out: String,
+  /// a run of whitespace which still has to be written as a single space
+  space: bool,
+  /// one entry per enclosing parentheses, `true` for `selector(…)`
+  functions: Vec<bool>,
+  square: usize,
+  /// the identifier just written, the name of a function if `(` follows
+  ident: Option<String>,
+  /// after a `!`, the whitespace up to `important` is dropped
+  important: bool,
+}
+
+impl<'a> Normalizer<'a> {
+  fn flush(&mut self) {
+    if self.space && !self.out.is_empty() && !self.out.ends_with('(') && !self.out.ends_with('[')
+    {
+      self.out.push(' ');
+    }
+    self.space = false;
+  }
+
+  fn push(&mut self, text: &str) {
+    self.flush();
+    self.out.push_str(text);
+  }
+
+  fn token(&mut self, token: &SyntaxToken) {
+    match token.kind() {
+      SyntaxKind::Space => {
+        if !self.important {
+          // no space around the operators of attribute selectors
+          self.space = !(self.square > 0 && self.out.ends_with('='));
+        }
+        return;
+      }
+      SyntaxKind::String => self.push(&requote(token.text(), self.options.quote_style)),
+      SyntaxKind::Comment => self.push(token.text()),
+      SyntaxKind::Brackets if self.ident.as_deref() == Some("url") && !self.space => {
+        // unquoted urls are copied verbatim
+        let content = &token.text()[1..token.text().len() - 1];
+        self.out.push('(');
+        self.out.push_str(content.trim());
+        self.out.push(')');
+      }
+      _ => {
+        let text = token.text();
+        let tokens = SpecTokenizer::new(text).collect::<Vec<_>>();
+        for (i, spec) in tokens.iter().enumerate() {
+          let next = tokens.get(i + 1).map(|next| &next.0);
+          self.spec(&spec.0, &text[spec.1..spec.2], next);
+        }
+        return;
+      }
+    }
+    self.ident = None;
+    self.important = false;
+  }
+
+  fn spec(&mut self, kind: &SpecTokenType<'_>, text: &str, next: Option<&SpecTokenType<'_>>) {
+    let context = self.context;
+    let ident = self.ident.take();
+    let important = std::mem::take(&mut self.important);
+    match kind {
+      SpecTokenType::Whitespace => {
+        self.important = important;
+        if !important {
+          self.space = !(self.square > 0 && self.out.ends_with('='));
+        }
+      }
+      SpecTokenType::Ident if important => {
+        self.out.push_str(&text.to_ascii_lowercase());
+      }
+      SpecTokenType::Ident => {
+        self.push(text);
+        self.ident = Some(text.to_ascii_lowercase());
+      }
+      SpecTokenType::Function => {
+        let name = text[..text.len() - 1].to_ascii_lowercase();
+        self.push(text);
+        self.functions.push(name == "selector");
+      }
+      SpecTokenType::Url => {
+        let content = &text[4..text.len() - 1];
+        self.push(&text[..4]);
+        self.out.push_str(content.trim());
+        self.out.push(')');
+      }
+      SpecTokenType::OpenParentheses => {
+        let selector = !self.space && ident.as_deref() == Some("selector");
+        self.push("(");
+        self.functions.push(selector);
+      }
+      SpecTokenType::CloseParentheses => {
+        self.space = false;
+        self.out.push(')');
+        self.functions.pop();
+      }
+      SpecTokenType::OpenSquare => {
+        self.push("[");
+        self.square += 1;
+      }
+      SpecTokenType::CloseSquare => {
+        self.space = false;
+        self.out.push(']');
+        self.square = self.square.saturating_sub(1);
+      }
+      SpecTokenType::Comma => {
+        self.out.push(',');
+        self.space = true;
+      }
+      SpecTokenType::Delim('=') if self.square > 0 => {
+        self.space = false;
+        self.out.push('=');
+      }
+      SpecTokenType::Delim(ch @ ('~' | '|' | '^' | '$' | '*'))
+        if self.square > 0 && next == Some(&SpecTokenType::Delim('=')) =>
+      {
+        self.space = false;
+        self.out.push(*ch);
+      }
+      SpecTokenType::Colon
+        if context == Context::Params
+          && !self.functions.is_empty()
+          && !self.functions.contains(&true)
+          && self.square == 0 =>
+      {
+        self.out.push(':');
+        self.space = true;
+      }
+      SpecTokenType::Delim(ch @ ('>' | '+' | '~'))
+        if context == Context::Selector && self.functions.is_empty() && self.square == 0 =>
+      {
+        if !self.out.is_empty() {
+          self.out.push(' ');
+        }
+        self.out.push(*ch);
+        self.space = true;
+      }
+      SpecTokenType::Delim('!') if context == Context::Value => {
+        if !self.out.is_empty() {
+          self.space = true;
+        }
+        self.push("!");
+        self.important = true;
+      }
+      SpecTokenType::Hash { .. } if context == Context::Value && self.options.lowercase_hex => {
+        let hex = &text[1..];
+        if matches!(hex.len(), 3 | 4 | 6 | 8) && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+          self.push(&text.to_ascii_lowercase());
+        } else {
+          self.push(text);
+        }
+      }
+      _ => self.push(text),
+    }
+  }
+}
@@ -0,0 +1,918 @@
+//! Semantic highlighting of a stylesheet, classifying the interesting parts of selectors,
+//! declarations and at-rules. The ranges are sorted and never overlap, so they can be turned into
+//! LSP semantic tokens or rendered as HTML with [`highlight_as_html`]. Selectors and values are
+//! classified by their [`SpecTokenizer`] tokens.
+use crate::syntax::{SyntaxKind, SyntaxNode};
+use rowan::{NodeOrToken, TextRange, TextSize};
+use tokenizer::spec::{SpecToken, SpecTokenType, SpecTokenizer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightTag {
+  Comment,
+  /// `.class`
+  SelectorClass,
+  /// `#id`
+  SelectorId,
+  /// `div`, `*`
+  SelectorTag,
+  /// `:hover`, `::before`
+  SelectorPseudo,
+  /// `[type="text"]`
+  SelectorAttribute,
+  /// a standard property like `color`
+  Property,
+  /// a property this crate doesn't know about
+  UnknownProperty,
+  /// `--custom`
+  CustomProperty,
+  /// `@media`
+  AtRule,
+  /// `12px`, `50%`, `1.5`
+  Number,
+  /// `#fff`, `red`
+  Color,
+  String,
+  /// `url(…)`
+  Url,
+  /// the name of a function call like `rgba`
+  Function,
+  /// `!important`
+  Important,
+}
+
+impl HighlightTag {
+  /// the name used as CSS class by [`highlight_as_html`]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      HighlightTag::Comment => "comment",
+      HighlightTag::SelectorClass => "selector-class",
+      HighlightTag::SelectorId => "selector-id",
+      HighlightTag::SelectorTag => "selector-tag",
+      HighlightTag::SelectorPseudo => "selector-pseudo",
+      HighlightTag::SelectorAttribute => "selector-attribute",
+      HighlightTag::Property => "property",
+      HighlightTag::UnknownProperty => "property-unknown",
+      HighlightTag::CustomProperty => "property-custom",
+      HighlightTag::AtRule => "at-rule",
+      HighlightTag::Number => "number",
+      HighlightTag::Color => "color",
+      HighlightTag::String => "string",
+      HighlightTag::Url => "url",
+      HighlightTag::Function => "function",
+      HighlightTag::Important => "important",
+    }
+  }
+}
+
+impl std::fmt::Display for HighlightTag {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightedRange {
+  pub range: TextRange,
+  pub tag: HighlightTag,
+}
+
+pub fn highlight(root: &SyntaxNode) -> Vec<HighlightedRange> {
+  let mut ranges = Highlighter { ranges: vec![] };
+  for element in root.descendants_with_tokens() {
+    match element {
+      NodeOrToken::Token(token) => match token.kind() {
+        SyntaxKind::Comment => ranges.push(token.text_range(), HighlightTag::Comment),
+        SyntaxKind::AtWord
+          if token.parent().map(|node| node.kind()) == Some(SyntaxKind::AtRule) =>
+        {
+          ranges.push(token.text_range(), HighlightTag::AtRule)
+        }
+        _ => {}
+      },
+      NodeOrToken::Node(node) => {
+        let text = node.to_string();
+        let base = node.text_range().start();
+        match node.kind() {
+          SyntaxKind::Prop => ranges.property(&text, base),
+          SyntaxKind::Selector => ranges.selector(&text, base),
+          SyntaxKind::Value | SyntaxKind::Params => ranges.value(&text, base),
+          _ => {}
+        }
+      }
+    }
+  }
+  let mut ranges = ranges.ranges;
+  ranges.sort_by_key(|range| range.range.start());
+  // the CST and the spec tokens can disagree on `url(a/**/)` and alike, the first range wins
+  let mut end = TextSize::from(0);
+  ranges.retain(|range| {
+    let keep = range.range.start() >= end;
+    if keep {
+      end = range.range.end();
+    }
+    keep
+  });
+  ranges
+}
+
+/// Render the source of `root` as HTML, every highlighted range wrapped in a
+/// `<span class="…">` named after [`HighlightTag::as_str`].
+pub fn highlight_as_html(root: &SyntaxNode) -> String {
+  let text = root.to_string();
+  let mut html = String::with_capacity(text.len() * 2);
+  let mut pos = 0;
+  for HighlightedRange { range, tag } in highlight(root) {
+    let range = std::ops::Range::<usize>::from(range);
+    escape(&mut html, &text[pos..range.start]);
+    html.push_str("<span class=\"");
+    html.push_str(tag.as_str());
+    html.push_str("\">");
+    escape(&mut html, &text[range.clone()]);
+    html.push_str("</span>");
+    pos = range.end;
+  }
+  escape(&mut html, &text[pos..]);
+  html
+}
+
+fn escape(html: &mut String, text: &str) {
+  for ch in text.chars() {
+    match ch {
+      '<' => html.push_str("&lt;"),
+      '>' => html.push_str("&gt;"),
+      '&' => html.push_str("&amp;"),
+      '"' => html.push_str("&quot;"),
+      _ => html.push(ch),
+    }
+  }
+}
+
+struct Highlighter {
+  ranges: Vec<HighlightedRange>,
+}
+
+impl Highlighter {
+  fn push(&mut self, range: TextRange, tag: HighlightTag) {
+    self.ranges.push(HighlightedRange { range, tag });
+  }
+
+  fn push_at(&mut self, base: TextSize, start: usize, end: usize, tag: HighlightTag) {
+    let range = TextRange::new(
+      base + TextSize::from(start as u32),
+      base + TextSize::from(end as u32),
+    );
+    self.push(range, tag);
+  }
+
+  fn property(&mut self, text: &str, base: TextSize) {
+    let prop = text.trim_end();
+    let tag = if prop.starts_with("--") {
+      HighlightTag::CustomProperty
+    } else if is_known_property(prop) {
+      HighlightTag::Property
+    } else {
+      HighlightTag::UnknownProperty
+    };
+    self.push_at(base, 0, prop.len(), tag);
+  }
+
+  fn selector(&mut self, text: &str, base: TextSize) {
+    let tokens = SpecTokenizer::new(text).collect::<Vec<_>>();
+    let mut i = 0;
+    while i < tokens.len() {
+      let SpecToken(kind, start, end) = &tokens[i];
+      let (start, mut end) = (*start, *end);
+      i += 1;
+      let tag = match kind {
+        SpecTokenType::String => HighlightTag::String,
+        SpecTokenType::Delim('.') => match tokens.get(i) {
+          Some(SpecToken(SpecTokenType::Ident, ident_start, ident_end)) if *ident_start == end => {
+            end = *ident_end;
+            i += 1;
+            HighlightTag::SelectorClass
+          }
+          _ => continue,
+        },
+        SpecTokenType::Hash { .. } => HighlightTag::SelectorId,
+        SpecTokenType::Colon => {
+          if let Some(SpecToken(SpecTokenType::Colon, _, colon_end)) = tokens.get(i) {
+            end = *colon_end;
+            i += 1;
+          }
+          match tokens.get(i) {
+            Some(SpecToken(SpecTokenType::Ident, _, ident_end)) => {
+              end = *ident_end;
+              i += 1;
+            }
+            Some(SpecToken(SpecTokenType::Function, _, function_end)) => {
+              end = function_end - 1;
+              i += 1;
+              let name = text[start..end]
+                .trim_start_matches(':')
+                .to_ascii_lowercase();
+              let takes_selectors = matches!(
+                name.as_str(),
+                "not"
+                  | "is"
+                  | "where"
+                  | "has"
+                  | "matches"
+                  | "-webkit-any"
+                  | "-moz-any"
+                  | "host"
+                  | "host-context"
+                  | "slotted"
+                  | "global"
+                  | "local"
+              );
+              if !takes_selectors {
+                // `:nth-child(2n + 1)` and alike, the arguments are no selectors
+                i = block_end(&tokens, i);
+              }
+            }
+            _ => {}
+          }
+          HighlightTag::SelectorPseudo
+        }
+        SpecTokenType::OpenSquare => {
+          i = block_end(&tokens, i);
+          end = tokens[i - 1].2;
+          HighlightTag::SelectorAttribute
+        }
+        SpecTokenType::Delim('*') | SpecTokenType::Ident => HighlightTag::SelectorTag,
+        // keyframe selectors like `50%`
+        SpecTokenType::Number { .. }
+        | SpecTokenType::Percentage { .. }
+        | SpecTokenType::Dimension { .. } => HighlightTag::Number,
+        _ => continue,
+      };
+      self.push_at(base, start, end, tag);
+    }
+  }
+
+  fn value(&mut self, text: &str, base: TextSize) {
+    let tokens = SpecTokenizer::new(text).collect::<Vec<_>>();
+    let mut i = 0;
+    while i < tokens.len() {
+      let token = &tokens[i];
+      let (start, mut end) = (token.1, token.2);
+      i += 1;
+      let tag = match token.0 {
+        SpecTokenType::String => HighlightTag::String,
+        SpecTokenType::Hash { .. } => {
+          let hex = token.value(text);
+          if !matches!(hex.len(), 3 | 4 | 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            continue;
+          }
+          HighlightTag::Color
+        }
+        SpecTokenType::Delim('!') => {
+          while matches!(
+            tokens.get(i),
+            Some(SpecToken(SpecTokenType::Whitespace, ..))
+          ) {
+            i += 1;
+          }
+          match tokens.get(i) {
+            Some(ident @ SpecToken(SpecTokenType::Ident, _, ident_end))
+              if ident.value(text).eq_ignore_ascii_case("important") =>
+            {
+              end = *ident_end;
+              i += 1;
+              HighlightTag::Important
+            }
+            _ => continue,
+          }
+        }
+        SpecTokenType::Number { .. }
+        | SpecTokenType::Percentage { .. }
+        | SpecTokenType::Dimension { .. } => HighlightTag::Number,
+        SpecTokenType::Url => HighlightTag::Url,
+        SpecTokenType::Function if token.value(text).eq_ignore_ascii_case("url") => {
+          i = block_end(&tokens, i);
+          end = tokens[i - 1].2;
+          HighlightTag::Url
+        }
+        SpecTokenType::Function => {
+          end -= 1;
+          HighlightTag::Function
+        }
+        SpecTokenType::Ident
+          if NAMED_COLORS
+            .binary_search(&token.value(text).to_ascii_lowercase().as_str())
+            .is_ok() =>
+        {
+          HighlightTag::Color
+        }
+        _ => continue,
+      };
+      self.push_at(base, start, end, tag);
+    }
+  }
+}
+
+/// The index after the token closing the block whose opening token is right before `i`, or the
+/// number of tokens if it isn't closed.
+fn block_end(tokens: &[SpecToken<'_>], mut i: usize) -> usize {
+  let mut depth = 1;
+  while i < tokens.len() {
+    match tokens[i].0 {
+      SpecTokenType::OpenParentheses | SpecTokenType::Function | SpecTokenType::OpenSquare => {
+        depth += 1
+      }
+      SpecTokenType::CloseParentheses | SpecTokenType::CloseSquare => depth -= 1,
+      _ => {}
+    }
+    i += 1;
+    if depth == 0 {
+      break;
+    }
+  }
+  i
+}
+
+fn is_known_property(prop: &str) -> bool {
+  let prop = prop.to_ascii_lowercase();
+  let unprefixed = ["-webkit-", "-moz-", "-ms-", "-o-"]
+    .iter()
+    .find_map(|prefix| prop.strip_prefix(prefix))
+    .unwrap_or(&prop);
+  KNOWN_PROPERTIES.binary_search(&unprefixed).is_ok()
+}
+
+const KNOWN_PROPERTIES: &[&str] = &[
+  "accent-color",
+  "align-content",
+  "align-items",
+  "align-self",
+  "alignment-baseline",
+  "all",
+  "animation",
+  "animation-composition",
+  "animation-delay",
+  "animation-direction",
+  "animation-duration",
+  "animation-fill-mode",
+  "animation-iteration-count",
+  "animation-name",
+  "animation-play-state",
+  "animation-timing-function",
+  "appearance",
+  "aspect-ratio",
+  "backdrop-filter",
+  "backface-visibility",
+  "background",
+  "background-attachment",
+  "background-blend-mode",
+  "background-clip",
+  "background-color",
+  "background-image",
+  "background-origin",
+  "background-position",
+  "background-position-x",
+  "background-position-y",
+  "background-repeat",
+  "background-size",
+  "baseline-shift",
+  "block-size",
+  "border",
+  "border-block",
+  "border-block-color",
+  "border-block-end",
+  "border-block-end-color",
+  "border-block-end-style",
+  "border-block-end-width",
+  "border-block-start",
+  "border-block-start-color",
+  "border-block-start-style",
+  "border-block-start-width",
+  "border-block-style",
+  "border-block-width",
+  "border-bottom",
+  "border-bottom-color",
+  "border-bottom-left-radius",
+  "border-bottom-right-radius",
+  "border-bottom-style",
+  "border-bottom-width",
+  "border-collapse",
+  "border-color",
+  "border-end-end-radius",
+  "border-end-start-radius",
+  "border-image",
+  "border-image-outset",
+  "border-image-repeat",
+  "border-image-slice",
+  "border-image-source",
+  "border-image-width",
+  "border-inline",
+  "border-inline-color",
+  "border-inline-end",
+  "border-inline-end-color",
+  "border-inline-end-style",
+  "border-inline-end-width",
+  "border-inline-start",
+  "border-inline-start-color",
+  "border-inline-start-style",
+  "border-inline-start-width",
+  "border-inline-style",
+  "border-inline-width",
+  "border-left",
+  "border-left-color",
+  "border-left-style",
+  "border-left-width",
+  "border-radius",
+  "border-right",
+  "border-right-color",
+  "border-right-style",
+  "border-right-width",
+  "border-spacing",
+  "border-start-end-radius",
+  "border-start-start-radius",
+  "border-style",
+  "border-top",
+  "border-top-color",
+  "border-top-left-radius",
+  "border-top-right-radius",
+  "border-top-style",
+  "border-top-width",
+  "border-width",
+  "bottom",
+  "box-decoration-break",
+  "box-shadow",
+  "box-sizing",
+  "break-after",
+  "break-before",
+  "break-inside",
+  "caption-side",
+  "caret-color",
+  "clear",
+  "clip",
+  "clip-path",
+  "clip-rule",
+  "color",
+  "color-adjust",
+  "color-interpolation",
+  "color-interpolation-filters",
+  "color-scheme",
+  "column-count",
+  "column-fill",
+  "column-gap",
+  "column-rule",
+  "column-rule-color",
+  "column-rule-style",
+  "column-rule-width",
+  "column-span",
+  "column-width",
+  "columns",
+  "contain",
+  "contain-intrinsic-size",
+  "container",
+  "container-name",
+  "container-type",
+  "content",
+  "content-visibility",
+  "counter-increment",
+  "counter-reset",
+  "counter-set",
+  "cursor",
+  "cx",
+  "cy",
+  "d",
+  "direction",
+  "display",
+  "dominant-baseline",
+  "empty-cells",
+  "fill",
+  "fill-opacity",
+  "fill-rule",
+  "filter",
+  "flex",
+  "flex-basis",
+  "flex-direction",
+  "flex-flow",
+  "flex-grow",
+  "flex-shrink",
+  "flex-wrap",
+  "float",
+  "flood-color",
+  "flood-opacity",
+  "font",
+  "font-display",
+  "font-family",
+  "font-feature-settings",
+  "font-kerning",
+  "font-language-override",
+  "font-optical-sizing",
+  "font-size",
+  "font-size-adjust",
+  "font-stretch",
+  "font-style",
+  "font-synthesis",
+  "font-variant",
+  "font-variant-alternates",
+  "font-variant-caps",
+  "font-variant-east-asian",
+  "font-variant-ligatures",
+  "font-variant-numeric",
+  "font-variant-position",
+  "font-variation-settings",
+  "font-weight",
+  "forced-color-adjust",
+  "gap",
+  "grid",
+  "grid-area",
+  "grid-auto-columns",
+  "grid-auto-flow",
+  "grid-auto-rows",
+  "grid-column",
+  "grid-column-end",
+  "grid-column-gap",
+  "grid-column-start",
+  "grid-gap",
+  "grid-row",
+  "grid-row-end",
+  "grid-row-gap",
+  "grid-row-start",
+  "grid-template",
+  "grid-template-areas",
+  "grid-template-columns",
+  "grid-template-rows",
+  "hanging-punctuation",
+  "height",
+  "hyphens",
+  "image-orientation",
+  "image-rendering",
+  "inline-size",
+  "inset",
+  "inset-block",
+  "inset-block-end",
+  "inset-block-start",
+  "inset-inline",
+  "inset-inline-end",
+  "inset-inline-start",
+  "isolation",
+  "justify-content",
+  "justify-items",
+  "justify-self",
+  "left",
+  "letter-spacing",
+  "lighting-color",
+  "line-break",
+  "line-clamp",
+  "line-height",
+  "list-style",
+  "list-style-image",
+  "list-style-position",
+  "list-style-type",
+  "margin",
+  "margin-block",
+  "margin-block-end",
+  "margin-block-start",
+  "margin-bottom",
+  "margin-inline",
+  "margin-inline-end",
+  "margin-inline-start",
+  "margin-left",
+  "margin-right",
+  "margin-top",
+  "marker",
+  "marker-end",
+  "marker-mid",
+  "marker-start",
+  "mask",
+  "mask-border",
+  "mask-clip",
+  "mask-composite",
+  "mask-image",
+  "mask-mode",
+  "mask-origin",
+  "mask-position",
+  "mask-repeat",
+  "mask-size",
+  "mask-type",
+  "max-block-size",
+  "max-height",
+  "max-inline-size",
+  "max-width",
+  "min-block-size",
+  "min-height",
+  "min-inline-size",
+  "min-width",
+  "mix-blend-mode",
+  "object-fit",
+  "object-position",
+  "offset",
+  "offset-anchor",
+  "offset-distance",
+  "offset-path",
+  "offset-rotate",
+  "opacity",
+  "order",
+  "orphans",
+  "outline",
+  "outline-color",
+  "outline-offset",
+  "outline-style",
+  "outline-width",
+  "overflow",
+  "overflow-anchor",
+  "overflow-block",
+  "overflow-clip-margin",
+  "overflow-inline",
+  "overflow-wrap",
+  "overflow-x",
+  "overflow-y",
+  "overscroll-behavior",
+  "overscroll-behavior-block",
+  "overscroll-behavior-inline",
+  "overscroll-behavior-x",
+  "overscroll-behavior-y",
+  "padding",
+  "padding-block",
+  "padding-block-end",
+  "padding-block-start",
+  "padding-bottom",
+  "padding-inline",
+  "padding-inline-end",
+  "padding-inline-start",
+  "padding-left",
+  "padding-right",
+  "padding-top",
+  "page-break-after",
+  "page-break-before",
+  "page-break-inside",
+  "paint-order",
+  "perspective",
+  "perspective-origin",
+  "place-content",
+  "place-items",
+  "place-self",
+  "pointer-events",
+  "position",
+  "print-color-adjust",
+  "quotes",
+  "r",
+  "resize",
+  "right",
+  "rotate",
+  "row-gap",
+  "ruby-align",
+  "ruby-position",
+  "rx",
+  "ry",
+  "scale",
+  "scroll-behavior",
+  "scroll-margin",
+  "scroll-margin-block",
+  "scroll-margin-block-end",
+  "scroll-margin-block-start",
+  "scroll-margin-bottom",
+  "scroll-margin-inline",
+  "scroll-margin-inline-end",
+  "scroll-margin-inline-start",
+  "scroll-margin-left",
+  "scroll-margin-right",
+  "scroll-margin-top",
+  "scroll-padding",
+  "scroll-padding-block",
+  "scroll-padding-block-end",
+  "scroll-padding-block-start",
+  "scroll-padding-bottom",
+  "scroll-padding-inline",
+  "scroll-padding-inline-end",
+  "scroll-padding-inline-start",
+  "scroll-padding-left",
+  "scroll-padding-right",
+  "scroll-padding-top",
+  "scroll-snap-align",
+  "scroll-snap-stop",
+  "scroll-snap-type",
+  "scrollbar-color",
+  "scrollbar-gutter",
+  "scrollbar-width",
+  "shape-image-threshold",
+  "shape-margin",
+  "shape-outside",
+  "shape-rendering",
+  "speak",
+  "src",
+  "stop-color",
+  "stop-opacity",
+  "stroke",
+  "stroke-dasharray",
+  "stroke-dashoffset",
+  "stroke-linecap",
+  "stroke-linejoin",
+  "stroke-miterlimit",
+  "stroke-opacity",
+  "stroke-width",
+  "tab-size",
+  "table-layout",
+  "text-align",
+  "text-align-last",
+  "text-anchor",
+  "text-combine-upright",
+  "text-decoration",
+  "text-decoration-color",
+  "text-decoration-line",
+  "text-decoration-skip-ink",
+  "text-decoration-style",
+  "text-decoration-thickness",
+  "text-emphasis",
+  "text-emphasis-color",
+  "text-emphasis-position",
+  "text-emphasis-style",
+  "text-indent",
+  "text-justify",
+  "text-orientation",
+  "text-overflow",
+  "text-rendering",
+  "text-shadow",
+  "text-size-adjust",
+  "text-transform",
+  "text-underline-offset",
+  "text-underline-position",
+  "top",
+  "touch-action",
+  "transform",
+  "transform-box",
+  "transform-origin",
+  "transform-style",
+  "transition",
+  "transition-delay",
+  "transition-duration",
+  "transition-property",
+  "transition-timing-function",
+  "translate",
+  "unicode-bidi",
+  "unicode-range",
+  "user-select",
+  "vector-effect",
+  "vertical-align",
+  "visibility",
+  "white-space",
+  "widows",
+  "width",
+  "will-change",
+  "word-break",
+  "word-spacing",
+  "word-wrap",
+  "writing-mode",
+  "x",
+  "y",
+  "z-index",
+  "zoom",
+];
+
+const NAMED_COLORS: &[&str] = &[
+  "aliceblue",
+  "antiquewhite",
+  "aqua",
+  "aquamarine",
+  "azure",
+  "beige",
+  "bisque",
+  "black",
+  "blanchedalmond",
+  "blue",
+  "blueviolet",
+  "brown",
+  "burlywood",
+  "cadetblue",
+  "chartreuse",
+  "chocolate",
+  "coral",
+  "cornflowerblue",
+  "cornsilk",
+  "crimson",
+  "currentcolor",
+  "cyan",
+  "darkblue",
+  "darkcyan",
+  "darkgoldenrod",
+  "darkgray",
+  "darkgreen",
+  "darkgrey",
+  "darkkhaki",
+  "darkmagenta",
+  "darkolivegreen",
+  "darkorange",
+  "darkorchid",
+  "darkred",
+  "darksalmon",
+  "darkseagreen",
+  "darkslateblue",
+  "darkslategray",
+  "darkslategrey",
+  "darkturquoise",
+  "darkviolet",
+  "deeppink",
+  "deepskyblue",
+  "dimgray",
+  "dimgrey",
+  "dodgerblue",
+  "firebrick",
+  "floralwhite",
+  "forestgreen",
+  "fuchsia",
+  "gainsboro",
+  "ghostwhite",
+  "gold",
+  "goldenrod",
+  "gray",
+  "green",
+  "greenyellow",
+  "grey",
+  "honeydew",
+  "hotpink",
+  "indianred",
+  "indigo",
+  "ivory",
+  "khaki",
+  "lavender",
+  "lavenderblush",
+  "lawngreen",
+  "lemonchiffon",
+  "lightblue",
+  "lightcoral",
+  "lightcyan",
+  "lightgoldenrodyellow",
+  "lightgray",
+  "lightgreen",
+  "lightgrey",
+  "lightpink",
+  "lightsalmon",
+  "lightseagreen",
+  "lightskyblue",
+  "lightslategray",
+  "lightslategrey",
+  "lightsteelblue",
+  "lightyellow",
+  "lime",
+  "limegreen",
+  "linen",
+  "magenta",
+  "maroon",
+  "mediumaquamarine",
+  "mediumblue",
+  "mediumorchid",
+  "mediumpurple",
+  "mediumseagreen",
+  "mediumslateblue",
+  "mediumspringgreen",
+  "mediumturquoise",
+  "mediumvioletred",
+  "midnightblue",
+  "mintcream",
+  "mistyrose",
+  "moccasin",
+  "navajowhite",
+  "navy",
+  "oldlace",
+  "olive",
+  "olivedrab",
+  "orange",
+  "orangered",
+  "orchid",
+  "palegoldenrod",
+  "palegreen",
+  "paleturquoise",
+  "palevioletred",
+  "papayawhip",
+  "peachpuff",
+  "peru",
+  "pink",
+  "plum",
+  "powderblue",
+  "purple",
+  "rebeccapurple",
+  "red",
+  "rosybrown",
+  "royalblue",
+  "saddlebrown",
+  "salmon",
+  "sandybrown",
+  "seagreen",
+  "seashell",
+  "sienna",
+  "silver",
+  "skyblue",
+  "slateblue",
+  "slategray",
+  "slategrey",
+  "snow",
+  "springgreen",
+  "steelblue",
+  "tan",
+  "teal",
+  "thistle",
+  "tomato",
+  "transparent",
+  "turquoise",
+  "violet",
+  "wheat",
+  "white",
+  "whitesmoke",
+  "yellow",
+  "yellowgreen",
+];
@@ -1,7 +1,30 @@
+use rowan::TextRange;
 use syntax::SyntaxNode;
+pub use tokenizer::line_index;
+use tokenizer::line_index::{Encoding, LineCol, LineIndex};
 
+pub mod css_in_js;
+pub mod document;
+pub mod formatter;
+pub mod highlight;
+pub mod make;
+pub mod markdown;
 pub mod parser;
 pub mod syntax;
+pub mod ted;
+
+/// The zero-based start and end of a range of the tree, columns counted in `encoding`,
+/// e.g. [`Encoding::Utf16`] for the language server protocol.
+pub fn line_cols(
+  index: &LineIndex,
+  range: TextRange,
+  encoding: Encoding,
+) -> Option<(LineCol, LineCol)> {
+  Some((
+    index.line_col(range.start().into(), encoding)?,
+    index.line_col(range.end().into(), encoding)?,
+  ))
+}
 
 pub fn ast_printer(root: SyntaxNode, level: usize, compatible_with_postcss: bool) {
   println!("{}{:?}", " ".repeat(level * 2), root);
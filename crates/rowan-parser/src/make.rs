@@ -0,0 +1,84 @@
+//! Factories for detached, mutable nodes which can be inserted into a tree with [`crate::ted`].
+//!
+//! Every factory goes through the parser, so the result has exactly the same shape as a
+//! parsed stylesheet. They panic when the given text is not valid in that position.
+use crate::parser::Parser;
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+/// `prop: value`
+pub fn decl(prop: &str, value: &str) -> SyntaxNode {
+  from_text(
+    &format!("a {{ {}: {} }}", prop, value),
+    SyntaxKind::Declaration,
+  )
+}
+
+/// `selector {}`, or a block with every declaration on its own line:
+///
+/// ```css
+/// selector {
+///   prop: value;
+/// }
+/// ```
+pub fn rule(selector: &str, decls: impl IntoIterator<Item = SyntaxNode>) -> SyntaxNode {
+  let mut text = format!("{} {{", selector);
+  let mut empty = true;
+  for decl in decls {
+    empty = false;
+    text.push_str("\n  ");
+    text.push_str(&decl.to_string());
+    if decl.kind() == SyntaxKind::Declaration {
+      text.push(';');
+    }
+  }
+  text.push_str(if empty { "}" } else { "\n}" });
+  from_text(&text, SyntaxKind::Rule)
+}
+
+/// `@name params;`, or `@name;` when `params` is empty
+pub fn at_rule(name: &str, params: &str) -> SyntaxNode {
+  if params.is_empty() {
+    from_text(&format!("@{};", name), SyntaxKind::AtRule)
+  } else {
+    from_text(&format!("@{} {};", name, params), SyntaxKind::AtRule)
+  }
+}
+
+/// `/* text */`
+pub fn comment(text: &str) -> SyntaxNode {
+  from_text(&format!("/* {} */", text), SyntaxKind::Comment)
+}
+
+fn from_text(text: &str, kind: SyntaxKind) -> SyntaxNode {
+  let root = Parser::new(text).parse();
+  let node = root
+    .descendants()
+    .find(|node| node.kind() == kind)
+    .unwrap_or_else(|| panic!("failed to make {:?} from `{}`", kind, text));
+  SyntaxNode::new_root(node.green().into_owned()).clone_for_update()
+}
+
+pub mod tokens {
+  use crate::syntax::{Lang, SyntaxKind, SyntaxNode, SyntaxToken};
+  use rowan::{GreenNodeBuilder, Language};
+
+  pub fn whitespace(text: &str) -> SyntaxToken {
+    assert!(text.trim().is_empty(), "`{}` is not whitespace", text);
+    token(SyntaxKind::Space, text)
+  }
+
+  pub fn semicolon() -> SyntaxToken {
+    token(SyntaxKind::Semicolon, ";")
+  }
+
+  fn token(kind: SyntaxKind, text: &str) -> SyntaxToken {
+    let mut builder = GreenNodeBuilder::new();
+    builder.start_node(Lang::kind_to_raw(SyntaxKind::Root));
+    builder.token(Lang::kind_to_raw(kind), text);
+    builder.finish_node();
+    let root = SyntaxNode::new_root(builder.finish()).clone_for_update();
+    let token = root.first_token().unwrap();
+    token.detach();
+    token
+  }
+}
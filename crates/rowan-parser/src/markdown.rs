@@ -0,0 +1,134 @@
+//! Stylesheets in the fenced code blocks of a Markdown file.
+//!
+//! [`parse`] builds a `Document` like [`crate::document::parse`], with a `Root` for every
+//! ```` ```css ````, ```` ```scss ```` and ```` ```less ```` block. Blocks are parsed with
+//! [`Parser::parse_nested`], since documentation often shows a few declarations on their own,
+//! and SCSS and Less with their tokenizer mode. That covers variables, `//` comments and nested
+//! rules. A fence that doesn't parse, like a bare selector, a lone value or a Less mixin call
+//! `.mixin();`, stays text, and [`parse_with_errors`] reports why.
+use crate::document::{self, range};
+use crate::parser::{ParseError, Parser};
+use crate::syntax::SyntaxNode;
+use rowan::{NodeOrToken, TextRange};
+use tokenizer::Syntax;
+
+/// A fenced code block in one of the stylesheet languages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fence<'a> {
+  /// the first word of the info string, e.g. `scss` for ```` ```scss title="a.scss" ````
+  pub lang: &'a str,
+  /// where the code is in the Markdown file, from the line after the opening fence to the
+  /// start of the closing fence's line
+  pub range: TextRange,
+}
+
+impl<'a> Fence<'a> {
+  pub fn syntax(&self) -> Syntax {
+    match self.lang.to_ascii_lowercase().as_str() {
+      "scss" => Syntax::Scss,
+      "less" => Syntax::Less,
+      _ => Syntax::Css,
+    }
+  }
+}
+
+/// Find every `css`, `scss` and `less` fenced code block in `source`, in document order.
+pub fn extract(source: &str) -> Vec<Fence<'_>> {
+  let mut fences = vec![];
+  let mut lines = lines(source);
+  while let Some((start, line)) = lines.next() {
+    let (marker, len) = match fence(line) {
+      Some(fence) => fence,
+      None => continue,
+    };
+    let info = line.trim_start_matches(' ')[len..].trim();
+    if marker == '`' && info.contains('`') {
+      // an inline code span like ```` ```a``` ````
+      continue;
+    }
+    let lang = info
+      .split(|ch: char| ch.is_whitespace() || ch == '{')
+      .next()
+      .unwrap_or_default();
+
+    let content = start + line.len();
+    let mut end = source.len();
+    for (start, line) in lines.by_ref() {
+      if let Some((closing, closing_len)) = fence(line) {
+        if closing == marker && closing_len >= len && line.trim()[closing_len..].is_empty() {
+          end = start;
+          break;
+        }
+      }
+    }
+    if ["css", "scss", "less"]
+      .iter()
+      .any(|css| lang.eq_ignore_ascii_case(css))
+    {
+      fences.push(Fence {
+        lang,
+        range: range(content, end),
+      });
+    }
+  }
+  fences
+}
+
+/// Parse `source` into a `Document` with a `Root` for every [`Fence`].
+pub fn parse(source: &str) -> SyntaxNode {
+  parse_with_errors(source).0
+}
+
+/// Like [`parse`], along with the error of every fence that stays text, at offsets into
+/// `source`.
+pub fn parse_with_errors(source: &str) -> (SyntaxNode, Vec<ParseError>) {
+  document::build(
+    source,
+    extract(source).into_iter().map(|fence| {
+      let css = &source[fence.range];
+      let root = Parser::with_syntax(css, fence.syntax()).try_parse_nested();
+      (fence.range, root.map(|root| root.green().into_owned()))
+    }),
+  )
+}
+
+/// Print `document` with every root replaced by what `f` returns for it.
+///
+/// A line break is added to CSS that doesn't end with one, so the closing fence stays on its
+/// own line.
+pub fn stringify(document: &SyntaxNode, mut f: impl FnMut(&SyntaxNode) -> String) -> String {
+  let mut result = String::with_capacity(document.text_range().len().into());
+  for child in document.children_with_tokens() {
+    match child {
+      NodeOrToken::Token(token) => result.push_str(token.text()),
+      NodeOrToken::Node(root) => {
+        let css = f(&root);
+        result.push_str(&css);
+        if !css.is_empty() && !css.ends_with('\n') && root.next_sibling_or_token().is_some() {
+          result.push('\n');
+        }
+      }
+    }
+  }
+  result
+}
+
+/// The lines of `source` with their offset, including the line break.
+fn lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+  source.split_inclusive('\n').scan(0, |offset, line| {
+    let start = *offset;
+    *offset += line.len();
+    Some((start, line))
+  })
+}
+
+/// The marker and length of the fence that `line` starts with, ```` ``` ```` or `~~~`.
+fn fence(line: &str) -> Option<(char, usize)> {
+  let trimmed = line.trim_start_matches(' ');
+  let marker = trimmed
+    .chars()
+    .next()
+    .filter(|&ch| ch == '`' || ch == '~')?;
+  let len = trimmed.len() - trimmed.trim_start_matches(marker).len();
+  (len >= 3).then_some((marker, len))
+}
@@ -1,21 +1,56 @@
 use crate::syntax::{Lang, Lexer, SyntaxKind, SyntaxNode};
 use rowan::{GreenNodeBuilder, Language};
-use std::iter::Peekable;
+use std::fmt;
+use tokenizer::Syntax;
+
+/// The first syntax error of a stylesheet, at a byte offset of the parsed text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+  pub message: String,
+  pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} at {}", self.message, self.offset)
+  }
+}
+
+impl std::error::Error for ParseError {}
 
 pub struct Parser<'a> {
-  lexer: Peekable<Lexer<'a>>,
+  source: &'a str,
+  lexer: Lexer<'a>,
   builder: GreenNodeBuilder<'static>,
+  /// declarations and rules can be mixed in every block, see [`Parser::parse_nested`]
+  nested: bool,
+  /// once set, [`Parser::peek`] returns `None` so every loop stops
+  error: Option<ParseError>,
 }
 
 impl<'a> Parser<'a> {
   pub fn new(input: &'a str) -> Self {
+    Self::with_syntax(input, Syntax::Css)
+  }
+
+  /// A parser whose tokenizer reads `syntax`, e.g. to keep SCSS `//` comments as comments.
+  /// Pair it with [`Parser::parse_nested`] for the nested rules of SCSS and Less.
+  pub fn with_syntax(input: &'a str, syntax: Syntax) -> Self {
     Self {
-      lexer: Lexer::new(input).peekable(),
+      source: input,
+      lexer: Lexer::with_syntax(input, syntax),
       builder: GreenNodeBuilder::new(),
+      nested: false,
+      error: None,
     }
   }
 
-  pub fn parse(mut self) -> SyntaxNode {
+  /// Parse a stylesheet, panicking on a syntax error, see [`Parser::try_parse`].
+  pub fn parse(self) -> SyntaxNode {
+    self.try_parse().unwrap_or_else(|err| panic!("{}", err))
+  }
+
+  pub fn try_parse(mut self) -> Result<SyntaxNode, ParseError> {
     self.builder.start_node(SyntaxKind::Root.into());
     // self.parse_element();
     while let Some(syntax) = self.peek() {
@@ -31,8 +66,51 @@ impl<'a> Parser<'a> {
         }
       }
     }
+    self.finish()
+  }
+
+  /// Parse a declaration list without the surrounding braces, like the content of a
+  /// `style=""` attribute, into a `Root` whose children are declarations and comments.
+  pub fn parse_declarations(self) -> SyntaxNode {
+    self
+      .try_parse_declarations()
+      .unwrap_or_else(|err| panic!("{}", err))
+  }
+
+  pub fn try_parse_declarations(mut self) -> Result<SyntaxNode, ParseError> {
+    use SyntaxKind::*;
+    self.builder.start_node(Root.into());
+    while let Some(syntax) = self.peek() {
+      match syntax {
+        Space | Semicolon => self.bump(),
+        Comment => self.parse_comment(),
+        AtWord => self.parse_at_rule(),
+        _ if self.nested && self.rule_ahead() => self.parse_rule(),
+        _ => self.parse_declaration(),
+      }
+    }
+    self.finish()
+  }
+
+  fn finish(mut self) -> Result<SyntaxNode, ParseError> {
     self.builder.finish_node();
-    SyntaxNode::new_root(self.builder.finish())
+    match self.error {
+      Some(err) => Err(err),
+      None => Ok(SyntaxNode::new_root(self.builder.finish())),
+    }
+  }
+
+  /// Parse a stylesheet where declarations and rules can be mixed, at the top level and in
+  /// every block, like the content of a styled-components template.
+  pub fn parse_nested(self) -> SyntaxNode {
+    self
+      .try_parse_nested()
+      .unwrap_or_else(|err| panic!("{}", err))
+  }
+
+  pub fn try_parse_nested(mut self) -> Result<SyntaxNode, ParseError> {
+    self.nested = true;
+    self.try_parse_declarations()
   }
 
   #[inline]
@@ -67,7 +145,9 @@ impl<'a> Parser<'a> {
                 }
               },
               None => {
-                panic!(r#"expected {} found none"#, "{");
+                self.finish_node();
+                self.error("expected { found none");
+                break;
               }
             }
           }
@@ -115,8 +195,8 @@ impl<'a> Parser<'a> {
           }
         },
         None => {
-          // TODO: error handle
-          panic!("expected ) found none");
+          self.error("expected ) found none");
+          break;
         }
       }
     }
@@ -136,8 +216,8 @@ impl<'a> Parser<'a> {
           }
         },
         None => {
-          // TODO: error handle
-          panic!("expected ] found none");
+          self.error("expected ] found none");
+          break;
         }
       }
     }
@@ -153,6 +233,7 @@ impl<'a> Parser<'a> {
         Some(kind) => match kind {
           Semicolon => self.bump(),
           AtWord => self.parse_at_rule(),
+          Comment => self.parse_comment(),
           Space => {
             self.bump();
           }
@@ -162,7 +243,13 @@ impl<'a> Parser<'a> {
             break;
           }
           _ => {
-            if rule {
+            if self.nested {
+              if self.rule_ahead() {
+                self.parse_rule();
+              } else {
+                self.parse_declaration();
+              }
+            } else if rule {
               // println!("parse rule -->");
               self.parse_rule();
             } else {
@@ -172,8 +259,8 @@ impl<'a> Parser<'a> {
           }
         },
         None => {
-          //TODO: error handle
-          panic!("expected close curly");
+          self.error("expected close curly");
+          break;
         }
       }
     }
@@ -182,41 +269,46 @@ impl<'a> Parser<'a> {
   fn parse_declaration(&mut self) {
     use SyntaxKind::*;
     self.start_node(Declaration);
-    assert!(
-      matches!(self.peek(), Some(Word)),
-      "expected word found {:?}",
-      self.peek(),
-    );
+    if !matches!(self.peek(), Some(Word)) {
+      let found = self.peek();
+      self.error(format!("expected word found {:?}", found));
+      self.finish_node();
+      return;
+    }
     self.start_node(SyntaxKind::Prop);
     self.bump();
     self.finish_node();
-    self.skip_whitespace();
-    assert!(
-      matches!(self.peek(), Some(SyntaxKind::Colon)),
-      "expected : found {:?}",
-      self.peek()
-    );
+    self.skip_trivia();
+    if !matches!(self.peek(), Some(SyntaxKind::Colon)) {
+      let found = self.peek();
+      self.error(format!("expected : found {:?}", found));
+      self.finish_node();
+      return;
+    }
     self.bump();
-    self.skip_whitespace();
+    self.skip_trivia();
     self.start_node(SyntaxKind::Value);
-    let mut has_finish = false;
     while let Some(kind) = self.peek() {
       match kind {
-        CloseCurly | Semicolon => {
-          has_finish = true;
-          self.finish_node();
-          break;
+        CloseCurly | Semicolon => break,
+        Space => {
+          // whitespace in front of `;` or `}` belongs to the block, not to the value
+          let (kind, text, _) = self.lexer.next().unwrap();
+          if matches!(self.peek(), None | Some(CloseCurly | Semicolon)) {
+            self.finish_node();
+            self.finish_node();
+            self.builder.token(Lang::kind_to_raw(kind), text);
+            return;
+          }
+          self.builder.token(Lang::kind_to_raw(kind), text);
         }
-        Space => self.bump(),
         _ => {
           // println!("parse the component");
           self.parse_component();
         }
       }
     }
-    if !has_finish {
-      self.finish_node();
-    }
+    self.finish_node();
     self.finish_node();
   }
 
@@ -224,7 +316,7 @@ impl<'a> Parser<'a> {
     use SyntaxKind::*;
     self.start_node(SyntaxKind::AtRule);
     self.bump(); // bump atWord
-    self.skip_whitespace();
+    self.skip_trivia();
     self.start_node(SyntaxKind::Params);
     while let Some(kind) = self.peek() {
       match kind {
@@ -246,20 +338,82 @@ impl<'a> Parser<'a> {
     self.finish_node();
   }
 
+  /// Whether the statement starting at the next token is a rule, i.e. a `{` comes before
+  /// the `;` or `}` that would end a declaration.
+  fn rule_ahead(&mut self) -> bool {
+    use SyntaxKind::*;
+    let checkpoint = self.lexer.checkpoint();
+    let mut depth = 0usize;
+    let mut rule = false;
+    // braces end the scan even inside brackets, so it never leaves the current block and every
+    // statement is scanned once; unclosed quotes are the parser's error, not the scan's
+    for (kind, ..) in self.lexer.by_ref() {
+      match kind {
+        OpenParentheses | OpenSquare => depth += 1,
+        CloseParentheses | CloseSquare => depth = depth.saturating_sub(1),
+        OpenCurly => {
+          rule = true;
+          break;
+        }
+        CloseCurly => break,
+        Semicolon if depth == 0 => break,
+        _ => {}
+      }
+    }
+    self.lexer.rewind(checkpoint);
+    rule
+  }
+
   #[inline]
   pub fn skip_whitespace(&mut self) {
     if let Some(SyntaxKind::Space) = self.peek() {
       self.bump();
     }
   }
+
+  /// bump every space and comment token, comments stay in the tree as `Comment` tokens
+  #[inline]
+  pub fn skip_trivia(&mut self) {
+    while let Some(SyntaxKind::Space | SyntaxKind::Comment) = self.peek() {
+      self.bump();
+    }
+  }
+
   pub fn peek(&mut self) -> Option<SyntaxKind> {
+    if self.error.is_some() {
+      return None;
+    }
     self.lexer.peek().map(|(kind, ..)| *kind)
   }
 
   pub fn bump(&mut self) {
-    let (kind, text, _) = self.lexer.next().unwrap();
+    let (kind, text, offset) = self.lexer.next().unwrap();
     // println!("{:?}, {:?}", kind, text);
     self.builder.token(Lang::kind_to_raw(kind), text);
+    // the lexer ignores unclosed quotes and comments to stay lossless, the parser doesn't
+    if kind == SyntaxKind::String && !is_closed_string(text) {
+      self.error_at("unclosed string", offset);
+    } else if kind == SyntaxKind::Comment && text.starts_with("/*") && !is_closed_comment(text) {
+      self.error_at("unclosed comment", offset);
+    }
+  }
+
+  /// Stop parsing with `message` about the next token, or the end of the input.
+  fn error(&mut self, message: impl Into<String>) {
+    let offset = match self.lexer.peek() {
+      Some((.., offset)) => *offset,
+      None => self.source.len(),
+    };
+    self.error_at(message, offset);
+  }
+
+  fn error_at(&mut self, message: impl Into<String>, offset: usize) {
+    if self.error.is_none() {
+      self.error = Some(ParseError {
+        message: message.into(),
+        offset,
+      });
+    }
   }
 
   fn start_node(&mut self, kind: SyntaxKind) {
@@ -270,3 +424,11 @@ impl<'a> Parser<'a> {
     self.builder.finish_node();
   }
 }
+
+fn is_closed_string(text: &str) -> bool {
+  text.len() >= 2 && text.as_bytes()[0] == text.as_bytes()[text.len() - 1]
+}
+
+fn is_closed_comment(text: &str) -> bool {
+  text.len() >= 4 && text.ends_with("*/")
+}
@@ -1,4 +1,5 @@
-use tokenizer::{TokenType, Tokenizer};
+use tokenizer::input::Input;
+use tokenizer::{Checkpoint, Syntax, TokenType, Tokenizer};
 
 #[repr(u16)]
 #[derive(Debug, PartialEq, Clone, Copy, Ord, PartialOrd, Eq, Hash)]
@@ -40,34 +41,81 @@ pub enum SyntaxKind {
   Prop,
 }
 
+impl SyntaxKind {
+  /// Whether a node of this kind is a statement of a block or the root, the unit that
+  /// [`crate::ted`] inserts and removes and the formatter lays out.
+  pub fn is_statement(self) -> bool {
+    matches!(
+      self,
+      SyntaxKind::Declaration | SyntaxKind::Rule | SyntaxKind::AtRule | SyntaxKind::Comment
+    )
+  }
+}
+
 impl From<SyntaxKind> for rowan::SyntaxKind {
   fn from(kind: SyntaxKind) -> Self {
     Self(kind as u16)
   }
 }
 
+/// A token with its text and its offset in the input.
+pub(crate) type LexToken<'a> = (SyntaxKind, &'a str, usize);
+
 pub(crate) struct Lexer<'a> {
   inner: Tokenizer<'a>,
+  /// a leading byte-order mark, it becomes a `Space` token so the tree stays lossless
+  bom: Option<&'a str>,
+  offset: usize,
+  /// the token [`Lexer::peek`] read but didn't return from `next`
+  peeked: Option<LexToken<'a>>,
 }
 
 impl<'a> Lexer<'a> {
-  pub(crate) fn new(input: &'a str) -> Self {
+  pub(crate) fn with_syntax(input: &'a str, syntax: Syntax) -> Self {
+    let css = Input::new(input, None).css;
+    let bom = &input[..input.len() - css.len()];
     Self {
-      inner: Tokenizer::new(input, false),
+      inner: Tokenizer::with_syntax(css, true, syntax),
+      bom: (!bom.is_empty()).then_some(bom),
+      offset: bom.len(),
+      peeked: None,
+    }
+  }
+
+  pub(crate) fn peek(&mut self) -> Option<&LexToken<'a>> {
+    if self.peeked.is_none() {
+      self.peeked = self.next();
     }
+    self.peeked.as_ref()
+  }
+
+  /// Remember the current position, peeked token included, to [`rewind`](Lexer::rewind) to.
+  pub(crate) fn checkpoint(&self) -> (Checkpoint<'a>, Option<LexToken<'a>>) {
+    (self.inner.checkpoint(), self.peeked)
+  }
+
+  pub(crate) fn rewind(&mut self, (checkpoint, peeked): (Checkpoint<'a>, Option<LexToken<'a>>)) {
+    self.inner.rewind(checkpoint);
+    self.peeked = peeked;
   }
 }
 
 impl<'a> Iterator for Lexer<'a> {
-  type Item = (SyntaxKind, &'a str, usize);
+  type Item = LexToken<'a>;
 
   fn next(&mut self) -> Option<Self::Item> {
-    if !self.inner.end_of_file() {
-      let token = self.inner.next_token(false);
-      Some((token.0.into(), &self.inner.css[token.1..token.2], token.1))
-    } else {
-      None
+    if let Some(token) = self.peeked.take() {
+      return Some(token);
+    }
+    if let Some(bom) = self.bom.take() {
+      return Some((SyntaxKind::Space, bom, 0));
     }
+    let token = self.inner.next()?;
+    Some((
+      token.0.into(),
+      &self.inner.css[token.1..token.2],
+      token.1 + self.offset,
+    ))
   }
 }
 
@@ -111,3 +159,5 @@ impl rowan::Language for Lang {
 pub type SyntaxNode = rowan::SyntaxNode<Lang>;
 
 pub type SyntaxToken = rowan::SyntaxToken<Lang>;
+
+pub type SyntaxElement = rowan::SyntaxElement<Lang>;
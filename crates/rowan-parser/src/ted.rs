@@ -0,0 +1,216 @@
+//! In-place editing of a mutable syntax tree (one created with `clone_for_update`).
+//!
+//! The helpers take care of the `;` between declarations and copy the surrounding whitespace,
+//! so the rest of the stylesheet stays byte-identical. New nodes usually come from
+//! [`crate::make`] and are re-indented to the place they are inserted at.
+use crate::make;
+use crate::syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+use rowan::NodeOrToken;
+
+/// Insert `node` as the next sibling of `anchor`.
+pub fn insert_after(anchor: &SyntaxNode, node: SyntaxNode) {
+  let parent = anchor.parent().expect("can't insert next to the root");
+  let separator = separator(anchor);
+  reindent(&node, &indent(anchor));
+
+  let mut index = anchor.index() + 1;
+  let mut trailing_semicolon = true;
+  if anchor.kind() == SyntaxKind::Declaration {
+    match semicolon_after(anchor) {
+      Some(semicolon) => index = semicolon.index() + 1,
+      None => {
+        // the anchor was the last declaration of the block and omitted its `;`,
+        // so it needs one now and the new declaration keeps that style.
+        parent.splice_children(index..index, vec![make::tokens::semicolon().into()]);
+        index += 1;
+        trailing_semicolon = false;
+      }
+    }
+  }
+
+  let mut elements: Vec<SyntaxElement> = vec![];
+  if !separator.is_empty() {
+    elements.push(make::tokens::whitespace(&separator).into());
+  }
+  let is_decl = node.kind() == SyntaxKind::Declaration;
+  elements.push(node.into());
+  if is_decl && (trailing_semicolon || next_statement(&parent, index).is_some()) {
+    elements.push(make::tokens::semicolon().into());
+  }
+  parent.splice_children(index..index, elements);
+}
+
+/// Insert `node` as the previous sibling of `anchor`.
+pub fn insert_before(anchor: &SyntaxNode, node: SyntaxNode) {
+  let parent = anchor.parent().expect("can't insert next to the root");
+  let separator = separator(anchor);
+  reindent(&node, &indent(anchor));
+
+  let index = anchor.index();
+  let is_decl = node.kind() == SyntaxKind::Declaration;
+  let mut elements: Vec<SyntaxElement> = vec![node.into()];
+  if is_decl {
+    elements.push(make::tokens::semicolon().into());
+  }
+  if !separator.is_empty() {
+    elements.push(make::tokens::whitespace(&separator).into());
+  }
+  parent.splice_children(index..index, elements);
+}
+
+/// Append `child` to the end of a `Root`, or to the end of the block of a `Rule` or `AtRule`.
+pub fn append_child(parent: &SyntaxNode, child: SyntaxNode) {
+  if let Some(last) = parent
+    .children()
+    .filter(|node| node.kind().is_statement())
+    .last()
+  {
+    insert_after(&last, child);
+    return;
+  }
+  if parent.kind() == SyntaxKind::Root {
+    let end = parent.children_with_tokens().count();
+    parent.splice_children(end..end, vec![child.into()]);
+    return;
+  }
+
+  let open = parent
+    .children_with_tokens()
+    .find(|element| element.kind() == SyntaxKind::OpenCurly)
+    .unwrap_or_else(|| panic!("{:?} has no block", parent.kind()));
+  let inner_space = open
+    .next_sibling_or_token()
+    .filter(|element| element.kind() == SyntaxKind::Space);
+  let index = open.index() + 1;
+  let is_decl = child.kind() == SyntaxKind::Declaration;
+  let mut elements: Vec<SyntaxElement> = vec![];
+  match inner_space {
+    Some(space) if space.to_string().contains('\n') => {
+      let indent = format!("{}  ", indent(parent));
+      reindent(&child, &indent);
+      elements.push(make::tokens::whitespace(&format!("\n{}", indent)).into());
+      elements.push(child.into());
+      if is_decl {
+        elements.push(make::tokens::semicolon().into());
+      }
+    }
+    space => {
+      reindent(&child, &indent(parent));
+      elements.push(make::tokens::whitespace(" ").into());
+      elements.push(child.into());
+      if is_decl {
+        elements.push(make::tokens::semicolon().into());
+      }
+      if space.is_none() {
+        elements.push(make::tokens::whitespace(" ").into());
+      }
+    }
+  }
+  parent.splice_children(index..index, elements);
+}
+
+/// Replace `old` with `new`, the whitespace around `old` is kept.
+pub fn replace(old: &SyntaxNode, new: SyntaxNode) {
+  let parent = old.parent().expect("can't replace the root");
+  reindent(&new, &indent(old));
+  let index = old.index();
+  parent.splice_children(index..index + 1, vec![new.into()]);
+}
+
+/// Remove `node` together with the `;` of a declaration and the whitespace in front of it.
+pub fn remove(node: &SyntaxNode) {
+  let parent = node.parent().expect("can't remove the root");
+  let mut start = node.index();
+  let mut end = start + 1;
+  if node.kind() == SyntaxKind::Declaration {
+    if let Some(semicolon) = semicolon_after(node) {
+      end = semicolon.index() + 1;
+    }
+  }
+  let before = node
+    .prev_sibling_or_token()
+    .filter(|element| element.kind() == SyntaxKind::Space);
+  let after = parent
+    .children_with_tokens()
+    .nth(end)
+    .filter(|element| element.kind() == SyntaxKind::Space);
+  match (before, after) {
+    (Some(_), _) => start -= 1,
+    (None, Some(_)) => end += 1,
+    (None, None) => {}
+  }
+  parent.splice_children(start..end, vec![]);
+}
+
+/// the `;` terminating `decl`, only whitespace may sit in between
+pub(crate) fn semicolon_after(decl: &SyntaxNode) -> Option<SyntaxToken> {
+  let mut next = decl.next_sibling_or_token();
+  while let Some(element) = next {
+    match element {
+      NodeOrToken::Token(token) if token.kind() == SyntaxKind::Semicolon => return Some(token),
+      NodeOrToken::Token(token) if token.kind() == SyntaxKind::Space => {
+        next = token.next_sibling_or_token();
+      }
+      _ => return None,
+    }
+  }
+  None
+}
+
+fn next_statement(parent: &SyntaxNode, index: usize) -> Option<SyntaxNode> {
+  parent
+    .children_with_tokens()
+    .skip(index)
+    .filter_map(|element| element.into_node())
+    .find(|node| node.kind().is_statement())
+}
+
+/// the whitespace which separates `node` from its siblings
+fn separator(node: &SyntaxNode) -> String {
+  let before = node
+    .prev_sibling_or_token()
+    .filter(|element| element.kind() == SyntaxKind::Space);
+  let after = semicolon_after(node)
+    .map(SyntaxElement::from)
+    .unwrap_or_else(|| node.clone().into())
+    .next_sibling_or_token()
+    .filter(|element| element.kind() == SyntaxKind::Space);
+  match before.or(after) {
+    Some(space) => space.to_string(),
+    None if node.parent().map(|parent| parent.kind()) == Some(SyntaxKind::Root) => "\n".into(),
+    None => String::new(),
+  }
+}
+
+/// the indentation of the line `node` starts on
+fn indent(node: &SyntaxNode) -> String {
+  let mut token = node.first_token().and_then(|token| token.prev_token());
+  while let Some(current) = token {
+    if current.kind() == SyntaxKind::Space {
+      if let Some(i) = current.text().rfind('\n') {
+        return current.text()[i + 1..].to_string();
+      }
+    }
+    token = current.prev_token();
+  }
+  String::new()
+}
+
+fn reindent(node: &SyntaxNode, indent: &str) {
+  if indent.is_empty() {
+    return;
+  }
+  let spaces = node
+    .descendants_with_tokens()
+    .filter_map(|element| element.into_token())
+    .filter(|token| token.kind() == SyntaxKind::Space && token.text().contains('\n'))
+    .collect::<Vec<_>>();
+  for space in spaces {
+    let text = space.text().replace('\n', &format!("\n{}", indent));
+    let index = space.index();
+    space.parent().unwrap().splice_children(
+      index..index + 1,
+      vec![make::tokens::whitespace(&text).into()],
+    );
+  }
+}
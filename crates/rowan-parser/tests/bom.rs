@@ -0,0 +1,14 @@
+use rowan_parser::parser::Parser;
+use rowan_parser::syntax::SyntaxKind;
+
+#[test]
+fn keeps_bom_out_of_the_first_selector() {
+  let css = "\u{feff}a {}";
+  let root = Parser::new(css).parse();
+  assert_eq!(root.to_string(), css);
+  let selector = root
+    .descendants()
+    .find(|node| node.kind() == SyntaxKind::Selector)
+    .unwrap();
+  assert_eq!(selector.to_string(), "a ");
+}
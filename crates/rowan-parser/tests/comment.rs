@@ -0,0 +1,86 @@
+use rowan::NodeOrToken;
+use rowan_parser::parser::Parser;
+use rowan_parser::syntax::{SyntaxKind, SyntaxNode};
+
+fn parse(css: &str) -> SyntaxNode {
+  let root = Parser::new(css).parse();
+  assert_eq!(root.text(), css);
+  root
+}
+
+fn comments(root: &SyntaxNode) -> Vec<(SyntaxKind, String)> {
+  root
+    .descendants_with_tokens()
+    .filter_map(|element| match element {
+      NodeOrToken::Token(token) if token.kind() == SyntaxKind::Comment => {
+        Some((token.parent().unwrap().kind(), token.text().to_string()))
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn parses_comment_between_declarations() {
+  let root = parse("a { /* first */ color: red; /* second */ width: 1px; /* last */ }");
+  assert_eq!(
+    comments(&root),
+    vec![
+      (SyntaxKind::Comment, "/* first */".to_string()),
+      (SyntaxKind::Comment, "/* second */".to_string()),
+      (SyntaxKind::Comment, "/* last */".to_string()),
+    ]
+  );
+  let rule = root.first_child().unwrap();
+  assert_eq!(
+    rule
+      .children()
+      .map(|child| child.kind())
+      .collect::<Vec<_>>(),
+    vec![
+      SyntaxKind::Selector,
+      SyntaxKind::Comment,
+      SyntaxKind::Declaration,
+      SyntaxKind::Comment,
+      SyntaxKind::Declaration,
+      SyntaxKind::Comment,
+    ]
+  );
+}
+
+#[test]
+fn parses_comment_inside_selector() {
+  let root = parse("a /* inner */ b, c/**/ { color: red }");
+  assert_eq!(
+    comments(&root),
+    vec![
+      (SyntaxKind::Selector, "/* inner */".to_string()),
+      (SyntaxKind::Selector, "/**/".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn parses_comment_inside_declaration() {
+  let root = parse("a { color /* a */ : /* b */ red /* c */ blue; }");
+  assert_eq!(
+    comments(&root),
+    vec![
+      (SyntaxKind::Declaration, "/* a */".to_string()),
+      (SyntaxKind::Declaration, "/* b */".to_string()),
+      (SyntaxKind::Value, "/* c */".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn parses_comment_inside_at_rule() {
+  let root = parse("@media /* a */ screen { /* b */ a { /* c */ } }");
+  assert_eq!(
+    comments(&root)
+      .into_iter()
+      .map(|(kind, _)| kind)
+      .collect::<Vec<_>>(),
+    vec![SyntaxKind::AtRule, SyntaxKind::Comment, SyntaxKind::Comment]
+  );
+}
@@ -0,0 +1,49 @@
+use rowan_parser::parser::Parser;
+use rowan_parser::syntax::{SyntaxKind, SyntaxNode};
+use std::fs::read_to_string;
+use tokenizer::newline::normalize_newlines;
+
+fn kinds(root: &SyntaxNode) -> Vec<SyntaxKind> {
+  root
+    .descendants_with_tokens()
+    .map(|element| element.kind())
+    .collect()
+}
+
+#[test]
+fn parses_windows_line_endings() {
+  let file_list = [
+    "simple.css",
+    "bootstrap-reboot.css",
+    "bootstrap.css",
+    "tailwind-components.css",
+  ];
+  for file_name in file_list {
+    let unix = read_to_string(format!("../../assets/{}", file_name))
+      .unwrap()
+      .replace("\r\n", "\n");
+    let windows = unix.replace('\n', "\r\n");
+    let root = Parser::new(&windows).parse();
+    assert_eq!(root.to_string(), windows, "{}", file_name);
+    assert_eq!(
+      kinds(&root),
+      kinds(&Parser::new(&unix).parse()),
+      "{}",
+      file_name
+    );
+
+    let normalized = normalize_newlines(&windows);
+    let root = Parser::new(&normalized.css).parse();
+    assert_eq!(root.to_string(), unix, "{}", file_name);
+    for node in root.descendants() {
+      let range = node.text_range();
+      let range = normalized.original_range(range.start().into()..range.end().into());
+      assert_eq!(
+        windows[range].replace("\r\n", "\n"),
+        node.to_string(),
+        "{}",
+        file_name
+      );
+    }
+  }
+}
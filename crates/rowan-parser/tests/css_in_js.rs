@@ -0,0 +1,171 @@
+use rowan_parser::syntax::SyntaxKind;
+use rowan_parser::{css_in_js, document};
+
+const TSX: &str = r#"import styled, { css, createGlobalStyle } from 'styled-components';
+
+// const Ignored = styled.div`color: red;`;
+const quote = "`";
+const pattern = /`[^`]*`/g;
+
+const mixin = css`
+  margin: 0;
+`;
+
+export const Button = styled.button.attrs({ type: 'button' })<{ primary: boolean }>`
+  color: ${(props) => (props.primary ? 'white' : 'black')};
+  ${mixin}
+  &:hover {
+    color: red;
+  }
+`;
+
+const Global = createGlobalStyle`
+  body { margin: 0 }
+`;
+
+const label = `not ${css`css`} ${`nested`}`;
+"#;
+
+#[test]
+fn extracts_tagged_templates() {
+  let templates = css_in_js::extract(TSX);
+  assert_eq!(
+    templates
+      .iter()
+      .map(|template| template.tag)
+      .collect::<Vec<_>>(),
+    vec![
+      "css",
+      "styled.button.attrs({ type: 'button' })<{ primary: boolean }>",
+      "createGlobalStyle",
+      "css",
+    ]
+  );
+  assert_eq!(&TSX[templates[3].range], "css");
+  assert_eq!(
+    templates[1]
+      .interpolations
+      .iter()
+      .map(|&range| &TSX[range])
+      .collect::<Vec<_>>(),
+    vec![
+      "${(props) => (props.primary ? 'white' : 'black')}",
+      "${mixin}"
+    ]
+  );
+}
+
+#[test]
+fn parses_templates_with_interpolations() {
+  let (doc, errors) = css_in_js::parse_with_errors(TSX);
+  assert_eq!(doc.to_string(), TSX);
+  let roots = document::roots(&doc).collect::<Vec<_>>();
+  assert_eq!(roots.len(), 3);
+  // `css` is not a statement, that template stays text
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].offset, TSX.find("css`}").unwrap() + 3);
+
+  let button = &roots[1];
+  assert_eq!(
+    button
+      .children()
+      .map(|node| (node.kind(), node.to_string()))
+      .collect::<Vec<_>>(),
+    vec![
+      (
+        SyntaxKind::Declaration,
+        "color: ${(props) => (props.primary ? 'white' : 'black')}".to_string()
+      ),
+      (SyntaxKind::Comment, "${mixin}".to_string()),
+      (
+        SyntaxKind::Rule,
+        "&:hover {\n    color: red;\n  }".to_string()
+      ),
+    ]
+  );
+
+  // ranges are offsets into the host file
+  let hover = button
+    .descendants()
+    .find(|node| node.kind() == SyntaxKind::Declaration && node.to_string() == "color: red")
+    .unwrap();
+  assert_eq!(&TSX[hover.text_range()], "color: red");
+
+  let global = roots[2].first_child().unwrap();
+  assert_eq!(global.kind(), SyntaxKind::Rule);
+}
+
+#[test]
+fn interpolations_in_selectors_and_values() {
+  let js = "styled.a`${Link}:hover & { margin: ${m}px ${n}; } ${a} ${b}`";
+  let doc = css_in_js::parse(js);
+  assert_eq!(doc.to_string(), js);
+  let root = document::roots(&doc).next().unwrap();
+  let kinds = root
+    .descendants()
+    .map(|node| node.kind())
+    .collect::<Vec<_>>();
+  assert!(kinds.contains(&SyntaxKind::Selector));
+  assert_eq!(
+    root
+      .descendants()
+      .filter(|node| node.kind() == SyntaxKind::Value)
+      .map(|node| node.to_string())
+      .collect::<Vec<_>>(),
+    vec!["${m}px ${n}"]
+  );
+  // two interpolations after each other on one line are both statements
+  assert_eq!(
+    root
+      .children()
+      .filter(|node| node.kind() == SyntaxKind::Comment)
+      .count(),
+    2
+  );
+}
+
+#[test]
+fn stringify_writes_css_back() {
+  let js = "const a = css`color: red;`;\nconst b = styled.p`\n  content: '\\`';\n`;\n";
+  let doc = css_in_js::parse(js);
+  assert_eq!(css_in_js::stringify(&doc, |root| root.to_string()), js);
+  let result = css_in_js::stringify(&doc, |root| {
+    root
+      .to_string()
+      .replace("red", "blue")
+      .replace("'\\`'", "'`'")
+  });
+  assert_eq!(
+    result,
+    "const a = css`color: blue;`;\nconst b = styled.p`\n  content: '\\`';\n`;\n"
+  );
+}
+
+#[test]
+fn keeps_templates_with_errors_as_text() {
+  for js in [
+    "styled.a`content: \"x;`",
+    "styled.a`a { color: red; `",
+    "css`}`",
+  ] {
+    let (doc, errors) = css_in_js::parse_with_errors(js);
+    assert_eq!(doc.to_string(), js);
+    assert_eq!(document::roots(&doc).count(), 0);
+    assert_eq!(errors.len(), 1, "{}", js);
+  }
+}
+
+#[test]
+fn reads_regular_expressions_after_keywords() {
+  let js = "function f(x) {\n  return /`/.test(x);\n}\nconst a = css`a: b;`;\nconst c = x.return / 2 / css`c: d;`;";
+  assert_eq!(
+    css_in_js::extract(js)
+      .iter()
+      .map(|template| &js[template.range])
+      .collect::<Vec<_>>(),
+    vec!["a: b;", "c: d;"]
+  );
+  for js in ["typeof /`/ + css`a: b;`", "case /`/: css`a: b;`"] {
+    assert_eq!(css_in_js::extract(js).len(), 1, "{}", js);
+  }
+}
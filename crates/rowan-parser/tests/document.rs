@@ -0,0 +1,147 @@
+use rowan_parser::document::{self, BlockKind};
+use rowan_parser::syntax::SyntaxKind;
+use rowan_parser::{make, ted};
+
+const VUE: &str = r#"<template>
+  <p style="color: red; margin: 0">hi</p>
+  <!-- <style>ignored {}</style> -->
+</template>
+
+<script>
+const css = "<style>ignored {}</style>";
+</script>
+
+<style scoped>
+.a { color: blue }
+</style>
+<style lang="scss">
+.b { .c { color: red } }
+</style>
+"#;
+
+#[test]
+fn extracts_style_elements_and_attributes() {
+  let blocks = document::extract(VUE);
+  assert_eq!(
+    blocks
+      .iter()
+      .map(|block| (block.kind, &VUE[block.range], block.lang))
+      .collect::<Vec<_>>(),
+    vec![
+      (BlockKind::Attribute, "color: red; margin: 0", None),
+      (BlockKind::Style, "\n.a { color: blue }\n", None),
+      (
+        BlockKind::Style,
+        "\n.b { .c { color: red } }\n",
+        Some("scss")
+      ),
+    ]
+  );
+  assert!(!blocks[2].is_css());
+}
+
+#[test]
+fn parses_a_document_of_roots() {
+  let doc = document::parse(VUE);
+  assert_eq!(doc.kind(), SyntaxKind::Document);
+  assert_eq!(doc.to_string(), VUE);
+  let roots = document::roots(&doc).collect::<Vec<_>>();
+  assert_eq!(roots.len(), 3);
+  assert_eq!(document::block_kind(&roots[0]), BlockKind::Attribute);
+  assert_eq!(document::block_kind(&roots[1]), BlockKind::Style);
+  assert_eq!(document::block_kind(&roots[2]), BlockKind::Style);
+
+  let decls = roots[0]
+    .children()
+    .filter(|node| node.kind() == SyntaxKind::Declaration)
+    .map(|node| node.to_string())
+    .collect::<Vec<_>>();
+  assert_eq!(decls, vec!["color: red", "margin: 0"]);
+
+  // ranges are offsets into the host file
+  let rule = roots[1].first_child().unwrap();
+  assert_eq!(rule.kind(), SyntaxKind::Rule);
+  assert_eq!(&VUE[rule.text_range()], ".a { color: blue }");
+}
+
+#[test]
+fn parses_scss_and_less_blocks() {
+  let vue = "<style lang=\"SCSS\">\n// c\n$x: 1;\n.a { .b { top: $x } }\n</style>\n\
+             <style lang=less>@x: 1; .a { .b { top: @x } }</style>\n<style lang=\"stylus\">\n.a\n  top 0\n</style>";
+  let (doc, errors) = document::parse_with_errors(vue);
+  assert_eq!(doc.to_string(), vue);
+  let roots = document::roots(&doc).collect::<Vec<_>>();
+  assert_eq!(roots.len(), 2);
+  assert!(roots[0]
+    .descendants_with_tokens()
+    .any(|node| node.kind() == SyntaxKind::Comment && node.to_string() == "// c"));
+  assert_eq!(roots[1].to_string(), "@x: 1; .a { .b { top: @x } }");
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].message, "unsupported style language `stylus`");
+  assert_eq!(errors[0].offset, vue.find("\n.a\n").unwrap());
+}
+
+#[test]
+fn handles_unusual_markup() {
+  let html =
+    "<DIV STYLE='top: 0' hidden style=left:0><Style media=print>a{}</STYLE ><style></style>";
+  let doc = document::parse(html);
+  assert_eq!(doc.to_string(), html);
+  assert_eq!(
+    document::roots(&doc)
+      .map(|root| (document::block_kind(&root), root.to_string()))
+      .collect::<Vec<_>>(),
+    vec![
+      (BlockKind::Attribute, "top: 0".to_string()),
+      (BlockKind::Attribute, "left:0".to_string()),
+      (BlockKind::Style, "a{}".to_string()),
+      (BlockKind::Style, "".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn stringify_splices_transformed_css() {
+  let html =
+    r#"<a style="color: red">x</a><b style='color: red'></b><style>a { color: red }</style>"#;
+  let doc = document::parse(html);
+  let result = document::stringify(&doc, |root| {
+    root.to_string().replace("red", r#"url("a'b")"#)
+  });
+  assert_eq!(
+    result,
+    r#"<a style="color: url(&quot;a'b&quot;)">x</a><b style='color: url("a&#39;b")'></b><style>a { color: url("a'b") }</style>"#
+  );
+}
+
+#[test]
+fn edits_roots_in_place() {
+  let html = "<style>\na {\n  color: red;\n}\n</style>";
+  let doc = document::parse(html).clone_for_update();
+  let decl = doc
+    .descendants()
+    .find(|node| node.kind() == SyntaxKind::Declaration)
+    .unwrap();
+  ted::insert_after(&decl, make::decl("width", "1px"));
+  assert_eq!(
+    doc.to_string(),
+    "<style>\na {\n  color: red;\n  width: 1px;\n}\n</style>"
+  );
+}
+
+#[test]
+fn keeps_blocks_with_errors_as_text() {
+  let svelte = r#"<div style="color: {color}">{name}</div><style>a { top: 0 }</style>"#;
+  let (doc, errors) = document::parse_with_errors(svelte);
+  assert_eq!(doc.to_string(), svelte);
+  assert_eq!(document::roots(&doc).count(), 1);
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].offset, svelte.find("}\"").unwrap());
+
+  for html in ["<style>}</style>", "<style>a { content: \"x }</style><p>"] {
+    let (doc, errors) = document::parse_with_errors(html);
+    assert_eq!(doc.to_string(), html);
+    assert_eq!(document::roots(&doc).count(), 0);
+    assert_eq!(errors.len(), 1, "{}", html);
+  }
+}
@@ -0,0 +1,107 @@
+use rowan::{TextRange, TextSize};
+use rowan_parser::formatter::{format_on_type, format_range, FormatOptions, TextEdit};
+use rowan_parser::parser::Parser;
+
+fn range(css: &str, needle: &str) -> TextRange {
+  let start = css.find(needle).unwrap();
+  TextRange::new(
+    TextSize::from(start as u32),
+    TextSize::from((start + needle.len()) as u32),
+  )
+}
+
+fn format_selection(css: &str, selection: &str) -> (String, Vec<TextEdit>) {
+  let root = Parser::new(css).parse();
+  let edits = format_range(&root, range(css, selection), FormatOptions::default());
+  (TextEdit::apply(css, &edits), edits)
+}
+
+#[test]
+fn formats_only_the_selected_declaration() {
+  let css = "a {\n  color:red;\n  top:0;\n}\nb{left:0}\n";
+  let (output, edits) = format_selection(css, "color");
+  assert_eq!(output, "a {\n  color: red;\n  top:0;\n}\nb{left:0}\n");
+  assert_eq!(
+    edits,
+    vec![TextEdit {
+      range: TextRange::empty(TextSize::from(12)),
+      new_text: " ".into(),
+    }]
+  );
+}
+
+#[test]
+fn formats_the_statements_touched_by_the_selection() {
+  let css = "a {\n  color:red;\n    top:0;\n  left:0;\n}\nb{left:0}\n";
+  let (output, _) = format_selection(css, "red;\n    top");
+  assert_eq!(
+    output,
+    "a {\n  color: red;\n  top: 0;\n  left:0;\n}\nb{left:0}\n"
+  );
+}
+
+#[test]
+fn formats_the_whole_rule_from_its_selector() {
+  let css = "a>b{color:red}\nc{top:0}\n";
+  let (output, _) = format_selection(css, "a>b");
+  assert_eq!(output, "a > b {\n  color: red;\n}\nc{top:0}\n");
+}
+
+#[test]
+fn keeps_inline_statements_on_their_line() {
+  let css = "a { color:red; top:0 }";
+  let (output, edits) = format_selection(css, "top");
+  assert_eq!(output, "a { color:red; top: 0; }");
+  assert_eq!(edits.len(), 1);
+}
+
+#[test]
+fn returns_no_edits_for_formatted_code() {
+  let css = "a {\n  color: red;\n}\n";
+  let root = Parser::new(css).parse();
+  let all = TextRange::new(0.into(), TextSize::from(css.len() as u32));
+  assert_eq!(format_range(&root, all, FormatOptions::default()), vec![]);
+}
+
+#[test]
+fn edits_are_minimal() {
+  let css = "a {\n  color: red;\n  top:0;\n  left: 0;\n}\n";
+  let root = Parser::new(css).parse();
+  let all = TextRange::new(0.into(), TextSize::from(css.len() as u32));
+  let edits = format_range(&root, all, FormatOptions::default());
+  assert_eq!(
+    edits,
+    vec![TextEdit {
+      range: TextRange::empty(TextSize::from(24)),
+      new_text: " ".into(),
+    }]
+  );
+  assert_eq!(
+    TextEdit::apply(css, &edits),
+    "a {\n  color: red;\n  top: 0;\n  left: 0;\n}\n"
+  );
+}
+
+#[test]
+fn formats_on_type() {
+  let css = "a {\n  color:red;\n  top:0;\n}\nb{left:0}";
+  let root = Parser::new(css).parse();
+  let semicolon = TextSize::from((css.find("red;").unwrap() + 4) as u32);
+  let edits = format_on_type(&root, semicolon, ';', FormatOptions::default());
+  assert_eq!(
+    TextEdit::apply(css, &edits),
+    "a {\n  color: red;\n  top:0;\n}\nb{left:0}"
+  );
+
+  let curly = TextSize::from(css.len() as u32);
+  let edits = format_on_type(&root, curly, '}', FormatOptions::default());
+  assert_eq!(
+    TextEdit::apply(css, &edits),
+    "a {\n  color:red;\n  top:0;\n}\nb {\n  left: 0;\n}"
+  );
+
+  assert_eq!(
+    format_on_type(&root, curly, 'x', FormatOptions::default()),
+    vec![]
+  );
+}
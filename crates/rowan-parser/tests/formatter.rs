@@ -0,0 +1,187 @@
+use rowan_parser::formatter::{format, FormatOptions, IndentStyle, QuoteStyle};
+use rowan_parser::parser::Parser;
+use rowan_parser::syntax::SyntaxKind;
+use std::fs::read_to_string;
+
+fn fmt(css: &str) -> String {
+  fmt_with(css, FormatOptions::default())
+}
+
+fn fmt_with(css: &str, options: FormatOptions) -> String {
+  format(&Parser::new(css).parse(), options)
+}
+
+fn comments(css: &str) -> Vec<String> {
+  Parser::new(css)
+    .parse()
+    .descendants_with_tokens()
+    .filter_map(|element| element.into_token())
+    .filter(|token| token.kind() == SyntaxKind::Comment)
+    .map(|token| token.text().to_string())
+    .collect()
+}
+
+#[test]
+fn formats_rules_and_declarations() {
+  assert_eq!(
+    fmt("a{color:red;width : 1px}b{}"),
+    "a {\n  color: red;\n  width: 1px;\n}\nb {\n}\n"
+  );
+}
+
+#[test]
+fn formats_at_rules() {
+  assert_eq!(
+    fmt("@import  url(foo.css)  screen;@media screen and (min-width:480px){a{color:red}}"),
+    "@import url(foo.css) screen;\n@media screen and (min-width: 480px) {\n  a {\n    color: red;\n  }\n}\n"
+  );
+  let options = FormatOptions {
+    normalize_at_rule_params: false,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with("@media screen and (min-width:480px){}", options),
+    "@media screen and (min-width:480px) {\n}\n"
+  );
+}
+
+#[test]
+fn formats_selectors() {
+  assert_eq!(
+    fmt("a>b,c  +  d,e~f ,[type = \"text\"],:is(a,b){}"),
+    "a > b,\nc + d,\ne ~ f,\n[type=\"text\"],\n:is(a, b) {\n}\n"
+  );
+  let options = FormatOptions {
+    selector_per_line: false,
+    ..Default::default()
+  };
+  assert_eq!(fmt_with("a,b{}", options), "a, b {\n}\n");
+}
+
+#[test]
+fn formats_values() {
+  assert_eq!(
+    fmt("a{color:#FFF;margin:0  auto;background:rgba( 0,0,0,.5 ) url( a.png );z-index:1!IMPORTANT}"),
+    "a {\n  color: #fff;\n  margin: 0 auto;\n  background: rgba(0, 0, 0, .5) url(a.png);\n  z-index: 1 !important;\n}\n"
+  );
+  let options = FormatOptions {
+    lowercase_hex: false,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with("a{color:#FFF}", options),
+    "a {\n  color: #FFF;\n}\n"
+  );
+  assert_eq!(fmt("a{--x:  {a:b}  ;}"), "a {\n  --x: {a:b};\n}\n");
+}
+
+#[test]
+fn breaks_long_comma_separated_values() {
+  assert_eq!(
+    fmt("a{transition:color .15s ease-in-out,background-color .15s ease-in-out,border-color .15s ease-in-out}"),
+    "a {\n  transition:\n    color .15s ease-in-out,\n    background-color .15s ease-in-out,\n    border-color .15s ease-in-out;\n}\n"
+  );
+  let options = FormatOptions {
+    line_width: 200,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with("a{font-family:a,b}", options),
+    "a {\n  font-family: a, b;\n}\n"
+  );
+}
+
+#[test]
+fn indents_with_tabs_or_spaces() {
+  let css = "@media print{a{color:red}}";
+  let options = FormatOptions {
+    indent_style: IndentStyle::Tab,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with(css, options),
+    "@media print {\n\ta {\n\t\tcolor: red;\n\t}\n}\n"
+  );
+  let options = FormatOptions {
+    indent_style: IndentStyle::Space(4),
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with(css, options),
+    "@media print {\n    a {\n        color: red;\n    }\n}\n"
+  );
+}
+
+#[test]
+fn changes_quotes() {
+  let css = "a{content:'a';font-family:\"b\",'it\"s'}";
+  assert_eq!(
+    fmt(css),
+    "a {\n  content: \"a\";\n  font-family: \"b\", 'it\"s';\n}\n"
+  );
+  let options = FormatOptions {
+    quote_style: QuoteStyle::Single,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with(css, options),
+    "a {\n  content: 'a';\n  font-family: 'b', 'it\"s';\n}\n"
+  );
+  let options = FormatOptions {
+    quote_style: QuoteStyle::Preserve,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with(css, options),
+    "a {\n  content: 'a';\n  font-family: \"b\", 'it\"s';\n}\n"
+  );
+}
+
+#[test]
+fn preserves_blank_lines() {
+  let css = "a{}\n\n\n\nb{\n\n  color: red;\n\n  top: 0;\n  left: 0;\n}";
+  assert_eq!(
+    fmt(css),
+    "a {\n}\n\nb {\n  color: red;\n\n  top: 0;\n  left: 0;\n}\n"
+  );
+  let options = FormatOptions {
+    preserve_blank_lines: false,
+    ..Default::default()
+  };
+  assert_eq!(
+    fmt_with(css, options),
+    "a {\n}\nb {\n  color: red;\n  top: 0;\n  left: 0;\n}\n"
+  );
+}
+
+#[test]
+fn keeps_comments_in_place() {
+  let css = "/* head */\na /* sel */ {color : red; /* trailing */\n/* own line */\ntop/* p */:/* v */0 /* in value */}";
+  assert_eq!(
+    fmt(css),
+    "/* head */\na /* sel */ {\n  color: red; /* trailing */\n  /* own line */\n  top /* p */: /* v */ 0 /* in value */;\n}\n"
+  );
+}
+
+#[test]
+fn formats_assets_idempotently() {
+  let file_list = [
+    "simple.css",
+    "utf8.css",
+    "bootstrap-reboot.css",
+    "bootstrap.css",
+    "tailwind-components.css",
+    "tailwind.css",
+  ];
+  for file_name in file_list {
+    let css = read_to_string(format!("../../assets/{}", file_name)).unwrap();
+    let formatted = fmt(&css);
+    assert_eq!(
+      fmt(&formatted),
+      formatted,
+      "{} is not idempotent",
+      file_name
+    );
+    assert_eq!(comments(&formatted), comments(&css), "{}", file_name);
+  }
+}
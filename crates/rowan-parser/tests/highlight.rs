@@ -0,0 +1,104 @@
+use rowan_parser::highlight::{highlight, highlight_as_html, HighlightTag};
+use rowan_parser::parser::Parser;
+use std::fs::read_to_string;
+
+fn tags(css: &str) -> Vec<(String, HighlightTag)> {
+  let root = Parser::new(css).parse();
+  highlight(&root)
+    .into_iter()
+    .map(|range| {
+      (
+        css[std::ops::Range::<usize>::from(range.range)].to_string(),
+        range.tag,
+      )
+    })
+    .collect()
+}
+
+fn tagged(css: &str, text: &str) -> HighlightTag {
+  tags(css)
+    .into_iter()
+    .find(|(t, _)| t == text)
+    .unwrap_or_else(|| panic!("{} is not highlighted", text))
+    .1
+}
+
+#[test]
+fn highlights_selectors() {
+  let css = "div.a#b > [type=\"text\"]:hover::before, *:not(.c):nth-child(2n+1) {}";
+  assert_eq!(
+    tags(css),
+    vec![
+      ("div".into(), HighlightTag::SelectorTag),
+      (".a".into(), HighlightTag::SelectorClass),
+      ("#b".into(), HighlightTag::SelectorId),
+      ("[type=\"text\"]".into(), HighlightTag::SelectorAttribute),
+      (":hover".into(), HighlightTag::SelectorPseudo),
+      ("::before".into(), HighlightTag::SelectorPseudo),
+      ("*".into(), HighlightTag::SelectorTag),
+      (":not".into(), HighlightTag::SelectorPseudo),
+      (".c".into(), HighlightTag::SelectorClass),
+      (":nth-child".into(), HighlightTag::SelectorPseudo),
+    ]
+  );
+}
+
+#[test]
+fn highlights_properties() {
+  let css = "a { color: red; -webkit-transition: none; colr: red; --main: 1px }";
+  assert_eq!(tagged(css, "color"), HighlightTag::Property);
+  assert_eq!(tagged(css, "-webkit-transition"), HighlightTag::Property);
+  assert_eq!(tagged(css, "colr"), HighlightTag::UnknownProperty);
+  assert_eq!(tagged(css, "--main"), HighlightTag::CustomProperty);
+}
+
+#[test]
+fn highlights_values() {
+  let css = "a { margin: -1.5em 0 50%; color: #FFF; border: 1px solid Red; background: rgba(0, 0, 0, .5) url(\"a.png\"); content: 'x' !important }";
+  assert_eq!(tagged(css, "-1.5em"), HighlightTag::Number);
+  assert_eq!(tagged(css, "50%"), HighlightTag::Number);
+  assert_eq!(tagged(css, ".5"), HighlightTag::Number);
+  assert_eq!(tagged(css, "#FFF"), HighlightTag::Color);
+  assert_eq!(tagged(css, "Red"), HighlightTag::Color);
+  assert_eq!(tagged(css, "rgba"), HighlightTag::Function);
+  assert_eq!(tagged(css, "url(\"a.png\")"), HighlightTag::Url);
+  assert_eq!(tagged(css, "'x'"), HighlightTag::String);
+  assert_eq!(tagged(css, "!important"), HighlightTag::Important);
+  assert!(!tags(css).iter().any(|(text, _)| text == "solid"));
+}
+
+#[test]
+fn highlights_at_rules_and_comments() {
+  let css = "/* a */@media (min-width: 480px) { @keyframes x { 50% { top: 0 } } }";
+  assert_eq!(tagged(css, "/* a */"), HighlightTag::Comment);
+  assert_eq!(tagged(css, "@media"), HighlightTag::AtRule);
+  assert_eq!(tagged(css, "480px"), HighlightTag::Number);
+  assert_eq!(tagged(css, "@keyframes"), HighlightTag::AtRule);
+  assert_eq!(tagged(css, "50%"), HighlightTag::Number);
+}
+
+#[test]
+fn renders_html() {
+  let root = Parser::new("a>b { color: red }").parse();
+  assert_eq!(
+    highlight_as_html(&root),
+    "<span class=\"selector-tag\">a</span>&gt;<span class=\"selector-tag\">b</span> { <span class=\"property\">color</span>: <span class=\"color\">red</span> }"
+  );
+}
+
+#[test]
+fn ranges_are_sorted_and_disjoint() {
+  let file_list = ["bootstrap.css", "tailwind-components.css", "utf8.css"];
+  for file_name in file_list {
+    let css = read_to_string(format!("../../assets/{}", file_name)).unwrap();
+    let ranges = highlight(&Parser::new(&css).parse());
+    assert!(!ranges.is_empty());
+    for pair in ranges.windows(2) {
+      assert!(
+        pair[0].range.end() <= pair[1].range.start(),
+        "{}",
+        file_name
+      );
+    }
+  }
+}
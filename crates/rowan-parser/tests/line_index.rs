@@ -0,0 +1,28 @@
+use rowan_parser::highlight::{highlight, HighlightTag};
+use rowan_parser::line_cols;
+use rowan_parser::line_index::{Encoding, LineCol, LineIndex};
+use rowan_parser::parser::Parser;
+
+#[test]
+fn converts_tree_ranges() {
+  let css = "/* 😀 */\n.é { color: red }";
+  let root = Parser::new(css).parse();
+  let index = LineIndex::new(css);
+  let property = highlight(&root)
+    .into_iter()
+    .find(|range| range.tag == HighlightTag::Property)
+    .unwrap();
+  let at = |line, col| LineCol { line, col };
+  assert_eq!(
+    line_cols(&index, property.range, Encoding::Utf16),
+    Some((at(1, 5), at(1, 10)))
+  );
+  assert_eq!(
+    line_cols(&index, property.range, Encoding::Utf8),
+    Some((at(1, 6), at(1, 11)))
+  );
+  assert_eq!(
+    line_cols(&index, root.text_range(), Encoding::Utf16),
+    Some((at(0, 0), at(1, 17)))
+  );
+}
@@ -0,0 +1,116 @@
+use rowan_parser::syntax::SyntaxKind;
+use rowan_parser::{document, markdown};
+
+const README: &str = r#"# Buttons
+
+Use the `.btn` class:
+
+```css
+.btn {
+  color: red;
+}
+```
+
+Or set a single property:
+
+~~~CSS title="inline"
+color: blue;
+~~~
+
+```scss
+// a variable
+$gap: 4px;
+.a { .b { margin: $gap } }
+```
+
+````markdown
+```css
+ignored {}
+```
+````
+
+```js
+const css = "ignored {}";
+```
+"#;
+
+#[test]
+fn extracts_stylesheet_fences() {
+  let fences = markdown::extract(README);
+  assert_eq!(
+    fences
+      .iter()
+      .map(|fence| (fence.lang, &README[fence.range]))
+      .collect::<Vec<_>>(),
+    vec![
+      ("css", ".btn {\n  color: red;\n}\n"),
+      ("CSS", "color: blue;\n"),
+      (
+        "scss",
+        "// a variable\n$gap: 4px;\n.a { .b { margin: $gap } }\n"
+      ),
+    ]
+  );
+}
+
+#[test]
+fn parses_fences_into_a_document() {
+  let doc = markdown::parse(README);
+  assert_eq!(doc.kind(), SyntaxKind::Document);
+  assert_eq!(doc.to_string(), README);
+  let roots = document::roots(&doc).collect::<Vec<_>>();
+  assert_eq!(roots.len(), 3);
+  assert_eq!(
+    roots[1].first_child().unwrap().kind(),
+    SyntaxKind::Declaration
+  );
+  assert_eq!(
+    roots[2]
+      .children()
+      .map(|node| node.kind())
+      .collect::<Vec<_>>(),
+    vec![
+      SyntaxKind::Comment,
+      SyntaxKind::Declaration,
+      SyntaxKind::Rule
+    ]
+  );
+
+  // ranges are offsets into the Markdown file
+  let decl = roots[0]
+    .descendants()
+    .find(|node| node.kind() == SyntaxKind::Declaration)
+    .unwrap();
+  assert_eq!(&README[decl.text_range()], "color: red");
+}
+
+#[test]
+fn stringify_writes_fences_back() {
+  let md = "```css\na{color:red}\n```\n\n```css\n```\n";
+  let doc = markdown::parse(md);
+  assert_eq!(markdown::stringify(&doc, |root| root.to_string()), md);
+  let result = markdown::stringify(&doc, |root| {
+    if root.text_range().is_empty() {
+      "b {}".to_string()
+    } else {
+      root
+        .to_string()
+        .replace("a{color:red}\n", "a {\n  color: red;\n}")
+    }
+  });
+  assert_eq!(
+    result,
+    "```css\na {\n  color: red;\n}\n```\n\n```css\nb {}\n```\n"
+  );
+}
+
+#[test]
+fn keeps_fences_that_dont_parse_as_text() {
+  let md = "```css\n.a\n```\n\n```css\na {\n```\n\n```css\nred\n```\n\n```less\n.a { .mixin(); }\n```\n\n```css\nb { top: 0 }\n```\n";
+  let (doc, errors) = markdown::parse_with_errors(md);
+  assert_eq!(doc.to_string(), md);
+  assert_eq!(errors.len(), 4);
+  let roots = document::roots(&doc).collect::<Vec<_>>();
+  assert_eq!(roots.len(), 1);
+  assert_eq!(roots[0].to_string(), "b { top: 0 }\n");
+}
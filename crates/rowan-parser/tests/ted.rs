@@ -0,0 +1,164 @@
+use rowan_parser::parser::Parser;
+use rowan_parser::syntax::{SyntaxKind, SyntaxNode};
+use rowan_parser::{make, ted};
+
+fn parse(css: &str) -> SyntaxNode {
+  Parser::new(css).parse().clone_for_update()
+}
+
+fn nth(root: &SyntaxNode, kind: SyntaxKind, n: usize) -> SyntaxNode {
+  root
+    .descendants()
+    .filter(|node| node.kind() == kind)
+    .nth(n)
+    .unwrap()
+}
+
+#[test]
+fn make_builds_nodes() {
+  assert_eq!(make::decl("color", "red").to_string(), "color: red");
+  assert_eq!(make::rule(".a", vec![]).to_string(), ".a {}");
+  assert_eq!(
+    make::rule(
+      ".a",
+      vec![make::decl("color", "red"), make::decl("width", "1px")]
+    )
+    .to_string(),
+    ".a {\n  color: red;\n  width: 1px;\n}"
+  );
+  assert_eq!(
+    make::at_rule("import", "url(a.css)").to_string(),
+    "@import url(a.css);"
+  );
+  assert_eq!(make::comment("todo").to_string(), "/* todo */");
+  assert_eq!(make::decl("color", "red").kind(), SyntaxKind::Declaration);
+}
+
+#[test]
+fn whitespace_before_semicolon_and_curly_is_outside_declarations() {
+  let root = parse("a { color: red ; top: 0\n}");
+  let rule = nth(&root, SyntaxKind::Rule, 0);
+  let block = rule
+    .children_with_tokens()
+    .filter(|element| element.kind() != SyntaxKind::Selector)
+    .map(|element| (element.kind(), element.to_string()))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    block,
+    vec![
+      (SyntaxKind::OpenCurly, "{".to_string()),
+      (SyntaxKind::Space, " ".to_string()),
+      (SyntaxKind::Declaration, "color: red".to_string()),
+      (SyntaxKind::Space, " ".to_string()),
+      (SyntaxKind::Semicolon, ";".to_string()),
+      (SyntaxKind::Space, " ".to_string()),
+      (SyntaxKind::Declaration, "top: 0".to_string()),
+      (SyntaxKind::Space, "\n".to_string()),
+      (SyntaxKind::CloseCurly, "}".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn insert_after_declaration() {
+  let root = parse("a {\n  color: red;\n  top: 0;\n}\n");
+  ted::insert_after(
+    &nth(&root, SyntaxKind::Declaration, 0),
+    make::decl("width", "1px"),
+  );
+  assert_eq!(
+    root.to_string(),
+    "a {\n  color: red;\n  width: 1px;\n  top: 0;\n}\n"
+  );
+}
+
+#[test]
+fn insert_after_last_declaration_without_semicolon() {
+  let root = parse("a { color: red }");
+  ted::insert_after(
+    &nth(&root, SyntaxKind::Declaration, 0),
+    make::decl("width", "1px"),
+  );
+  assert_eq!(root.to_string(), "a { color: red; width: 1px }");
+}
+
+#[test]
+fn insert_before_declaration() {
+  let root = parse("a {\n  color: red;\n}");
+  ted::insert_before(
+    &nth(&root, SyntaxKind::Declaration, 0),
+    make::decl("width", "1px"),
+  );
+  assert_eq!(root.to_string(), "a {\n  width: 1px;\n  color: red;\n}");
+}
+
+#[test]
+fn append_child_to_rule() {
+  let root = parse("a {\n  color: red;\n}\nb {}\nc {\n}");
+  ted::append_child(&nth(&root, SyntaxKind::Rule, 0), make::decl("top", "0"));
+  ted::append_child(&nth(&root, SyntaxKind::Rule, 1), make::decl("top", "0"));
+  ted::append_child(&nth(&root, SyntaxKind::Rule, 2), make::decl("top", "0"));
+  assert_eq!(
+    root.to_string(),
+    "a {\n  color: red;\n  top: 0;\n}\nb { top: 0; }\nc {\n  top: 0;\n}"
+  );
+}
+
+#[test]
+fn append_rule_reindents_it() {
+  let root = parse("@media print {\n  a {}\n}\n\nb {}\n");
+  let rule = make::rule(".c", vec![make::decl("color", "red")]);
+  ted::append_child(&nth(&root, SyntaxKind::AtRule, 0), rule);
+  ted::append_child(&root, make::rule(".d", vec![]));
+  assert_eq!(
+    root.to_string(),
+    "@media print {\n  a {}\n  .c {\n    color: red;\n  }\n}\n\nb {}\n\n.d {}\n"
+  );
+}
+
+#[test]
+fn replace_keeps_surroundings() {
+  let root = parse("a { color: red; top: 0 }");
+  ted::replace(
+    &nth(&root, SyntaxKind::Declaration, 0),
+    make::decl("color", "blue"),
+  );
+  assert_eq!(root.to_string(), "a { color: blue; top: 0 }");
+}
+
+#[test]
+fn remove_declaration() {
+  let root = parse("a {\n  color: red;\n  top: 0;\n}");
+  ted::remove(&nth(&root, SyntaxKind::Declaration, 0));
+  assert_eq!(root.to_string(), "a {\n  top: 0;\n}");
+  ted::remove(&nth(&root, SyntaxKind::Declaration, 0));
+  assert_eq!(root.to_string(), "a {\n}");
+
+  let root = parse("a {}\nb {}\n");
+  ted::remove(&nth(&root, SyntaxKind::Rule, 0));
+  assert_eq!(root.to_string(), "b {}\n");
+}
+
+#[test]
+fn untouched_source_stays_identical() {
+  let css = include_str!("../../../assets/bootstrap-reboot.css");
+  let root = parse(css);
+  let first = root
+    .descendants()
+    .find(|node| node.kind() == SyntaxKind::Declaration)
+    .unwrap();
+  let text = first.to_string();
+  ted::insert_after(&first, make::decl("color", "red"));
+  let output = root.to_string();
+  let offset: usize = first.text_range().end().into();
+  assert_eq!(&output[..offset], &css[..offset]);
+  assert!(output[offset..].starts_with(";\n  color: red;"));
+  assert_eq!(
+    output.replacen(
+      &format!("{};\n  color: red;", text),
+      &format!("{};", text),
+      1
+    ),
+    css
+  );
+}
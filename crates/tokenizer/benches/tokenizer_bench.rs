@@ -4,13 +4,24 @@ use tokenizer::Tokenizer;
 
 const SMALL_CSS_FILE: &str = include_str!("../../../assets/bootstrap-reboot.css");
 const LARGE_CSS_FILE: &str = include_str!("../../../assets/bootstrap.css");
+const HUGE_CSS_FILE: &str = include_str!("../../../assets/tailwind.css");
 
 fn tokenize<'a>(css: &'a str, ignore_errors: bool) {
   let input: Input<'a> = Input::new(css, None);
   let processor: Tokenizer<'a> = Tokenizer::new(input.css, ignore_errors);
-  while !processor.end_of_file() {
-    processor.next_token(false);
-  }
+  for _ in processor {}
+}
+
+/// long words, where the scanning of word ends matters most
+fn long_words() -> String {
+  (0..20000)
+    .map(|i| {
+      format!(
+        ".generated-utility-class-name-for-component-{:08} {{ font-family: fallback-font-family-name-{}; }}\n",
+        i, i
+      )
+    })
+    .collect()
 }
 
 fn tokenize_bench(c: &mut Criterion) {
@@ -20,6 +31,13 @@ fn tokenize_bench(c: &mut Criterion) {
   c.bench_function("tokenizer/large(201K)", |b| {
     b.iter_with_large_drop(|| tokenize(LARGE_CSS_FILE, false));
   });
+  c.bench_function("tokenizer/huge(3.5M)", |b| {
+    b.iter_with_large_drop(|| tokenize(HUGE_CSS_FILE, false));
+  });
+  let long_words = long_words();
+  c.bench_function("tokenizer/long-words(2M)", |b| {
+    b.iter_with_large_drop(|| tokenize(&long_words, false));
+  });
 }
 
 criterion_group!(benches, tokenize_bench);
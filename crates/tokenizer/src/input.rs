@@ -1,4 +1,6 @@
-use once_cell::sync::Lazy;
+use crate::line_index::{Encoding, LineCol, LineIndex};
+use once_cell::sync::{Lazy, OnceCell};
+use std::borrow::Cow;
 use std::fmt;
 
 #[derive(Debug)]
@@ -10,11 +12,11 @@ pub struct FilePosition {
   column: u32,
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
+/// A one-based position like postcss reports it, `col` counts UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Position {
-  line: u32,
-  col: u32,
+  pub line: u32,
+  pub col: u32,
 }
 
 impl fmt::Display for FilePosition {
@@ -37,6 +39,7 @@ pub struct Input<'a> {
   has_bom: bool,
   line: u32,
   column: u32,
+  line_index: OnceCell<LineIndex>,
 }
 
 impl<'a> Default for &'a Input<'a> {
@@ -58,18 +61,46 @@ impl<'a> fmt::Display for Input<'a> {
 #[derive(Debug)]
 pub struct ProcessOptions {}
 
+const BOM: char = '\u{feff}';
+
 impl<'a> Input<'a> {
+  /// A leading byte-order mark is stripped from `css`, see [`Input::has_bom`].
   pub fn new(css: &'a str, _opts: Option<ProcessOptions>) -> Input<'a> {
+    let (css, has_bom) = match css.strip_prefix(BOM) {
+      Some(css) => (css, true),
+      None => (css, false),
+    };
     Input {
       css,
       file: Some(String::new()),
       id: Some(String::from("123")),
-      has_bom: false,
+      has_bom,
       line: 1,
       column: 1,
+      line_index: OnceCell::new(),
+    }
+  }
+
+  /// whether the source started with a byte-order mark
+  pub fn has_bom(&self) -> bool {
+    self.has_bom
+  }
+
+  /// Put the byte-order mark back in front of `output` if the source had one.
+  pub fn with_bom<'b>(&self, output: &'b str) -> Cow<'b, str> {
+    if self.has_bom && !output.starts_with(BOM) {
+      Cow::Owned(format!("{}{}", BOM, output))
+    } else {
+      Cow::Borrowed(output)
     }
   }
 
+  /// The encoding declared by a leading `@charset "…";`, which has to be the very first
+  /// thing in the stylesheet, written exactly like that.
+  pub fn charset(&self) -> Option<&'a str> {
+    charset(self.css.as_bytes()).map(|label| std::str::from_utf8(label).unwrap())
+  }
+
   pub fn from(&self) -> String {
     String::from("/home/ai/a.css")
   }
@@ -84,10 +115,95 @@ impl<'a> Input<'a> {
     })
   }
 
+  /// the line index of `css`, built on first use
+  pub fn line_index(&self) -> &LineIndex {
+    self.line_index.get_or_init(|| LineIndex::new(self.css))
+  }
+
+  /// The position of a byte offset into `css`, `None` past its end.
   pub fn from_offset(&self, offset: u32) -> Option<Position> {
+    let LineCol { line, col } = self
+      .line_index()
+      .line_col(offset as usize, Encoding::Utf16)?;
     Some(Position {
-      line: 1,
-      col: offset,
+      line: line as u32 + 1,
+      col: col as u32 + 1,
     })
   }
 }
+
+/// Decode a stylesheet to UTF-8 the way browsers do: a byte-order mark decides between UTF-8 and
+/// UTF-16, otherwise a leading `@charset` may select Latin-1 (decoded as windows-1252, like the
+/// WHATWG encoding standard does), anything else is UTF-8. Invalid sequences become U+FFFD.
+///
+/// A byte-order mark is kept as `U+FEFF`, so [`Input::new`] can record it.
+pub fn decode(bytes: &[u8]) -> Cow<'_, str> {
+  if bytes.starts_with(&[0xfe, 0xff]) {
+    return Cow::Owned(decode_utf16(bytes, u16::from_be_bytes));
+  }
+  if bytes.starts_with(&[0xff, 0xfe]) {
+    return Cow::Owned(decode_utf16(bytes, u16::from_le_bytes));
+  }
+  if !bytes.starts_with(BOM.to_string().as_bytes()) {
+    if let Some(label) = charset(bytes) {
+      let label = String::from_utf8_lossy(label).trim().to_ascii_lowercase();
+      if WINDOWS_1252_LABELS.contains(&label.as_str()) {
+        return Cow::Owned(bytes.iter().map(|&byte| windows_1252(byte)).collect());
+      }
+    }
+  }
+  String::from_utf8_lossy(bytes)
+}
+
+/// the label of `@charset "label";`
+fn charset(bytes: &[u8]) -> Option<&[u8]> {
+  const PREFIX: &[u8] = b"@charset \"";
+  let rest = bytes.strip_prefix(PREFIX)?;
+  let end = rest.iter().take(1024).position(|&byte| byte == b'"')?;
+  if rest.get(end + 1) == Some(&b';') {
+    Some(&rest[..end])
+  } else {
+    None
+  }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+  let units = bytes
+    .chunks(2)
+    .map(|pair| from_bytes([pair[0], *pair.get(1).unwrap_or(&0)]));
+  char::decode_utf16(units)
+    .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+    .collect()
+}
+
+const WINDOWS_1252_LABELS: [&str; 17] = [
+  "ansi_x3.4-1968",
+  "ascii",
+  "cp1252",
+  "cp819",
+  "csisolatin1",
+  "ibm819",
+  "iso-8859-1",
+  "iso-ir-100",
+  "iso8859-1",
+  "iso88591",
+  "iso_8859-1",
+  "iso_8859-1:1987",
+  "l1",
+  "latin1",
+  "us-ascii",
+  "windows-1252",
+  "x-cp1252",
+];
+
+fn windows_1252(byte: u8) -> char {
+  const HIGH: [u16; 32] = [
+    0x20ac, 0x0081, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0x008d, 0x017d, 0x008f, 0x0090, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x02dc, 0x2122, 0x0161, 0x203a, 0x0153, 0x009d, 0x017e, 0x0178,
+  ];
+  match byte {
+    0x80..=0x9f => char::from_u32(HIGH[(byte - 0x80) as usize] as u32).unwrap(),
+    _ => byte as char,
+  }
+}
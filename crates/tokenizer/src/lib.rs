@@ -1,17 +1,24 @@
 pub mod input;
+pub mod line_index;
 mod tokenizer;
 
 pub mod ref_ring;
 pub use crate::tokenizer::*;
 
 pub mod list;
+pub mod newline;
+pub mod spec;
+pub mod stream;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
 
 /// **Not recommend**   
 /// this method is a allocated version of tokenize which is inefficient, useful when debug.
 /// almost three times slower than a non allocated on demand tokenize (just use )
 pub fn tokenize(input: &str) -> Vec<Token> {
   let mut res = vec![];
-  let tokenizer = Tokenizer::new(input, false);
+  let mut tokenizer = Tokenizer::new(input, false);
   while !tokenizer.end_of_file() {
     res.push(tokenizer.next_token(true));
   }
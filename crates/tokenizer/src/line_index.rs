@@ -0,0 +1,164 @@
+//! Byte offsets to line/column positions and back.
+//!
+//! Offsets in this crate are byte offsets into UTF-8, while editors count columns in other
+//! units: the language server protocol and JavaScript count UTF-16 code units, other tools
+//! count chars. A [`LineIndex`] is built once per source and answers both directions with a
+//! binary search, only the non-ASCII chars are remembered besides the line starts.
+//!
+//! ```
+//! use tokenizer::line_index::{Encoding, LineCol, LineIndex};
+//!
+//! let index = LineIndex::new("a {}\n.😀 { color: red }");
+//! let position = index.line_col(11, Encoding::Utf16).unwrap();
+//! assert_eq!(position, LineCol { line: 1, col: 4 });
+//! assert_eq!(index.offset(position, Encoding::Utf16), Some(11));
+//! ```
+
+/// The unit columns are counted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+  /// bytes of UTF-8
+  Utf8,
+  /// code units of UTF-16, what JavaScript strings and the language server protocol use
+  Utf16,
+  /// unicode scalar values, i.e. `char`s
+  Char,
+}
+
+impl Encoding {
+  fn len(self, ch: char) -> usize {
+    match self {
+      Encoding::Utf8 => ch.len_utf8(),
+      Encoding::Utf16 => ch.len_utf16(),
+      Encoding::Char => 1,
+    }
+  }
+}
+
+/// A zero-based position, `col` is counted in some [`Encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct LineCol {
+  pub line: usize,
+  pub col: usize,
+}
+
+/// A char that takes more than one byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WideChar {
+  /// byte offset in the source
+  start: usize,
+  ch: char,
+}
+
+/// Line starts and non-ASCII chars of a source, see the [module docs](self).
+///
+/// Lines end after `\n`, so a `\r` of `\r\n` is the last column of its line.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LineIndex {
+  /// byte offset of the first char of each line, the first one is always `0`
+  line_starts: Vec<usize>,
+  /// sorted by `start`
+  wide_chars: Vec<WideChar>,
+  len: usize,
+}
+
+impl LineIndex {
+  pub fn new(text: &str) -> LineIndex {
+    let mut line_starts = vec![0];
+    let mut wide_chars = vec![];
+    if text.is_ascii() {
+      line_starts.extend(memchr::memchr_iter(b'\n', text.as_bytes()).map(|i| i + 1));
+    } else {
+      for (i, ch) in text.char_indices() {
+        if ch == '\n' {
+          line_starts.push(i + 1);
+        } else if !ch.is_ascii() {
+          wide_chars.push(WideChar { start: i, ch });
+        }
+      }
+    }
+    LineIndex {
+      line_starts,
+      wide_chars,
+      len: text.len(),
+    }
+  }
+
+  /// the number of lines, a trailing newline starts an empty last line
+  pub fn len_lines(&self) -> usize {
+    self.line_starts.len()
+  }
+
+  /// the byte range of `line` including its newline
+  pub fn line_range(&self, line: usize) -> Option<std::ops::Range<usize>> {
+    let start = *self.line_starts.get(line)?;
+    let end = self.line_starts.get(line + 1).copied().unwrap_or(self.len);
+    Some(start..end)
+  }
+
+  /// The position of a byte `offset`, `None` past the end of the source. An offset inside a
+  /// multi-byte char maps to the column of that char.
+  pub fn line_col(&self, offset: usize, encoding: Encoding) -> Option<LineCol> {
+    if offset > self.len {
+      return None;
+    }
+    let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+    let line_start = self.line_starts[line];
+    let mut col = offset - line_start;
+    if encoding != Encoding::Utf8 {
+      for wide in self.wide_chars_in(line_start..offset) {
+        let len = wide.ch.len_utf8();
+        // the bytes of `wide` before `offset`
+        let covered = len.min(offset - wide.start);
+        col -= covered;
+        if covered == len {
+          col += encoding.len(wide.ch);
+        }
+      }
+    }
+    Some(LineCol { line, col })
+  }
+
+  /// The byte offset of a position, `None` when the line does not exist or the column is past
+  /// its end (the newline included) or in the middle of a char.
+  pub fn offset(&self, position: LineCol, encoding: Encoding) -> Option<usize> {
+    let range = self.line_range(position.line)?;
+    let mut offset = range.start + position.col;
+    if encoding != Encoding::Utf8 {
+      let mut col = 0;
+      let mut byte = range.start;
+      for wide in self.wide_chars_in(range.clone()) {
+        let ascii = wide.start - byte;
+        if col + ascii >= position.col {
+          break;
+        }
+        col += ascii + encoding.len(wide.ch);
+        byte = wide.start + wide.ch.len_utf8();
+        if col > position.col {
+          return None;
+        }
+      }
+      offset = byte + (position.col - col);
+    }
+    let last_line = position.line + 1 == self.line_starts.len();
+    if offset > range.end
+      || (offset == range.end && !last_line)
+      || self
+        .wide_chars_in(offset..offset + 1)
+        .any(|w| w.start < offset)
+    {
+      return None;
+    }
+    Some(offset)
+  }
+
+  /// the wide chars overlapping `range`
+  fn wide_chars_in(&self, range: std::ops::Range<usize>) -> impl Iterator<Item = &WideChar> {
+    let first = self
+      .wide_chars
+      .partition_point(|wide| wide.start + wide.ch.len_utf8() <= range.start);
+    self.wide_chars[first..]
+      .iter()
+      .take_while(move |wide| wide.start < range.end)
+  }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 /// Safely splits values.
 pub fn split(input: &str, separators: Vec<char>, last: bool) -> Vec<String> {
   let mut array: Vec<String> = vec![];
@@ -59,3 +61,116 @@ pub fn space(input: &str) -> Vec<String> {
 pub fn comma(input: &str) -> Vec<String> {
   split(input, vec![','], true)
 }
+
+/// The items of a list as slices of the input together with their byte ranges, see [`spans`].
+#[derive(Debug, Clone)]
+pub struct Spans<'a> {
+  input: &'a str,
+  separators: &'a [char],
+  last: bool,
+  pos: usize,
+  finished: bool,
+}
+
+/// Like [`split`] without allocating: yields each trimmed item with its byte range in `input`,
+/// so one item can be rewritten in place. Separators inside `()`, `[]`, `{}`, strings and
+/// comments and escaped separators don't split.
+///
+/// ```
+/// use tokenizer::list::comma_spans;
+///
+/// let mut value = String::from("opacity 1s, transform calc(1s / 2) ease");
+/// let (_, range) = comma_spans(&value).nth(1).unwrap();
+/// value.replace_range(range, "none");
+/// assert_eq!(value, "opacity 1s, none");
+/// ```
+pub fn spans<'a>(input: &'a str, separators: &'a [char], last: bool) -> Spans<'a> {
+  Spans {
+    input,
+    separators,
+    last,
+    pos: 0,
+    finished: false,
+  }
+}
+
+/// [`space`] as [`spans`]
+pub fn space_spans(input: &str) -> Spans<'_> {
+  spans(input, &[' ', '\n', '\t', '\r', '\x0c'], false)
+}
+
+/// [`comma`] as [`spans`]
+pub fn comma_spans(input: &str) -> Spans<'_> {
+  spans(input, &[','], true)
+}
+
+impl<'a> Spans<'a> {
+  /// the offset and length of the next separator at the top level
+  fn find_separator(&self) -> Option<(usize, usize)> {
+    let mut chars = self.input[self.pos..]
+      .char_indices()
+      .map(|(i, ch)| (self.pos + i, ch))
+      .peekable();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escape = false;
+    let mut comment = false;
+    while let Some((i, ch)) = chars.next() {
+      if comment {
+        if ch == '*' && chars.next_if(|&(_, ch)| ch == '/').is_some() {
+          comment = false;
+        }
+      } else if escape {
+        escape = false;
+      } else if ch == '\\' {
+        escape = true;
+      } else if let Some(quote_char) = quote {
+        if ch == quote_char {
+          quote = None;
+        }
+      } else {
+        match ch {
+          '"' | '\'' => quote = Some(ch),
+          '/' if chars.next_if(|&(_, ch)| ch == '*').is_some() => comment = true,
+          '(' | '[' | '{' => depth += 1,
+          ')' | ']' | '}' => depth = depth.saturating_sub(1),
+          _ if depth == 0 && self.separators.contains(&ch) => return Some((i, ch.len_utf8())),
+          _ => {}
+        }
+      }
+    }
+    None
+  }
+
+  fn trimmed(&self, range: Range<usize>) -> (&'a str, Range<usize>) {
+    let item = &self.input[range.clone()];
+    let start = range.start + (item.len() - item.trim_start().len());
+    let item = item.trim();
+    (item, start..start + item.len())
+  }
+}
+
+impl<'a> Iterator for Spans<'a> {
+  type Item = (&'a str, Range<usize>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while !self.finished {
+      let start = self.pos;
+      match self.find_separator() {
+        Some((end, len)) => {
+          self.pos = end + len;
+          if end > start {
+            return Some(self.trimmed(start..end));
+          }
+        }
+        None => {
+          self.finished = true;
+          if self.last || start < self.input.len() {
+            return Some(self.trimmed(start..self.input.len()));
+          }
+        }
+      }
+    }
+    None
+  }
+}
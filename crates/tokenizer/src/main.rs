@@ -32,9 +32,7 @@ fn main() {
   //   let mut vec = Vec::default();
   //   let start = Instant::now();
   //   let processor = Tokenizer::new(&css, false);
-  //   while !processor.end_of_file() {
-  //     vec.push(processor.next_token(false));
-  //   }
+  //   vec.extend(processor);
   //   let end = start.elapsed();
   //   println!("rust: tokenizer/{}({}): {:?}", file, size, end);
   // }
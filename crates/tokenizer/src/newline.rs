@@ -0,0 +1,72 @@
+//! Newline preprocessing as described in
+//! [CSS Syntax Module Level 3](https://www.w3.org/TR/css-syntax-3/#input-preprocessing):
+//! `\r\n`, `\r` and `\f` become `\n`.
+//!
+//! ```
+//! use tokenizer::newline::normalize_newlines;
+//! use tokenizer::Tokenizer;
+//!
+//! let css = "a {\r\n  color: red;\r\n}";
+//! let normalized = normalize_newlines(css);
+//! let tokens: Vec<_> = Tokenizer::new(&normalized.css, false).collect();
+//! let color = tokens[4];
+//! assert_eq!(&css[normalized.original_range(color.1..color.2)], "color");
+//! ```
+use std::borrow::Cow;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedSource<'a> {
+  /// the source with normalized newlines, borrowed when there was nothing to replace
+  pub css: Cow<'a, str>,
+  /// the offsets in `css` of the `\n`s which replaced a `\r\n`, in ascending order
+  crlf: Vec<usize>,
+}
+
+impl<'a> NormalizedSource<'a> {
+  /// Map an offset into the normalized source back to the original source.
+  pub fn original_offset(&self, offset: usize) -> usize {
+    offset + self.crlf.partition_point(|&crlf| crlf < offset)
+  }
+
+  pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+    self.original_offset(range.start)..self.original_offset(range.end)
+  }
+}
+
+pub fn normalize_newlines(css: &str) -> NormalizedSource<'_> {
+  if !css.bytes().any(|byte| byte == b'\r' || byte == b'\x0c') {
+    return NormalizedSource {
+      css: Cow::Borrowed(css),
+      crlf: vec![],
+    };
+  }
+  let bytes = css.as_bytes();
+  let mut normalized = String::with_capacity(css.len());
+  let mut crlf = vec![];
+  let mut last = 0;
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+        normalized.push_str(&css[last..i]);
+        crlf.push(normalized.len());
+        normalized.push('\n');
+        i += 2;
+        last = i;
+      }
+      b'\r' | b'\x0c' => {
+        normalized.push_str(&css[last..i]);
+        normalized.push('\n');
+        i += 1;
+        last = i;
+      }
+      _ => i += 1,
+    }
+  }
+  normalized.push_str(&css[last..]);
+  NormalizedSource {
+    css: Cow::Owned(normalized),
+    crlf,
+  }
+}
@@ -36,4 +36,23 @@ impl<'a> RefRing<'a> {
 
     self.buffer[index].take()
   }
+
+  /// the most recently pushed element, without removing it
+  pub fn last(&self) -> Option<&'a str> {
+    self.buffer[self.index.wrapping_sub(1) as usize]
+  }
+
+  /// where the next element is pushed
+  pub fn index(&self) -> u8 {
+    self.index
+  }
+
+  /// Move back to `index`, with `last` as the most recently pushed element.
+  ///
+  /// Elements pushed after `index` are overwritten by the next pushes, elements popped after it
+  /// other than `last` stay popped.
+  pub fn rewind(&mut self, index: u8, last: Option<&'a str>) {
+    self.index = index;
+    self.buffer[index.wrapping_sub(1) as usize] = last;
+  }
 }
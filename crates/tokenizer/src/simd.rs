@@ -0,0 +1,159 @@
+//! Vectorized scanners for the `simd` feature: 16 bytes are classified at once, with a nibble
+//! lookup on cpus with SSSE3, which is detected at runtime, and with one comparison per
+//! delimiter on plain SSE2, which every x86_64 cpu has. Most words are short, so the first
+//! chunk and the tail are still looked up byte by byte.
+use crate::tokenizer::{INDEX_OF_AT_END, INDEX_OF_WORD_END};
+use std::arch::x86_64::*;
+
+/// the bytes `1` or `2` in `INDEX_OF_WORD_END`
+const WORD_END: [u8; 20] = [
+  b'\t', b'\n', 0x0c, b'\r', b' ', b'!', b'"', b'#', b'\'', b'(', b')', b'/', b':', b';', b'@',
+  b'[', b'\\', b']', b'{', b'}',
+];
+/// the bytes `1` in `INDEX_OF_AT_END`
+const AT_END: [u8; 17] = [
+  b'\t', b'\n', 0x0c, b'\r', b' ', b'"', b'#', b'\'', b'(', b')', b'/', b';', b'[', b'\\', b']',
+  b'{', b'}',
+];
+const WORD_END_NIBBLES: [u8; 16] = nibble_table(&WORD_END);
+const AT_END_NIBBLES: [u8; 16] = nibble_table(&AT_END);
+const CHUNK: usize = 16;
+
+/// A set of ASCII bytes for the nibble lookup: the entry for the low nibble of a byte
+/// has bit `n` set when the byte with high nibble `n` is in the set.
+const fn nibble_table<const N: usize>(delimiters: &[u8; N]) -> [u8; 16] {
+  let mut table = [0; 16];
+  let mut i = 0;
+  while i < N {
+    table[(delimiters[i] & 0x0f) as usize] |= 1 << (delimiters[i] >> 4);
+    i += 1;
+  }
+  table
+}
+
+/// Scan `s` a chunk at a time from `i` on: the index of the first byte in the set of `nibbles`
+/// and `true`, or where the tail shorter than a chunk starts and `false`.
+#[target_feature(enable = "ssse3")]
+unsafe fn scan_ssse3(s: &[u8], mut i: usize, nibbles: &[u8; 16]) -> (usize, bool) {
+  let high_table: [u8; 16] = [1, 2, 4, 8, 16, 32, 64, 128, 0, 0, 0, 0, 0, 0, 0, 0];
+  let low_table = _mm_loadu_si128(nibbles.as_ptr() as *const __m128i);
+  let high_table = _mm_loadu_si128(high_table.as_ptr() as *const __m128i);
+  let nibble = _mm_set1_epi8(0x0f);
+  while i + CHUNK <= s.len() {
+    let chunk = _mm_loadu_si128(s.as_ptr().add(i) as *const __m128i);
+    let low = _mm_shuffle_epi8(low_table, _mm_and_si128(chunk, nibble));
+    let high = _mm_shuffle_epi8(high_table, _mm_and_si128(_mm_srli_epi16(chunk, 4), nibble));
+    let misses = _mm_cmpeq_epi8(_mm_and_si128(low, high), _mm_setzero_si128());
+    let mask = !_mm_movemask_epi8(misses) & 0xffff;
+    if mask != 0 {
+      return (i + mask.trailing_zeros() as usize, true);
+    }
+    i += CHUNK;
+  }
+  (i, false)
+}
+
+/// Like [`scan_ssse3`] with one comparison per delimiter.
+unsafe fn scan_sse2<const N: usize>(s: &[u8], mut i: usize, delimiters: &[u8; N]) -> (usize, bool) {
+  while i + CHUNK <= s.len() {
+    let chunk = _mm_loadu_si128(s.as_ptr().add(i) as *const __m128i);
+    let mut hits = _mm_setzero_si128();
+    for byte in delimiters {
+      hits = _mm_or_si128(hits, _mm_cmpeq_epi8(chunk, _mm_set1_epi8(*byte as i8)));
+    }
+    let mask = _mm_movemask_epi8(hits);
+    if mask != 0 {
+      return (i + mask.trailing_zeros() as usize, true);
+    }
+    i += CHUNK;
+  }
+  (i, false)
+}
+
+/// the index of the first byte of `s` in `delimiters`, or `s.len()`;
+/// `table` has a non-zero entry for each of the `delimiters`, and so has `nibbles`
+#[inline]
+fn find<const N: usize>(
+  s: &[u8],
+  delimiters: &[u8; N],
+  nibbles: &[u8; 16],
+  table: &[usize; 255],
+) -> usize {
+  let scalar = |from: usize, to: usize| {
+    (from..to)
+      .find(|&i| table[s[i] as usize] != 0)
+      .unwrap_or(to)
+  };
+  let head = s.len().min(CHUNK);
+  let i = scalar(0, head);
+  if i < head {
+    return i;
+  }
+  // SAFETY: SSSE3 is only used when the cpu has it, SSE2 is part of x86_64,
+  // and the unaligned loads stay inside `s`.
+  let (i, found) = unsafe {
+    if is_x86_feature_detected!("ssse3") {
+      scan_ssse3(s, i, nibbles)
+    } else {
+      scan_sse2(s, i, delimiters)
+    }
+  };
+  if found {
+    i
+  } else {
+    scalar(i, s.len())
+  }
+}
+
+#[inline]
+pub(crate) fn index_of_at_end(s: &[u8]) -> usize {
+  find(s, &AT_END, &AT_END_NIBBLES, &INDEX_OF_AT_END)
+}
+
+#[inline]
+pub(crate) fn index_of_word_end(s: &[u8]) -> usize {
+  let mut i = 0;
+  loop {
+    i += find(&s[i..], &WORD_END, &WORD_END_NIBBLES, &INDEX_OF_WORD_END);
+    // a `/` only ends the word when a comment starts
+    if i == s.len() || s[i] != b'/' || s.get(i + 1) == Some(&b'*') {
+      return i;
+    }
+    i += 1;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn scanners_agree() {
+    if !is_x86_feature_detected!("ssse3") {
+      return;
+    }
+    let mut source = include_str!("../../../assets/bootstrap.css").to_string();
+    for byte in 0..=255u8 {
+      source.push_str(&"a".repeat(byte as usize % 40));
+      source.push(char::from(byte));
+    }
+    let s = source.as_bytes();
+    for start in 0..s.len() {
+      // SAFETY: checked for SSSE3 above
+      unsafe {
+        assert_eq!(
+          scan_ssse3(s, start, &WORD_END_NIBBLES),
+          scan_sse2(s, start, &WORD_END),
+          "{}",
+          start
+        );
+        assert_eq!(
+          scan_ssse3(s, start, &AT_END_NIBBLES),
+          scan_sse2(s, start, &AT_END),
+          "{}",
+          start
+        );
+      }
+    }
+  }
+}
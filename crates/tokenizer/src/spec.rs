@@ -0,0 +1,471 @@
+//! A tokenizer following [CSS Syntax Module Level 3](https://www.w3.org/TR/css-syntax-3/#tokenization).
+//!
+//! Unlike [`crate::Tokenizer`], which produces the coarse postcss tokens, this one tells numbers,
+//! dimensions, hashes, functions and urls apart. Comments are kept as tokens and no input is
+//! preprocessed, so the tokens cover the source without gaps and `\r\n`, `\r` and `\f` count
+//! as one newline wherever the spec cares about newlines.
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecTokenType<'a> {
+  Ident,
+  /// `name(`, the token ends after the `(`
+  Function,
+  AtKeyword,
+  /// `#name`, `is_id` when the name would start an identifier
+  Hash {
+    is_id: bool,
+  },
+  String,
+  /// a string broken by an unescaped newline, the newline is not part of the token
+  BadString,
+  /// an unquoted `url(…)`
+  Url,
+  BadUrl,
+  Delim(char),
+  Number {
+    value: f64,
+    is_integer: bool,
+  },
+  Percentage {
+    value: f64,
+  },
+  Dimension {
+    value: f64,
+    is_integer: bool,
+    /// the unit with escapes resolved
+    unit: Cow<'a, str>,
+  },
+  Whitespace,
+  /// `<!--`
+  Cdo,
+  /// `-->`
+  Cdc,
+  Colon,
+  Semicolon,
+  Comma,
+  OpenSquare,
+  CloseSquare,
+  OpenParentheses,
+  CloseParentheses,
+  OpenCurly,
+  CloseCurly,
+  Comment,
+}
+
+/// (token_type, start_offset, end_offset) like [`crate::Token`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecToken<'a>(pub SpecTokenType<'a>, pub usize, pub usize);
+
+impl<'a> SpecToken<'a> {
+  /// The value of the token with escapes resolved: the name of an ident, function, at-keyword
+  /// or hash, the content of a string or url, the source text of everything else.
+  pub fn value(&self, css: &'a str) -> Cow<'a, str> {
+    let text = &css[self.1..self.2];
+    match self.0 {
+      SpecTokenType::Ident => unescape(text),
+      SpecTokenType::Function => unescape(&text[..text.len() - 1]),
+      SpecTokenType::AtKeyword | SpecTokenType::Hash { .. } => unescape(&text[1..]),
+      SpecTokenType::String => {
+        let quote = text.as_bytes()[0];
+        let content = &text[1..];
+        unescape(content.strip_suffix(quote as char).unwrap_or(content))
+      }
+      SpecTokenType::Url => {
+        let content = text[4..].strip_suffix(')').unwrap_or(&text[4..]);
+        unescape(content.trim_matches(is_whitespace_char))
+      }
+      _ => Cow::Borrowed(text),
+    }
+  }
+}
+
+/// Iterates the [`SpecToken`]s of a stylesheet, it never fails: invalid input becomes
+/// [`SpecTokenType::BadString`], [`SpecTokenType::BadUrl`] or [`SpecTokenType::Delim`] tokens.
+#[derive(Debug, Clone)]
+pub struct SpecTokenizer<'a> {
+  css: &'a str,
+  pos: usize,
+}
+
+impl<'a> SpecTokenizer<'a> {
+  pub fn new(css: &'a str) -> SpecTokenizer<'a> {
+    SpecTokenizer { css, pos: 0 }
+  }
+
+  #[inline]
+  pub fn position(&self) -> usize {
+    self.pos
+  }
+
+  #[inline]
+  fn byte(&self, i: usize) -> u8 {
+    *self.css.as_bytes().get(i).unwrap_or(&b'\0')
+  }
+
+  #[inline]
+  fn is_eof(&self, i: usize) -> bool {
+    i >= self.css.len()
+  }
+
+  /// the length of the newline at `i`, `\r\n` counts as one
+  fn newline_len(&self, i: usize) -> usize {
+    match self.byte(i) {
+      b'\r' if self.byte(i + 1) == b'\n' => 2,
+      b'\n' | b'\r' | b'\x0c' => 1,
+      _ => 0,
+    }
+  }
+
+  fn is_valid_escape(&self, i: usize) -> bool {
+    self.byte(i) == b'\\' && self.newline_len(i + 1) == 0
+  }
+
+  fn would_start_ident(&self, i: usize) -> bool {
+    match self.byte(i) {
+      b'-' => {
+        let next = self.byte(i + 1);
+        (!self.is_eof(i + 1) && is_ident_start(next)) || next == b'-' || self.is_valid_escape(i + 1)
+      }
+      b'\\' => self.is_valid_escape(i),
+      byte => !self.is_eof(i) && is_ident_start(byte),
+    }
+  }
+
+  fn would_start_number(&self, i: usize) -> bool {
+    match self.byte(i) {
+      b'+' | b'-' => {
+        self.byte(i + 1).is_ascii_digit()
+          || (self.byte(i + 1) == b'.' && self.byte(i + 2).is_ascii_digit())
+      }
+      b'.' => self.byte(i + 1).is_ascii_digit(),
+      byte => byte.is_ascii_digit(),
+    }
+  }
+
+  /// `i` points behind the `\`, returns the escaped char and the end of the escape
+  fn consume_escape(&self, i: usize) -> (char, usize) {
+    if self.is_eof(i) {
+      return (char::REPLACEMENT_CHARACTER, i);
+    }
+    if self.byte(i).is_ascii_hexdigit() {
+      let mut end = i;
+      while end < i + 6 && self.byte(end).is_ascii_hexdigit() {
+        end += 1;
+      }
+      let code = u32::from_str_radix(&self.css[i..end], 16).unwrap();
+      let ch = match code {
+        0 => char::REPLACEMENT_CHARACTER,
+        code => char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER),
+      };
+      if matches!(self.byte(end), b' ' | b'\t') {
+        end += 1;
+      } else {
+        end += self.newline_len(end);
+      }
+      return (ch, end);
+    }
+    let ch = self.css[i..].chars().next().unwrap();
+    (ch, i + ch.len_utf8())
+  }
+
+  /// returns the ident with escapes resolved and its end
+  fn consume_ident_sequence(&self, start: usize) -> (Cow<'a, str>, usize) {
+    let mut i = start;
+    let mut owned: Option<String> = None;
+    loop {
+      let byte = self.byte(i);
+      if !self.is_eof(i) && is_ident_char(byte) {
+        if let Some(owned) = owned.as_mut() {
+          let ch = self.css[i..].chars().next().unwrap();
+          owned.push(ch);
+          i += ch.len_utf8();
+        } else {
+          i += 1;
+        }
+      } else if self.is_valid_escape(i) {
+        let owned = owned.get_or_insert_with(|| self.css[start..i].to_string());
+        let (ch, end) = self.consume_escape(i + 1);
+        owned.push(ch);
+        i = end;
+      } else {
+        break;
+      }
+    }
+    let ident = match owned {
+      Some(owned) => Cow::Owned(owned),
+      None => Cow::Borrowed(&self.css[start..i]),
+    };
+    (ident, i)
+  }
+
+  fn consume_digits(&self, mut i: usize) -> usize {
+    while self.byte(i).is_ascii_digit() {
+      i += 1;
+    }
+    i
+  }
+
+  fn consume_numeric(&self, start: usize) -> SpecToken<'a> {
+    let mut i = start;
+    let mut is_integer = true;
+    if matches!(self.byte(i), b'+' | b'-') {
+      i += 1;
+    }
+    i = self.consume_digits(i);
+    if self.byte(i) == b'.' && self.byte(i + 1).is_ascii_digit() {
+      is_integer = false;
+      i = self.consume_digits(i + 1);
+    }
+    if matches!(self.byte(i), b'e' | b'E') {
+      let digits = if matches!(self.byte(i + 1), b'+' | b'-') {
+        i + 2
+      } else {
+        i + 1
+      };
+      if self.byte(digits).is_ascii_digit() {
+        is_integer = false;
+        i = self.consume_digits(digits);
+      }
+    }
+    let value = self.css[start..i].parse::<f64>().unwrap_or(0.0);
+
+    if self.would_start_ident(i) {
+      let (unit, end) = self.consume_ident_sequence(i);
+      SpecToken(
+        SpecTokenType::Dimension {
+          value,
+          is_integer,
+          unit,
+        },
+        start,
+        end,
+      )
+    } else if self.byte(i) == b'%' {
+      SpecToken(SpecTokenType::Percentage { value }, start, i + 1)
+    } else {
+      SpecToken(SpecTokenType::Number { value, is_integer }, start, i)
+    }
+  }
+
+  fn consume_ident_like(&self, start: usize) -> SpecToken<'a> {
+    let (name, end) = self.consume_ident_sequence(start);
+    if self.byte(end) != b'(' {
+      return SpecToken(SpecTokenType::Ident, start, end);
+    }
+    if name.eq_ignore_ascii_case("url") {
+      let mut i = end + 1;
+      while is_whitespace(self.byte(i)) {
+        i += 1;
+      }
+      if !matches!(self.byte(i), b'"' | b'\'') {
+        return self.consume_url(start, i);
+      }
+    }
+    // the whitespace in front of a quoted url becomes a token of its own
+    SpecToken(SpecTokenType::Function, start, end + 1)
+  }
+
+  /// `i` points behind the whitespace following `url(`
+  fn consume_url(&self, start: usize, mut i: usize) -> SpecToken<'a> {
+    loop {
+      let byte = self.byte(i);
+      if self.is_eof(i) {
+        return SpecToken(SpecTokenType::Url, start, i);
+      }
+      match byte {
+        b')' => return SpecToken(SpecTokenType::Url, start, i + 1),
+        _ if is_whitespace(byte) => {
+          while is_whitespace(self.byte(i)) {
+            i += 1;
+          }
+          if self.is_eof(i) {
+            return SpecToken(SpecTokenType::Url, start, i);
+          }
+          if self.byte(i) == b')' {
+            return SpecToken(SpecTokenType::Url, start, i + 1);
+          }
+          return self.consume_bad_url(start, i);
+        }
+        b'"' | b'\'' | b'(' => return self.consume_bad_url(start, i),
+        _ if is_non_printable(byte) => return self.consume_bad_url(start, i),
+        b'\\' => {
+          if self.is_valid_escape(i) {
+            i = self.consume_escape(i + 1).1;
+          } else {
+            return self.consume_bad_url(start, i);
+          }
+        }
+        _ => i += 1,
+      }
+    }
+  }
+
+  fn consume_bad_url(&self, start: usize, mut i: usize) -> SpecToken<'a> {
+    while !self.is_eof(i) {
+      if self.byte(i) == b')' {
+        return SpecToken(SpecTokenType::BadUrl, start, i + 1);
+      }
+      if self.is_valid_escape(i) {
+        i = self.consume_escape(i + 1).1;
+      } else {
+        i += 1;
+      }
+    }
+    SpecToken(SpecTokenType::BadUrl, start, i)
+  }
+
+  fn consume_string(&self, start: usize) -> SpecToken<'a> {
+    let quote = self.byte(start);
+    let mut i = start + 1;
+    while !self.is_eof(i) {
+      match self.byte(i) {
+        byte if byte == quote => return SpecToken(SpecTokenType::String, start, i + 1),
+        b'\n' | b'\r' | b'\x0c' => return SpecToken(SpecTokenType::BadString, start, i),
+        b'\\' => {
+          let newline = self.newline_len(i + 1);
+          if newline > 0 {
+            i += 1 + newline;
+          } else {
+            i = self.consume_escape(i + 1).1;
+          }
+        }
+        _ => i += 1,
+      }
+    }
+    SpecToken(SpecTokenType::String, start, i)
+  }
+
+  fn delim(&self, start: usize) -> SpecToken<'a> {
+    let ch = self.css[start..].chars().next().unwrap();
+    SpecToken(SpecTokenType::Delim(ch), start, start + ch.len_utf8())
+  }
+
+  fn consume_token(&self, start: usize) -> SpecToken<'a> {
+    let single = |kind| SpecToken(kind, start, start + 1);
+    match self.byte(start) {
+      b'/' if self.byte(start + 1) == b'*' => {
+        let end = memchr::memmem::find(&self.css.as_bytes()[start + 2..], b"*/")
+          .map_or(self.css.len(), |i| start + 2 + i + 2);
+        SpecToken(SpecTokenType::Comment, start, end)
+      }
+      byte if is_whitespace(byte) => {
+        let mut i = start + 1;
+        while is_whitespace(self.byte(i)) {
+          i += 1;
+        }
+        SpecToken(SpecTokenType::Whitespace, start, i)
+      }
+      b'"' | b'\'' => self.consume_string(start),
+      b'#' => {
+        let i = start + 1;
+        if (!self.is_eof(i) && is_ident_char(self.byte(i))) || self.is_valid_escape(i) {
+          let is_id = self.would_start_ident(i);
+          let (_, end) = self.consume_ident_sequence(i);
+          SpecToken(SpecTokenType::Hash { is_id }, start, end)
+        } else {
+          self.delim(start)
+        }
+      }
+      b'(' => single(SpecTokenType::OpenParentheses),
+      b')' => single(SpecTokenType::CloseParentheses),
+      b'[' => single(SpecTokenType::OpenSquare),
+      b']' => single(SpecTokenType::CloseSquare),
+      b'{' => single(SpecTokenType::OpenCurly),
+      b'}' => single(SpecTokenType::CloseCurly),
+      b',' => single(SpecTokenType::Comma),
+      b':' => single(SpecTokenType::Colon),
+      b';' => single(SpecTokenType::Semicolon),
+      b'+' | b'.' if self.would_start_number(start) => self.consume_numeric(start),
+      b'-' => {
+        if self.would_start_number(start) {
+          self.consume_numeric(start)
+        } else if self.css[start..].starts_with("-->") {
+          SpecToken(SpecTokenType::Cdc, start, start + 3)
+        } else if self.would_start_ident(start) {
+          self.consume_ident_like(start)
+        } else {
+          self.delim(start)
+        }
+      }
+      b'<' if self.css[start..].starts_with("<!--") => {
+        SpecToken(SpecTokenType::Cdo, start, start + 4)
+      }
+      b'@' if self.would_start_ident(start + 1) => {
+        let (_, end) = self.consume_ident_sequence(start + 1);
+        SpecToken(SpecTokenType::AtKeyword, start, end)
+      }
+      b'\\' if self.is_valid_escape(start) => self.consume_ident_like(start),
+      byte if byte.is_ascii_digit() => self.consume_numeric(start),
+      byte if is_ident_start(byte) => self.consume_ident_like(start),
+      _ => self.delim(start),
+    }
+  }
+}
+
+impl<'a> Iterator for SpecTokenizer<'a> {
+  type Item = SpecToken<'a>;
+
+  fn next(&mut self) -> Option<SpecToken<'a>> {
+    if self.is_eof(self.pos) {
+      return None;
+    }
+    let token = self.consume_token(self.pos);
+    self.pos = token.2;
+    Some(token)
+  }
+}
+
+/// Resolve the escapes of an ident or the content of a string or url,
+/// an escaped newline is removed like inside a string.
+pub fn unescape(text: &str) -> Cow<'_, str> {
+  if !text.contains('\\') {
+    return Cow::Borrowed(text);
+  }
+  let tokenizer = SpecTokenizer::new(text);
+  let mut value = String::with_capacity(text.len());
+  let mut i = 0;
+  while i < text.len() {
+    if tokenizer.byte(i) == b'\\' {
+      let newline = tokenizer.newline_len(i + 1);
+      if newline > 0 {
+        i += 1 + newline;
+      } else {
+        let (ch, end) = tokenizer.consume_escape(i + 1);
+        value.push(ch);
+        i = end;
+      }
+    } else {
+      let ch = text[i..].chars().next().unwrap();
+      value.push(ch);
+      i += ch.len_utf8();
+    }
+  }
+  Cow::Owned(value)
+}
+
+#[inline]
+fn is_whitespace(byte: u8) -> bool {
+  matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c')
+}
+
+#[inline]
+fn is_whitespace_char(ch: char) -> bool {
+  ch.is_ascii() && is_whitespace(ch as u8)
+}
+
+/// a letter, `_`, or a non-ASCII byte; `\0` is replaced by U+FFFD in the spec and counts as well
+#[inline]
+fn is_ident_start(byte: u8) -> bool {
+  byte.is_ascii_alphabetic() || byte == b'_' || byte >= 0x80 || byte == b'\0'
+}
+
+#[inline]
+fn is_ident_char(byte: u8) -> bool {
+  is_ident_start(byte) || byte.is_ascii_digit() || byte == b'-'
+}
+
+#[inline]
+fn is_non_printable(byte: u8) -> bool {
+  matches!(byte, 0x00..=0x08 | 0x0b | 0x0e..=0x1f | 0x7f)
+}
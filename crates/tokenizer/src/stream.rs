@@ -0,0 +1,219 @@
+//! Tokenizing a stylesheet while it is read, for inputs too large to hold in memory at once.
+//!
+//! ```
+//! use tokenizer::stream::StreamTokenizer;
+//! use tokenizer::TokenType;
+//!
+//! let css = "a { color: red }".as_bytes();
+//! let tokens = StreamTokenizer::new(css, false)
+//!   .with_chunk_size(4)
+//!   .collect::<std::io::Result<Vec<_>>>()
+//!   .unwrap();
+//! assert_eq!(tokens[4].0.0, TokenType::Word);
+//! assert_eq!(tokens[4].1, "color");
+//! ```
+use crate::tokenizer::{index_of_at_end, index_of_word_end};
+use crate::{Token, TokenType, Tokenizer};
+use memchr::{memchr, memmem};
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind, Read};
+
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A tokenizer pulling its input from a [`Read`] in chunks.
+///
+/// It yields the same tokens as [`Tokenizer`] does for the whole input, with offsets into the
+/// whole input and the text of each token. Only the current chunk and a token crossing its end
+/// are kept in memory, so memory grows with the longest token rather than with the input: an
+/// unclosed comment or string keeps everything up to the end of the input in memory.
+/// A token spanning many chunks is tokenized again only once a chunk could end it.
+pub struct StreamTokenizer<R> {
+  reader: R,
+  ignore: bool,
+  chunk: Vec<u8>,
+  /// text read but not tokenized yet
+  buffer: String,
+  /// the start of an UTF-8 sequence cut by the end of the last chunk
+  incomplete: Vec<u8>,
+  /// the offset of `buffer` in the whole input
+  offset: usize,
+  /// the last word in front of `buffer`, to recognize `url(`
+  prev_word: Option<String>,
+  tokens: VecDeque<(Token, String)>,
+  /// the type of the token at the start of `buffer` which was cut by its end, and the length
+  /// of `buffer` searched for its end so far
+  pending: Option<(TokenType, usize)>,
+  eof: bool,
+}
+
+impl<R: Read> StreamTokenizer<R> {
+  pub fn new(reader: R, ignore_errors: bool) -> StreamTokenizer<R> {
+    StreamTokenizer {
+      reader,
+      ignore: ignore_errors,
+      chunk: vec![0; DEFAULT_CHUNK_SIZE],
+      buffer: String::new(),
+      incomplete: vec![],
+      offset: 0,
+      prev_word: None,
+      tokens: VecDeque::new(),
+      pending: None,
+      eof: false,
+    }
+  }
+
+  /// The number of bytes read at once, 64 KiB by default.
+  pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+    self.chunk = vec![0; chunk_size.max(1)];
+    self
+  }
+
+  fn fill(&mut self) -> io::Result<()> {
+    let read = loop {
+      match self.reader.read(&mut self.chunk) {
+        Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+        result => break result?,
+      }
+    };
+    if read == 0 {
+      self.eof = true;
+      if !self.incomplete.is_empty() {
+        return Err(invalid_utf8());
+      }
+      return Ok(());
+    }
+
+    self.incomplete.extend_from_slice(&self.chunk[..read]);
+    let valid = match std::str::from_utf8(&self.incomplete) {
+      Ok(text) => text.len(),
+      Err(err) if err.error_len().is_none() => err.valid_up_to(),
+      Err(_) => return Err(invalid_utf8()),
+    };
+    // SAFETY: checked by `from_utf8` above
+    self
+      .buffer
+      .push_str(unsafe { std::str::from_utf8_unchecked(&self.incomplete[..valid]) });
+    self.incomplete.drain(..valid);
+    Ok(())
+  }
+
+  /// Move the tokens of `buffer` to `tokens`, a token which might continue in the next
+  /// chunk and everything after it stay in `buffer`, unless the input is read completely.
+  fn tokenize(&mut self) {
+    // tokenizing again from the start of the pending token is only worth it once the bytes
+    // read since could end it
+    if let Some((kind, scanned)) = self.pending {
+      if !self.eof && !may_end(kind, &self.buffer, scanned) {
+        self.pending = Some((kind, self.buffer.len()));
+        return;
+      }
+    }
+    let buffer = std::mem::take(&mut self.buffer);
+    let prev_word = self.prev_word.take();
+    let complete = self.eof;
+
+    let mut tokenizer = Tokenizer::new(&buffer, self.ignore || !complete);
+    if let Some(word) = prev_word.as_deref() {
+      tokenizer.push(word);
+    }
+    let mut consumed = 0;
+    let mut checkpoint = tokenizer.checkpoint();
+    self.pending = None;
+    while !tokenizer.end_of_file() {
+      let token = tokenizer.next_token(false);
+      if !complete && !is_complete(&buffer, token) {
+        self.pending = Some((token.0, buffer.len() - consumed));
+        break;
+      }
+      self.tokens.push_back((
+        Token(token.0, self.offset + token.1, self.offset + token.2),
+        buffer[token.1..token.2].to_string(),
+      ));
+      consumed = token.2;
+      checkpoint = tokenizer.checkpoint();
+    }
+
+    self.prev_word = checkpoint.prev_word.map(str::to_string);
+    self.buffer = buffer[consumed..].to_string();
+    self.offset += consumed;
+  }
+}
+
+impl<R: Read> Iterator for StreamTokenizer<R> {
+  type Item = io::Result<(Token, String)>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(token) = self.tokens.pop_front() {
+        return Some(Ok(token));
+      }
+      if self.eof && self.buffer.is_empty() {
+        return None;
+      }
+      if !self.eof {
+        if let Err(err) = self.fill() {
+          return Some(Err(err));
+        }
+      }
+      self.tokenize();
+    }
+  }
+}
+
+/// whether more input can't change `token`, which was read from `css`
+fn is_complete(css: &str, token: Token) -> bool {
+  // words and spaces end at the first byte not belonging to them, and a comment
+  // or string closed right at the end is too rare to bother
+  if token.2 >= css.len() {
+    return false;
+  }
+  match token.0 {
+    // whether the `(` starts a `Brackets` token depends on the next `)`
+    TokenType::OpenParentheses => memchr(b')', &css.as_bytes()[token.1..]).is_some(),
+    // an unclosed `url(` is a `(` alone, and a `)` escaped in it doesn't close it
+    TokenType::Brackets => {
+      let bytes = &css.as_bytes()[token.1..token.2];
+      let backslashes = bytes[..bytes.len() - 1]
+        .iter()
+        .rev()
+        .take_while(|&&b| b == b'\\')
+        .count();
+      bytes.len() > 1 && bytes[bytes.len() - 1] == b')' && backslashes % 2 == 0
+    }
+    // an unclosed string is cut after its first char
+    TokenType::String => {
+      let bytes = css.as_bytes();
+      token.2 - token.1 >= 2 && bytes[token.2 - 1] == bytes[token.1]
+    }
+    _ => true,
+  }
+}
+
+/// Whether the token of type `kind` at the start of `buffer`, which was cut by the end of its
+/// first `scanned` bytes, could end in the bytes after them. The search starts a little before
+/// `scanned`, for a `*/` or `/*` cut in half and a token closed right at the end.
+fn may_end(kind: TokenType, buffer: &str, scanned: usize) -> bool {
+  let bytes = buffer.as_bytes();
+  let tail = |back: usize, min: usize| &bytes[scanned.saturating_sub(back).max(min)..];
+  match kind {
+    TokenType::Space => bytes[scanned..]
+      .iter()
+      .any(|b| !matches!(b, b' ' | b'\n' | b'\t' | b'\r' | b'\x0c')),
+    TokenType::Word => {
+      let tail = tail(1, 0);
+      index_of_word_end(tail) < tail.len()
+    }
+    TokenType::AtWord => {
+      let tail = tail(1, 0);
+      index_of_at_end(tail) < tail.len()
+    }
+    TokenType::Comment => memmem::find(tail(2, 2), b"*/").is_some(),
+    TokenType::String => memchr(bytes[0], tail(1, 1)).is_some(),
+    TokenType::OpenParentheses | TokenType::Brackets => memchr(b')', tail(1, 0)).is_some(),
+    _ => bytes.len() > scanned,
+  }
+}
+
+fn invalid_utf8() -> io::Error {
+  io::Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
@@ -1,11 +1,11 @@
+use crate::line_index::{Encoding, LineCol, LineIndex};
 use crate::ref_ring::RefRing;
 use memchr::memchr;
 use memchr::memmem::Finder;
 use once_cell::sync::Lazy;
-use std::cell::RefCell;
 use std::clone::Clone;
+use std::cmp::Eq;
 use std::cmp::PartialEq;
-use std::cmp::{min, Eq};
 use std::hint::unreachable_unchecked;
 
 const SINGLE_QUOTE: char = '\'';
@@ -28,9 +28,7 @@ const ASTERISK: char = '*';
 const COLON: char = ':';
 const AT: char = '@';
 
-const MAX_BUFFER: usize = 102400;
-
-const INDEX_OF_WORD_END: [usize; 255] = [
+pub(crate) const INDEX_OF_WORD_END: [usize; 255] = [
   0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
   1, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0,
   1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0,
@@ -40,7 +38,7 @@ const INDEX_OF_WORD_END: [usize; 255] = [
   0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
   0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 ];
-const INDEX_OF_AT_END: [usize; 255] = [
+pub(crate) const INDEX_OF_AT_END: [usize; 255] = [
   0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
   1, 0, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0,
   0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0,
@@ -101,91 +99,152 @@ impl Token {
   }
 }
 
+/// The dialect a stylesheet is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Syntax {
+  #[default]
+  Css,
+  /// SCSS as `postcss-scss` reads it: `//` comments are comment tokens, `#{…}` interpolation
+  /// is part of the word or string it appears in.
+  Scss,
+  /// Less as `postcss-less` reads it: `//` comments are comment tokens, `@{…}` interpolation
+  /// is part of the word it appears in and a variable's at-word ends after its `:`.
+  Less,
+  /// SugarSS, the indentation based syntax: `//` comments are comment tokens.
+  Sugarss,
+}
+
+impl Syntax {
+  fn inline_comments(self) -> bool {
+    self != Syntax::Css
+  }
+
+  /// the first byte of `#{` or `@{`
+  fn interpolation(self) -> Option<u8> {
+    match self {
+      Syntax::Css | Syntax::Sugarss => None,
+      Syntax::Scss => Some(b'#'),
+      Syntax::Less => Some(b'@'),
+    }
+  }
+}
+
+/// A cheap snapshot of the [`Tokenizer`] state, see [`Tokenizer::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint<'a> {
+  pos: usize,
+  pub(crate) prev_word: Option<&'a str>,
+  words: u8,
+  url: bool,
+}
+
 #[derive(Debug)]
 pub struct Tokenizer<'a> {
   pub css: &'a str,
   ignore: bool,
   length: usize,
-  pos: RefCell<usize>,
-  buffer: RefCell<RefRing<'a>>,
-  returned: RefCell<Vec<Token>>,
-  rope: Option<ropey::Rope>,
+  pos: usize,
+  buffer: RefRing<'a>,
+  line_index: Option<LineIndex>,
+  syntax: Syntax,
+  /// inside the `(` of a `url(` which isn't a single `Brackets` token, where `//` is no comment
+  url: bool,
 }
 
 impl<'a> Tokenizer<'a> {
   pub fn new(source_code: &'a str, ignore_errors: bool) -> Tokenizer<'a> {
+    Tokenizer::with_syntax(source_code, ignore_errors, Syntax::Css)
+  }
+
+  pub fn with_syntax(source_code: &'a str, ignore_errors: bool, syntax: Syntax) -> Tokenizer<'a> {
     let length = source_code.len();
     Tokenizer {
       css: source_code,
       ignore: ignore_errors,
       length,
-      pos: RefCell::new(0),
-      buffer: RefCell::new(Default::default()),
-      returned: RefCell::new(Vec::with_capacity(min(MAX_BUFFER, length / 8))),
-      rope: None,
+      pos: 0,
+      buffer: Default::default(),
+      line_index: None,
+      syntax,
+      url: false,
     }
   }
 
+  pub fn syntax(&self) -> Syntax {
+    self.syntax
+  }
+
   #[inline]
-  fn push(&self, t: &'a str) {
-    self.buffer.borrow_mut().push(t);
+  pub(crate) fn push(&mut self, t: &'a str) {
+    self.buffer.push(t);
   }
 
   #[inline]
   pub fn position(&self) -> usize {
-    *self.pos.borrow()
+    self.pos
   }
 
   pub fn unclosed(&self, what: &str) {
-    panic!("Unclosed {} {}", what, self.position());
+    panic!("Unclosed {} {}", what, self.pos);
   }
 
   pub fn end_of_file(&self) -> bool {
-    self.returned.borrow().is_empty() && self.position() >= self.length
+    self.pos >= self.length
   }
 
-  pub fn back(&self, token: Token) {
-    self.returned.borrow_mut().push(token);
+  /// Remember the current position to [`rewind`](Tokenizer::rewind) to it later.
+  pub fn checkpoint(&self) -> Checkpoint<'a> {
+    Checkpoint {
+      pos: self.pos,
+      prev_word: self.buffer.last(),
+      words: self.buffer.index(),
+      url: self.url,
+    }
   }
 
-  #[inline]
-  fn pos_plus_one(&self) {
-    self.pos.replace_with(|it| *it + 1);
+  /// Continue tokenizing from `checkpoint`, the tokens after it are produced again.
+  ///
+  /// The words in front of the checkpoint, used to detect `url(`, are restored as long as no
+  /// more than one of them was taken by a `(` since, which only happens when several `(`
+  /// follow each other without words in between.
+  pub fn rewind(&mut self, checkpoint: Checkpoint<'a>) {
+    self.pos = checkpoint.pos;
+    self.url = checkpoint.url;
+    self.buffer.rewind(checkpoint.words, checkpoint.prev_word);
   }
 
-  pub fn next_token(&self, ignore_unclosed: bool) -> Token {
-    if !self.returned.borrow().is_empty() {
-      return self.returned.borrow_mut().pop().unwrap();
-    }
-
-    let mut code = char_code_at(self.css, self.position());
+  pub fn next_token(&mut self, ignore_unclosed: bool) -> Token {
+    let mut code = char_code_at(self.css, self.pos);
 
     let current_token: Token;
 
     match code {
       NEWLINE | SPACE | TAB | CR | FEED => {
-        let mut next = self.position();
+        let mut next = self.pos;
         loop {
           next += 1;
           code = char_code_at(self.css, next);
-          if !(code == SPACE || code == NEWLINE || code == TAB || code == FEED) {
+          if !(code == SPACE || code == NEWLINE || code == TAB || code == CR || code == FEED) {
             break;
           }
         }
 
-        current_token = Token(TokenType::Space, self.position(), next);
+        current_token = Token(TokenType::Space, self.pos, next);
 
-        self.pos.replace(next);
+        self.pos = next;
       }
       OPEN_SQUARE | CLOSE_SQUARE | OPEN_CURLY | CLOSE_CURLY | COLON | SEMICOLON
       | CLOSE_PARENTHESES => {
-        let start = self.position();
+        if code == CLOSE_PARENTHESES {
+          self.url = false;
+        }
+        let start = self.pos;
         current_token = Token(get_token_type(code), start, start + 1);
-        self.pos_plus_one();
+        self.pos += 1;
       }
       OPEN_PARENTHESES => {
-        let prev = self.buffer.borrow_mut().pop().unwrap_or("");
-        let n = char_code_at(self.css, self.position() + 1);
+        let prev = self.buffer.pop().unwrap_or("");
+        let n = char_code_at(self.css, self.pos + 1);
         if prev == "url"
           && n != SINGLE_QUOTE
           && n != DOUBLE_QUOTE
@@ -195,7 +254,7 @@ impl<'a> Tokenizer<'a> {
           && n != FEED
           && n != CR
         {
-          let mut next = self.position();
+          let mut next = self.pos;
           loop {
             let mut escaped = false;
             match index_of_byte(self.css, b')', next + 1) {
@@ -204,7 +263,7 @@ impl<'a> Tokenizer<'a> {
               }
               None => {
                 if self.ignore || ignore_unclosed {
-                  next = self.position();
+                  next = self.pos;
                   break;
                 } else {
                   self.unclosed("bracket")
@@ -222,75 +281,99 @@ impl<'a> Tokenizer<'a> {
               break;
             }
           }
-          let start_offset = self.position();
+          let start_offset = self.pos;
           current_token = Token(TokenType::Brackets, start_offset, next + 1);
 
-          self.pos.replace(next + 1);
+          self.pos = next + 1;
         } else {
-          match index_of_byte(self.css, b')', self.position() + 1) {
+          match index_of_byte(self.css, b')', self.pos + 1) {
             Some(i) => {
-              let content = &self.css[self.position()..i + 1];
+              let content = &self.css[self.pos..i + 1];
 
-              let start_offset = self.position();
+              let start_offset = self.pos;
               if is_bad_bracket(content) {
+                self.url = prev == "url";
                 current_token = Token(TokenType::OpenParentheses, start_offset, start_offset + 1);
               } else {
                 current_token = Token(TokenType::Brackets, start_offset, i + 1);
-                self.pos.replace(i);
+                self.pos = i;
               }
             }
             None => {
-              let start_offset = self.position();
+              self.url = prev == "url";
+              let start_offset = self.pos;
               current_token = Token(TokenType::OpenParentheses, start_offset, start_offset + 1);
             }
           };
-          self.pos_plus_one();
+          self.pos += 1;
         }
       }
       SINGLE_QUOTE | DOUBLE_QUOTE => {
         let quote = if code == SINGLE_QUOTE { b'\'' } else { b'"' };
-        let mut next = self.position();
-        loop {
-          let mut escaped = false;
-          match index_of_byte(self.css, quote, next + 1) {
-            Some(i) => {
-              next = i;
-            }
+        let mut next = self.pos;
+        if self.syntax == Syntax::Scss {
+          next = match scss_string_end(self.css.as_bytes(), self.pos, quote) {
+            Some(i) => i,
             None => {
-              if self.ignore || ignore_unclosed {
-                next = self.position() + 1;
-                break;
-              } else {
-                self.unclosed("string")
+              if !self.ignore && !ignore_unclosed {
+                self.unclosed("string");
+              }
+              self.pos + 1
+            }
+          };
+        } else {
+          loop {
+            let mut escaped = false;
+            match index_of_byte(self.css, quote, next + 1) {
+              Some(i) => {
+                next = i;
+              }
+              None => {
+                if self.ignore || ignore_unclosed {
+                  next = self.pos + 1;
+                  break;
+                } else {
+                  self.unclosed("string")
+                }
               }
             }
-          }
 
-          let mut escape_pos = next;
-          while char_code_at(self.css, escape_pos - 1) == BACKSLASH {
-            escape_pos -= 1;
-            escaped = !escaped;
-          }
+            let mut escape_pos = next;
+            while char_code_at(self.css, escape_pos - 1) == BACKSLASH {
+              escape_pos -= 1;
+              escaped = !escaped;
+            }
 
-          if !escaped {
-            break;
+            if !escaped {
+              break;
+            }
           }
         }
 
-        current_token = Token(TokenType::String, self.position(), next + 1);
-        self.pos.replace(next + 1);
+        current_token = Token(TokenType::String, self.pos, next + 1);
+        self.pos = next + 1;
+      }
+      AT if self.syntax == Syntax::Less && char_code_at(self.css, self.pos + 1) == OPEN_CURLY => {
+        let end = interpolated_word_end(self.css.as_bytes(), self.pos, b'@');
+        current_token = Token::new(TokenType::Word, self.pos, end);
+        self.push(&self.css[self.pos..end]);
+        self.pos = end;
       }
       AT => {
-        let next = index_of_at_end(&self.css[self.position() + 1..]);
-        current_token = Token(
-          TokenType::AtWord,
-          self.position(),
-          next + self.position() + 1,
-        );
-        self.pos.replace(next + self.position() + 1);
+        let mut next = index_of_at_end(&self.css.as_bytes()[self.pos + 1..]);
+        if self.syntax == Syntax::Less {
+          if let Some(colon) = memchr(
+            b':',
+            &self.css.as_bytes()[self.pos + 1..self.pos + 1 + next],
+          ) {
+            next = colon + 1;
+          }
+        }
+        current_token = Token(TokenType::AtWord, self.pos, next + self.pos + 1);
+        self.pos = next + self.pos + 1;
       }
       BACKSLASH => {
-        let mut next = self.position();
+        let mut next = self.pos;
         let mut escape = true;
         while char_code_at(self.css, next + 1) == BACKSLASH {
           next += 1;
@@ -316,51 +399,81 @@ impl<'a> Tokenizer<'a> {
           }
         }
 
-        current_token = Token(TokenType::Word, self.position(), next + 1);
-        self.pos.replace(next + 1);
+        current_token = Token(TokenType::Word, self.pos, next + 1);
+        self.pos = next + 1;
       }
       _ => {
-        self.pos.replace(
-          if code == SLASH && char_code_at(self.css, self.position() + 1) == ASTERISK {
-            let next = match index_of_end_comment(self.css, self.position() + 2) {
-              Some(i) => i + 1,
-              None => {
-                if !self.ignore && !ignore_unclosed {
-                  self.unclosed("comment");
-                }
-                self.length - 1
+        let next = if code == SLASH && char_code_at(self.css, self.pos + 1) == ASTERISK {
+          let next = match index_of_end_comment(self.css, self.pos + 2) {
+            Some(i) => i + 1,
+            None => {
+              if !self.ignore && !ignore_unclosed {
+                self.unclosed("comment");
               }
-            };
-
-            current_token = Token(TokenType::Comment, self.position(), next + 1);
-            next
-          } else {
-            let position = self.position();
-            let next = index_of_word_end(&self.css[position + 1..]);
-            let content = sub_str(self.css, self.position(), next + position + 1);
-            current_token = Token::new(TokenType::Word, self.position(), next + position + 1);
-            self.push(content);
-            next + position
-          },
-        );
-        self.pos_plus_one();
+              self.length - 1
+            }
+          };
+
+          current_token = Token(TokenType::Comment, self.pos, next + 1);
+          next
+        } else if self.syntax.inline_comments()
+          && !self.url
+          && code == SLASH
+          && char_code_at(self.css, self.pos + 1) == SLASH
+        {
+          let end = index_of_byte(self.css, b'\n', self.pos).unwrap_or(self.length);
+          current_token = Token(TokenType::Comment, self.pos, end);
+          end - 1
+        } else if let Some(interpolation) = self.syntax.interpolation() {
+          let mut end = interpolated_word_end(self.css.as_bytes(), self.pos, interpolation);
+          if !self.url {
+            end = inline_comment_start(self.css.as_bytes(), self.pos, end);
+          }
+          current_token = Token::new(TokenType::Word, self.pos, end);
+          self.push(&self.css[self.pos..end]);
+          end - 1
+        } else {
+          let position = self.pos;
+          let mut next = index_of_word_end(&self.css.as_bytes()[position + 1..]);
+          if self.syntax.inline_comments() && !self.url {
+            next = inline_comment_start(self.css.as_bytes(), position + 1, position + 1 + next)
+              - position
+              - 1;
+          }
+          let content = sub_str(self.css, self.pos, next + position + 1);
+          current_token = Token::new(TokenType::Word, self.pos, next + position + 1);
+          self.push(content);
+          next + position
+        };
+        self.pos = next + 1;
       }
     }
 
     current_token
   }
 
-  /// return (line, column), use rope for simplicity
+  /// The zero-based `(line, column)` of a byte offset, the column counted in chars.
+  /// See [`LineIndex`] for other units, the index is built on the first call.
   pub fn from_offset(&mut self, offset: usize) -> (usize, usize) {
-    let rope = if let Some(ref rope) = self.rope {
-      rope
+    let css = self.css;
+    let index = self.line_index.get_or_insert_with(|| LineIndex::new(css));
+    let LineCol { line, col } = index
+      .line_col(offset, Encoding::Char)
+      .expect("offset out of bounds");
+    (line, col)
+  }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+  type Item = Token;
+
+  #[inline]
+  fn next(&mut self) -> Option<Token> {
+    if self.end_of_file() {
+      None
     } else {
-      self.rope = Some(ropey::Rope::from_str(self.css));
-      self.rope.as_ref().unwrap()
-    };
-    let column = rope.byte_to_char(offset);
-    let line = rope.byte_to_line(offset);
-    (line, column)
+      Some(self.next_token(false))
+    }
   }
 }
 
@@ -400,7 +513,7 @@ fn is_hex_char(s: &str, n: usize) -> bool {
     return false;
   }
 
-  matches!(s.as_bytes()[n], b'A'..=b'F' | b'a'..=b'f' | b'0'..=b'9')
+  s.as_bytes()[n].is_ascii_hexdigit()
 }
 
 #[inline]
@@ -409,7 +522,7 @@ fn is_bad_bracket(s: &str) -> bool {
   #[allow(clippy::needless_range_loop)]
   for i in 1..bytes.len() {
     match bytes[i] as char {
-      '\n' | '"' | '\'' | '(' | '/' | '\\' => {
+      '\n' | '\r' | '"' | '\'' | '(' | '/' | '\\' => {
         return true;
       }
       _ => continue,
@@ -418,10 +531,106 @@ fn is_bad_bracket(s: &str) -> bool {
   false
 }
 
+/// the end of the `#{…}` or `@{…}` starting at `start`, nested braces and strings included
+fn interpolation_end(bytes: &[u8], start: usize) -> usize {
+  let mut depth = 0;
+  let mut i = start + 1;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'{' => depth += 1,
+      b'}' => {
+        depth -= 1;
+        if depth == 0 {
+          return i + 1;
+        }
+      }
+      b'\\' => i += 1,
+      quote @ (b'"' | b'\'') => match scss_string_end(bytes, i, quote) {
+        Some(end) => i = end,
+        None => return bytes.len(),
+      },
+      _ => {}
+    }
+    i += 1;
+  }
+  bytes.len()
+}
+
+/// the index of the quote closing the string at `start`, skipping interpolations
+fn scss_string_end(bytes: &[u8], start: usize, quote: u8) -> Option<usize> {
+  let mut i = start + 1;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\\' => i += 1,
+      b'#' if bytes.get(i + 1) == Some(&b'{') => {
+        i = interpolation_end(bytes, i);
+        continue;
+      }
+      byte if byte == quote => return Some(i),
+      _ => {}
+    }
+    i += 1;
+  }
+  None
+}
+
+/// where a `//` comment starts in the word from `start` to `end`, or `end`; interpolations are
+/// skipped, the `//` in `#{$a//2}` is no comment
+fn inline_comment_start(bytes: &[u8], start: usize, end: usize) -> usize {
+  let mut depth = 0usize;
+  let mut i = start;
+  while i + 1 < end {
+    match bytes[i] {
+      b'{' => depth += 1,
+      b'}' => depth = depth.saturating_sub(1),
+      b'/' if depth == 0 && bytes[i + 1] == b'/' => return i,
+      _ => {}
+    }
+    i += 1;
+  }
+  end
+}
+
+/// the end of the word at `start`, which goes on through interpolations starting with `opener`
+fn interpolated_word_end(bytes: &[u8], start: usize, opener: u8) -> usize {
+  let is_interpolation =
+    |i: usize| bytes.get(i) == Some(&opener) && bytes.get(i + 1) == Some(&b'{');
+  let mut end = if is_interpolation(start) {
+    interpolation_end(bytes, start)
+  } else {
+    start + 1
+  };
+  loop {
+    end += index_of_word_end(&bytes[end..]);
+    if is_interpolation(end) {
+      end = interpolation_end(bytes, end);
+    } else {
+      return end;
+    }
+  }
+}
+
 #[inline]
-fn index_of_at_end(s: &str) -> usize {
-  for (i, ch) in s.bytes().enumerate() {
-    if let 1 = INDEX_OF_AT_END[ch as usize] {
+pub(crate) fn index_of_at_end(s: &[u8]) -> usize {
+  #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+  return crate::simd::index_of_at_end(s);
+  #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+  index_of_at_end_scalar(s)
+}
+
+#[inline]
+pub(crate) fn index_of_word_end(s: &[u8]) -> usize {
+  #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+  return crate::simd::index_of_word_end(s);
+  #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+  index_of_word_end_scalar(s)
+}
+
+#[inline]
+#[cfg_attr(all(feature = "simd", target_arch = "x86_64"), allow(dead_code))]
+fn index_of_at_end_scalar(s: &[u8]) -> usize {
+  for (i, ch) in s.iter().enumerate() {
+    if let 1 = INDEX_OF_AT_END[*ch as usize] {
       return i;
     };
   }
@@ -430,12 +639,13 @@ fn index_of_at_end(s: &str) -> usize {
 }
 
 #[inline]
-fn index_of_word_end(s: &str) -> usize {
-  for (i, ch) in s.bytes().enumerate() {
-    match INDEX_OF_WORD_END[ch as usize] {
+#[cfg_attr(all(feature = "simd", target_arch = "x86_64"), allow(dead_code))]
+fn index_of_word_end_scalar(s: &[u8]) -> usize {
+  for (i, ch) in s.iter().enumerate() {
+    match INDEX_OF_WORD_END[*ch as usize] {
       1 => return i,
       2 => {
-        if s.as_bytes().get(i + 1) == Some(&b'*') {
+        if s.get(i + 1) == Some(&b'*') {
           return i;
         }
       }
@@ -471,6 +681,31 @@ mod test {
     assert_eq!(char_code_at(s, 100), '\0');
   }
 
+  #[test]
+  fn scanners_match_the_scalar_ones() {
+    let mut sources = vec![
+      include_str!("../../../assets/bootstrap.css").to_string(),
+      include_str!("../../../assets/tailwind-components.css").to_string(),
+      include_str!("../../../assets/utf8.css").to_string(),
+    ];
+    // every byte the tables know about, at every position of a 16 byte chunk
+    for byte in 0..128u8 {
+      for position in 0..40 {
+        let mut source = "a".repeat(position);
+        source.push(byte as char);
+        source.push_str("*/bb/");
+        sources.push(source);
+      }
+    }
+    for source in &sources {
+      for start in 0..source.len() {
+        let s = &source.as_bytes()[start..];
+        assert_eq!(index_of_word_end(s), index_of_word_end_scalar(s), "{:?}", s);
+        assert_eq!(index_of_at_end(s), index_of_at_end_scalar(s), "{:?}", s);
+      }
+    }
+  }
+
   #[test]
   fn test_sub_str() {
     let s = "0123456789abc";
@@ -0,0 +1,95 @@
+use std::fs::read_to_string;
+use tokenizer::newline::normalize_newlines;
+use tokenizer::*;
+
+fn tokenize(css: &str) -> Vec<Token> {
+  Tokenizer::new(css, false).collect()
+}
+
+fn windows(css: &str) -> String {
+  css.replace("\r\n", "\n").replace('\n', "\r\n")
+}
+
+#[test]
+fn tokenizes_crlf_as_one_space() {
+  assert_eq!(
+    tokenize("a\r\n\r\n  b"),
+    vec![
+      Token::new(TokenType::Word, 0, 1),
+      Token::new(TokenType::Space, 1, 7),
+      Token::new(TokenType::Word, 7, 8),
+    ]
+  );
+  assert_eq!(
+    tokenize("\t\r \r\u{c}"),
+    vec![Token::new(TokenType::Space, 0, 5)]
+  );
+}
+
+#[test]
+fn carriage_return_makes_bad_brackets() {
+  assert_eq!(
+    tokenize("(a\r)"),
+    vec![
+      Token::new(TokenType::OpenParentheses, 0, 1),
+      Token::new(TokenType::Word, 1, 2),
+      Token::new(TokenType::Space, 2, 3),
+      Token::new(TokenType::CloseParentheses, 3, 4),
+    ]
+  );
+}
+
+#[test]
+fn normalizes_newlines() {
+  let css = "a\r\nb\rc\u{c}d\n";
+  let normalized = normalize_newlines(css);
+  assert_eq!(normalized.css, "a\nb\nc\nd\n");
+  assert_eq!(normalized.original_offset(1), 1);
+  assert_eq!(normalized.original_offset(2), 3);
+  assert_eq!(normalized.original_offset(8), 9);
+  assert_eq!(normalized.original_range(2..3), 3..4);
+  assert!(matches!(
+    normalize_newlines("a\nb").css,
+    std::borrow::Cow::Borrowed(_)
+  ));
+}
+
+#[test]
+fn tokenizes_windows_line_endings_like_unix_ones() {
+  let file_list = [
+    "simple.css",
+    "bootstrap-reboot.css",
+    "bootstrap.css",
+    "utf8.css",
+  ];
+  for file_name in file_list {
+    let unix = read_to_string(format!("../../assets/{}", file_name))
+      .unwrap()
+      .replace("\r\n", "\n");
+    let windows = windows(&unix);
+    let unix_tokens = tokenize(&unix);
+    let windows_tokens = tokenize(&windows);
+    assert_eq!(windows_tokens.len(), unix_tokens.len(), "{}", file_name);
+    for (unix_token, windows_token) in unix_tokens.iter().zip(&windows_tokens) {
+      assert_eq!(unix_token.0, windows_token.0, "{}", file_name);
+      assert_eq!(
+        unix[unix_token.1..unix_token.2],
+        windows[windows_token.1..windows_token.2].replace("\r\n", "\n"),
+        "{}",
+        file_name
+      );
+    }
+
+    let normalized = normalize_newlines(&windows);
+    assert_eq!(normalized.css, unix);
+    for token in tokenize(&normalized.css) {
+      let range = normalized.original_range(token.1..token.2);
+      assert_eq!(
+        windows[range].replace("\r\n", "\n"),
+        normalized.css[token.1..token.2],
+        "{}",
+        file_name
+      );
+    }
+  }
+}
@@ -0,0 +1,66 @@
+use tokenizer::input::{decode, Input};
+
+#[test]
+fn strips_bom() {
+  let input = Input::new("\u{feff}a{}", None);
+  assert_eq!(input.css, "a{}");
+  assert!(input.has_bom());
+  assert_eq!(input.with_bom("b{}"), "\u{feff}b{}");
+
+  let input = Input::new("a{}", None);
+  assert_eq!(input.css, "a{}");
+  assert!(!input.has_bom());
+  assert_eq!(input.with_bom("b{}"), "b{}");
+}
+
+#[test]
+fn reports_charset() {
+  assert_eq!(
+    Input::new("\u{feff}@charset \"utf-8\";a{}", None).charset(),
+    Some("utf-8")
+  );
+  assert_eq!(Input::new("@charset 'utf-8';", None).charset(), None);
+  assert_eq!(Input::new(" @charset \"utf-8\";", None).charset(), None);
+  assert_eq!(Input::new("@charset \"utf-8\"", None).charset(), None);
+}
+
+#[test]
+fn decodes_utf8() {
+  assert_eq!(decode("a{content:\"é\"}".as_bytes()), "a{content:\"é\"}");
+  assert_eq!(decode(b"\xef\xbb\xbfa{}"), "\u{feff}a{}");
+  assert_eq!(decode(b"a\xff"), "a\u{fffd}");
+}
+
+#[test]
+fn decodes_utf16() {
+  let css = "\u{feff}a{content:\"é😀\"}";
+  let le: Vec<u8> = css.encode_utf16().flat_map(u16::to_le_bytes).collect();
+  let be: Vec<u8> = css.encode_utf16().flat_map(u16::to_be_bytes).collect();
+  assert_eq!(decode(&le), css);
+  assert_eq!(decode(&be), css);
+  let input = Input::new(&decode(&le), None).css.to_string();
+  assert_eq!(input, "a{content:\"é😀\"}");
+}
+
+#[test]
+fn decodes_latin1_charset() {
+  assert_eq!(
+    decode(b"@charset \"ISO-8859-1\";a{content:\"\xe9\x80\"}"),
+    "@charset \"ISO-8859-1\";a{content:\"é€\"}"
+  );
+  // a UTF-16 label without a byte-order mark means UTF-8
+  assert_eq!(
+    decode("@charset \"utf-16\";a{content:\"é\"}".as_bytes()),
+    "@charset \"utf-16\";a{content:\"é\"}"
+  );
+}
+
+#[test]
+fn reports_positions_like_postcss() {
+  let input = Input::new("\u{feff}a{}\n.😀{color:red}", None);
+  let position = |offset: u32| input.from_offset(offset).map(|p| (p.line, p.col));
+  assert_eq!(position(0), Some((1, 1)));
+  // JavaScript strings count the emoji twice
+  assert_eq!(position(input.css.rfind('{').unwrap() as u32), Some((2, 4)));
+  assert_eq!(position(100), None);
+}
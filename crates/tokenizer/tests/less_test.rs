@@ -0,0 +1,47 @@
+use tokenizer::{Syntax, Token, TokenType, Tokenizer};
+
+fn tokens(less: &str) -> Vec<(TokenType, &str)> {
+  Tokenizer::with_syntax(less, false, Syntax::Less)
+    .map(|Token(kind, start, end)| (kind, &less[start..end]))
+    .collect()
+}
+
+#[test]
+fn ends_variables_after_the_colon() {
+  assert_eq!(
+    tokens("@a:1px;@media"),
+    vec![
+      (TokenType::AtWord, "@a:"),
+      (TokenType::Word, "1px"),
+      (TokenType::Semicolon, ";"),
+      (TokenType::AtWord, "@media"),
+    ]
+  );
+}
+
+#[test]
+fn keeps_interpolation_in_words() {
+  assert_eq!(
+    tokens(".@{name}-x @{prop}: 1 // c"),
+    vec![
+      (TokenType::Word, ".@{name}-x"),
+      (TokenType::Space, " "),
+      (TokenType::Word, "@{prop}"),
+      (TokenType::Colon, ":"),
+      (TokenType::Space, " "),
+      (TokenType::Word, "1"),
+      (TokenType::Space, " "),
+      (TokenType::Comment, "// c"),
+    ]
+  );
+  // `#{` is SCSS
+  assert_eq!(
+    tokens("#{a}"),
+    vec![
+      (TokenType::Word, "#"),
+      (TokenType::OpenCurly, "{"),
+      (TokenType::Word, "a"),
+      (TokenType::CloseCurly, "}"),
+    ]
+  );
+}
@@ -0,0 +1,79 @@
+use tokenizer::line_index::{Encoding, LineCol, LineIndex};
+use tokenizer::Tokenizer;
+
+fn at(line: usize, col: usize) -> LineCol {
+  LineCol { line, col }
+}
+
+#[test]
+fn converts_ascii() {
+  let css = "a {\n  color: red;\n}\n";
+  let index = LineIndex::new(css);
+  assert_eq!(index.len_lines(), 4);
+  assert_eq!(index.line_range(1), Some(4..18));
+  for encoding in [Encoding::Utf8, Encoding::Utf16, Encoding::Char] {
+    assert_eq!(index.line_col(0, encoding), Some(at(0, 0)));
+    assert_eq!(index.line_col(3, encoding), Some(at(0, 3)));
+    assert_eq!(index.line_col(6, encoding), Some(at(1, 2)));
+    assert_eq!(index.line_col(css.len(), encoding), Some(at(3, 0)));
+    assert_eq!(index.line_col(css.len() + 1, encoding), None);
+    for offset in 0..=css.len() {
+      let position = index.line_col(offset, encoding).unwrap();
+      assert_eq!(index.offset(position, encoding), Some(offset));
+    }
+  }
+}
+
+#[test]
+fn counts_columns_in_each_encoding() {
+  // `é` is 2 bytes, 1 UTF-16 unit; `😀` is 4 bytes, 2 UTF-16 units
+  let css = "a{}\n.é😀b{}";
+  let index = LineIndex::new(css);
+  let b = css.find('b').unwrap();
+  assert_eq!(index.line_col(b, Encoding::Utf8), Some(at(1, 7)));
+  assert_eq!(index.line_col(b, Encoding::Utf16), Some(at(1, 4)));
+  assert_eq!(index.line_col(b, Encoding::Char), Some(at(1, 3)));
+  assert_eq!(index.offset(at(1, 4), Encoding::Utf16), Some(b));
+  assert_eq!(index.offset(at(1, 3), Encoding::Char), Some(b));
+  assert_eq!(index.offset(at(1, 7), Encoding::Utf8), Some(b));
+}
+
+#[test]
+fn round_trips_every_char_boundary() {
+  let css = "é{}\r\n😀 { content: \"中文\" }\n\n/* ü */";
+  let index = LineIndex::new(css);
+  for encoding in [Encoding::Utf8, Encoding::Utf16, Encoding::Char] {
+    for (offset, _) in css.char_indices().chain([(css.len(), ' ')]) {
+      let position = index.line_col(offset, encoding).unwrap();
+      assert_eq!(
+        index.offset(position, encoding),
+        Some(offset),
+        "{:?}",
+        encoding
+      );
+    }
+  }
+}
+
+#[test]
+fn rejects_positions_outside_the_source() {
+  let css = "😀\nab";
+  let index = LineIndex::new(css);
+  // inside the emoji
+  assert_eq!(index.line_col(2, Encoding::Utf16), Some(at(0, 0)));
+  assert_eq!(index.offset(at(0, 2), Encoding::Utf8), None);
+  assert_eq!(index.offset(at(0, 1), Encoding::Utf16), None);
+  // past the end of a line or of the source
+  assert_eq!(index.offset(at(0, 3), Encoding::Utf16), None);
+  assert_eq!(index.offset(at(1, 2), Encoding::Char), Some(css.len()));
+  assert_eq!(index.offset(at(1, 3), Encoding::Char), None);
+  assert_eq!(index.offset(at(2, 0), Encoding::Char), None);
+}
+
+#[test]
+fn tokenizer_reports_char_columns() {
+  let css = "a{}\n.中文 { color: red }";
+  let mut tokenizer = Tokenizer::new(css, false);
+  assert_eq!(tokenizer.from_offset(css.find('{').unwrap()), (0, 1));
+  assert_eq!(tokenizer.from_offset(css.find(" {").unwrap()), (1, 3));
+}
@@ -54,3 +54,50 @@ fn comma_fn_checks_functions() {
 fn comma_fn_does_not_split_on_escaped_commas() {
   assert_eq!(comma("a\\, b"), vec!["a\\, b"]);
 }
+
+fn items(spans: Spans<'_>) -> Vec<&str> {
+  spans.map(|(item, _)| item).collect()
+}
+
+#[test]
+fn spans_match_split() {
+  for input in [
+    " a  b rust真棒 ",
+    "\"a b\\\"\" ''",
+    "f( )) a( () )",
+    "a\\ b",
+    "a, b,",
+    "a, , b,我你他",
+    "f(,)), a(,(你好),)",
+    "a\\, b",
+  ] {
+    assert_eq!(items(space_spans(input)), space(input), "{}", input);
+    assert_eq!(items(comma_spans(input)), comma(input), "{}", input);
+  }
+}
+
+#[test]
+fn spans_report_ranges() {
+  let input = " a,  你好 ,b ";
+  for (item, range) in comma_spans(input) {
+    assert_eq!(&input[range], item);
+  }
+  let ranges: Vec<_> = comma_spans(input).map(|(_, range)| range).collect();
+  assert_eq!(ranges, vec![1..2, 5..11, 13..14]);
+}
+
+#[test]
+fn spans_check_all_brackets_and_comments() {
+  assert_eq!(
+    items(space_spans("[a b] {c d} /* e f */ g\\ h")),
+    vec!["[a b]", "{c d}", "/* e f */", "g\\ h"]
+  );
+  assert_eq!(
+    items(comma_spans("repeat(2, [a, b] 1fr), /* c, d */ e")),
+    vec!["repeat(2, [a, b] 1fr)", "/* c, d */ e"]
+  );
+  assert_eq!(
+    items(space_spans("\"a\"\n'b'\t\r\x0cc")),
+    vec!["\"a\"", "'b'", "c"]
+  );
+}
@@ -0,0 +1,96 @@
+use tokenizer::{Syntax, Token, TokenType, Tokenizer};
+
+fn tokens(scss: &str) -> Vec<(TokenType, &str)> {
+  Tokenizer::with_syntax(scss, false, Syntax::Scss)
+    .map(|Token(kind, start, end)| (kind, &scss[start..end]))
+    .collect()
+}
+
+#[test]
+fn tokenizes_inline_comments() {
+  assert_eq!(
+    tokens("a // b { c }\n/* d */"),
+    vec![
+      (TokenType::Word, "a"),
+      (TokenType::Space, " "),
+      (TokenType::Comment, "// b { c }"),
+      (TokenType::Space, "\n"),
+      (TokenType::Comment, "/* d */"),
+    ]
+  );
+  assert_eq!(tokens("// end"), vec![(TokenType::Comment, "// end")]);
+  assert_eq!(
+    tokens("red// c"),
+    vec![(TokenType::Word, "red"), (TokenType::Comment, "// c")]
+  );
+  assert_eq!(
+    tokens("#{$a//2}//c"),
+    vec![(TokenType::Word, "#{$a//2}"), (TokenType::Comment, "//c")]
+  );
+  // but not in a `url(` whose `(` is a token of its own
+  assert_eq!(
+    tokens("url( //a )"),
+    vec![
+      (TokenType::Word, "url"),
+      (TokenType::OpenParentheses, "("),
+      (TokenType::Space, " "),
+      (TokenType::Word, "//a"),
+      (TokenType::Space, " "),
+      (TokenType::CloseParentheses, ")"),
+    ]
+  );
+  // plain CSS has no inline comments
+  assert_eq!(Tokenizer::new("//a", false).count(), 1);
+}
+
+#[test]
+fn keeps_interpolation_in_words() {
+  assert_eq!(
+    tokens(".a-#{$b + 1}-c #{&}:hover{"),
+    vec![
+      (TokenType::Word, ".a-#{$b + 1}-c"),
+      (TokenType::Space, " "),
+      (TokenType::Word, "#{&}"),
+      (TokenType::Colon, ":"),
+      (TokenType::Word, "hover"),
+      (TokenType::OpenCurly, "{"),
+    ]
+  );
+  assert_eq!(
+    tokens("#{map-get($m, \"}\")}{x}"),
+    vec![
+      (TokenType::Word, "#{map-get($m, \"}\")}"),
+      (TokenType::OpenCurly, "{"),
+      (TokenType::Word, "x"),
+      (TokenType::CloseCurly, "}"),
+    ]
+  );
+  assert_eq!(tokens("#id"), vec![(TokenType::Word, "#id")]);
+}
+
+#[test]
+fn keeps_interpolation_in_strings() {
+  assert_eq!(
+    tokens("\"a #{\"b\" + 'c'} d\" 'e'"),
+    vec![
+      (TokenType::String, "\"a #{\"b\" + 'c'} d\""),
+      (TokenType::Space, " "),
+      (TokenType::String, "'e'"),
+    ]
+  );
+}
+
+#[test]
+fn tokenizes_variables_and_placeholders() {
+  assert_eq!(
+    tokens("$a: 1;%b"),
+    vec![
+      (TokenType::Word, "$a"),
+      (TokenType::Colon, ":"),
+      (TokenType::Space, " "),
+      (TokenType::Word, "1"),
+      (TokenType::Semicolon, ";"),
+      (TokenType::Word, "%b"),
+    ]
+  );
+}
@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+use tokenizer::spec::*;
+
+fn tokenize(css: &str) -> Vec<SpecToken<'_>> {
+  SpecTokenizer::new(css).collect()
+}
+
+fn kinds(css: &str) -> Vec<SpecTokenType<'_>> {
+  tokenize(css).into_iter().map(|token| token.0).collect()
+}
+
+fn run(css: &str, tokens: Vec<SpecToken>) {
+  assert_eq!(tokenize(css), tokens);
+}
+
+#[test]
+fn tokenizes_empty_file() {
+  run("", vec![]);
+}
+
+#[test]
+fn tokenizes_numbers() {
+  assert_eq!(
+    kinds("12 -1.5 +.5e2 1e 50% 12px 1.5E+3em"),
+    vec![
+      SpecTokenType::Number {
+        value: 12.0,
+        is_integer: true
+      },
+      SpecTokenType::Whitespace,
+      SpecTokenType::Number {
+        value: -1.5,
+        is_integer: false
+      },
+      SpecTokenType::Whitespace,
+      SpecTokenType::Number {
+        value: 50.0,
+        is_integer: false
+      },
+      SpecTokenType::Whitespace,
+      SpecTokenType::Dimension {
+        value: 1.0,
+        is_integer: true,
+        unit: Cow::Borrowed("e")
+      },
+      SpecTokenType::Whitespace,
+      SpecTokenType::Percentage { value: 50.0 },
+      SpecTokenType::Whitespace,
+      SpecTokenType::Dimension {
+        value: 12.0,
+        is_integer: true,
+        unit: Cow::Borrowed("px")
+      },
+      SpecTokenType::Whitespace,
+      SpecTokenType::Dimension {
+        value: 1500.0,
+        is_integer: false,
+        unit: Cow::Borrowed("em")
+      },
+    ]
+  );
+}
+
+#[test]
+fn resolves_escapes_in_units() {
+  assert_eq!(
+    kinds("1\\70 x"),
+    vec![SpecTokenType::Dimension {
+      value: 1.0,
+      is_integer: true,
+      unit: Cow::Owned("px".into())
+    }]
+  );
+}
+
+#[test]
+fn tokenizes_hashes() {
+  run(
+    "#fff #-a #1a # ",
+    vec![
+      SpecToken(SpecTokenType::Hash { is_id: true }, 0, 4),
+      SpecToken(SpecTokenType::Whitespace, 4, 5),
+      SpecToken(SpecTokenType::Hash { is_id: true }, 5, 8),
+      SpecToken(SpecTokenType::Whitespace, 8, 9),
+      SpecToken(SpecTokenType::Hash { is_id: false }, 9, 12),
+      SpecToken(SpecTokenType::Whitespace, 12, 13),
+      SpecToken(SpecTokenType::Delim('#'), 13, 14),
+      SpecToken(SpecTokenType::Whitespace, 14, 15),
+    ],
+  );
+}
+
+#[test]
+fn tokenizes_functions_and_urls() {
+  let css = "rgba(0,0,0) url( a.png ) url(\"b.png\") url(a b) url(a\"b)";
+  run(
+    css,
+    vec![
+      SpecToken(SpecTokenType::Function, 0, 5),
+      SpecToken(
+        SpecTokenType::Number {
+          value: 0.0,
+          is_integer: true,
+        },
+        5,
+        6,
+      ),
+      SpecToken(SpecTokenType::Comma, 6, 7),
+      SpecToken(
+        SpecTokenType::Number {
+          value: 0.0,
+          is_integer: true,
+        },
+        7,
+        8,
+      ),
+      SpecToken(SpecTokenType::Comma, 8, 9),
+      SpecToken(
+        SpecTokenType::Number {
+          value: 0.0,
+          is_integer: true,
+        },
+        9,
+        10,
+      ),
+      SpecToken(SpecTokenType::CloseParentheses, 10, 11),
+      SpecToken(SpecTokenType::Whitespace, 11, 12),
+      SpecToken(SpecTokenType::Url, 12, 24),
+      SpecToken(SpecTokenType::Whitespace, 24, 25),
+      SpecToken(SpecTokenType::Function, 25, 29),
+      SpecToken(SpecTokenType::String, 29, 36),
+      SpecToken(SpecTokenType::CloseParentheses, 36, 37),
+      SpecToken(SpecTokenType::Whitespace, 37, 38),
+      SpecToken(SpecTokenType::BadUrl, 38, 46),
+      SpecToken(SpecTokenType::Whitespace, 46, 47),
+      SpecToken(SpecTokenType::BadUrl, 47, 55),
+    ],
+  );
+  let url = tokenize(css)[8].clone();
+  assert_eq!(url.value(css), "a.png");
+}
+
+#[test]
+fn tokenizes_strings() {
+  let css = "'a\\'b' \"c\\\r\nd\" 'e\nf'";
+  run(
+    css,
+    vec![
+      SpecToken(SpecTokenType::String, 0, 6),
+      SpecToken(SpecTokenType::Whitespace, 6, 7),
+      SpecToken(SpecTokenType::String, 7, 14),
+      SpecToken(SpecTokenType::Whitespace, 14, 15),
+      SpecToken(SpecTokenType::BadString, 15, 17),
+      SpecToken(SpecTokenType::Whitespace, 17, 18),
+      SpecToken(SpecTokenType::Ident, 18, 19),
+      SpecToken(SpecTokenType::String, 19, 20),
+    ],
+  );
+  let tokens = tokenize(css);
+  assert_eq!(tokens[0].value(css), "a'b");
+  assert_eq!(tokens[2].value(css), "cd");
+}
+
+#[test]
+fn tokenizes_idents_and_escapes() {
+  let css = "\\31 a -foo --bar @media @ \\\n";
+  run(
+    css,
+    vec![
+      SpecToken(SpecTokenType::Ident, 0, 5),
+      SpecToken(SpecTokenType::Whitespace, 5, 6),
+      SpecToken(SpecTokenType::Ident, 6, 10),
+      SpecToken(SpecTokenType::Whitespace, 10, 11),
+      SpecToken(SpecTokenType::Ident, 11, 16),
+      SpecToken(SpecTokenType::Whitespace, 16, 17),
+      SpecToken(SpecTokenType::AtKeyword, 17, 23),
+      SpecToken(SpecTokenType::Whitespace, 23, 24),
+      SpecToken(SpecTokenType::Delim('@'), 24, 25),
+      SpecToken(SpecTokenType::Whitespace, 25, 26),
+      SpecToken(SpecTokenType::Delim('\\'), 26, 27),
+      SpecToken(SpecTokenType::Whitespace, 27, 28),
+    ],
+  );
+  assert_eq!(tokenize(css)[0].value(css), "1a");
+  assert_eq!(unescape("\\0 \\D800x\\110000"), "\u{FFFD}\u{FFFD}x\u{FFFD}");
+}
+
+#[test]
+fn tokenizes_punctuation_and_cdo_cdc() {
+  assert_eq!(
+    kinds("<!--a{b:c;}[]-->/**/<"),
+    vec![
+      SpecTokenType::Cdo,
+      SpecTokenType::Ident,
+      SpecTokenType::OpenCurly,
+      SpecTokenType::Ident,
+      SpecTokenType::Colon,
+      SpecTokenType::Ident,
+      SpecTokenType::Semicolon,
+      SpecTokenType::CloseCurly,
+      SpecTokenType::OpenSquare,
+      SpecTokenType::CloseSquare,
+      SpecTokenType::Cdc,
+      SpecTokenType::Comment,
+      SpecTokenType::Delim('<'),
+    ]
+  );
+}
+
+#[test]
+fn covers_the_whole_source() {
+  let css = include_str!("../../../assets/bootstrap.css");
+  let mut end = 0;
+  for token in SpecTokenizer::new(css) {
+    assert_eq!(token.1, end);
+    assert!(token.2 > token.1);
+    end = token.2;
+  }
+  assert_eq!(end, css.len());
+}
@@ -0,0 +1,133 @@
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use tokenizer::stream::StreamTokenizer;
+use tokenizer::*;
+
+/// a reader returning at most `max` bytes per call, like a socket or a pipe might
+struct Trickle<'a> {
+  data: &'a [u8],
+  max: usize,
+}
+
+impl<'a> Read for Trickle<'a> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let len = self.data.len().min(self.max).min(buf.len());
+    buf[..len].copy_from_slice(&self.data[..len]);
+    self.data = &self.data[len..];
+    Ok(len)
+  }
+}
+
+fn expected(css: &str) -> Vec<(Token, String)> {
+  Tokenizer::new(css, false)
+    .map(|token| (token, css[token.1..token.2].to_string()))
+    .collect()
+}
+
+fn stream(css: &str, chunk_size: usize, max_read: usize) -> Vec<(Token, String)> {
+  let reader = Trickle {
+    data: css.as_bytes(),
+    max: max_read,
+  };
+  StreamTokenizer::new(reader, false)
+    .with_chunk_size(chunk_size)
+    .collect::<io::Result<Vec<_>>>()
+    .unwrap()
+}
+
+#[test]
+fn streams_tokens_across_chunk_boundaries() {
+  let cases = [
+    "a { color: red; background: url(a.png) }",
+    "/* comment */ a::before { content: \"x\\\"y\" }",
+    "@media (min-width: 1px) { .a\\:b { x: y } }",
+    "a { b: url( a ) url(\"b\") c(d e) (f }",
+    "a\r\n\r\n  b // c /* d */",
+    "a { content: '你好' } 🦀 { x: y }",
+  ];
+  for css in cases {
+    for chunk_size in 1..=8 {
+      assert_eq!(
+        stream(css, chunk_size, usize::MAX),
+        expected(css),
+        "{}",
+        css
+      );
+    }
+  }
+}
+
+#[test]
+fn streams_escaped_parentheses_in_urls() {
+  for css in ["a{b:url(x\\)y)}", "a{b:url(x\\\\)y)}"] {
+    for chunk_size in 1..=css.len() {
+      assert_eq!(
+        stream(css, chunk_size, usize::MAX),
+        expected(css),
+        "{} in chunks of {}",
+        css,
+        chunk_size
+      );
+    }
+  }
+}
+
+#[test]
+fn streams_assets() {
+  let file_list = ["bootstrap-reboot.css", "bootstrap.css", "utf8.css"];
+  for file_name in file_list {
+    let css = read_to_string(format!("../../assets/{}", file_name)).unwrap();
+    let expected = expected(&css);
+    for (chunk_size, max_read) in [(4096, 4096), (1000, 17), (64 * 1024, usize::MAX)] {
+      assert_eq!(
+        stream(&css, chunk_size, max_read),
+        expected,
+        "{} {}",
+        file_name,
+        chunk_size
+      );
+    }
+  }
+}
+
+#[test]
+fn reports_invalid_utf8() {
+  let tokens = StreamTokenizer::new(&b"a { b: \xff }"[..], false).collect::<io::Result<Vec<_>>>();
+  assert_eq!(tokens.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+  let tokens = StreamTokenizer::new(&"a é".as_bytes()[..3], false)
+    .with_chunk_size(2)
+    .collect::<io::Result<Vec<_>>>();
+  assert_eq!(tokens.unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+#[should_panic(expected = "Unclosed string")]
+fn throws_error_on_unclosed_string_at_the_end() {
+  let _ = StreamTokenizer::new(" \"".as_bytes(), false)
+    .with_chunk_size(1)
+    .count();
+}
+
+#[test]
+fn streams_tokens_spanning_many_chunks() {
+  let long = "x".repeat(200_000);
+  let cases = [
+    format!("a {{ b: c }} /* {} */ d", long),
+    format!("a {{ b: \"{}\" }}", long),
+    format!("a {{ b: url({}) }}", long),
+    format!("a {} b", long),
+    format!("a{}b", " ".repeat(200_000)),
+    format!("@{} a", long),
+  ];
+  for css in &cases {
+    for chunk_size in [1, 7, 64] {
+      assert_eq!(
+        stream(css, chunk_size, usize::MAX),
+        expected(css),
+        "{}",
+        &css[..10]
+      );
+    }
+  }
+}
@@ -2,7 +2,7 @@ use tokenizer::input::Input;
 use tokenizer::*;
 fn tokenize(css: &str, ignore_errors: bool) -> Vec<Token> {
   let input = Input::new(css, None);
-  let processor = Tokenizer::new(input.css, ignore_errors);
+  let mut processor = Tokenizer::new(input.css, ignore_errors);
   let mut tokens = vec![];
   while !processor.end_of_file() {
     tokens.push(processor.next_token(false))
@@ -380,7 +380,7 @@ fn tokenizes_hexadecimal_escape() {
 fn ignore_unclosed_per_token_request() {
   fn token(css: &str) -> Vec<Token> {
     let input = Input::new(css, None);
-    let processor = Tokenizer::new(input.css, false);
+    let mut processor = Tokenizer::new(input.css, false);
     let mut tokens = vec![];
     while !processor.end_of_file() {
       tokens.push(processor.next_token(true))
@@ -406,7 +406,7 @@ fn ignore_unclosed_per_token_request() {
 fn provides_correct_position() {
   let css = "Three tokens";
   let input = Input::new(css, None);
-  let processor = Tokenizer::new(input.css, false);
+  let mut processor = Tokenizer::new(input.css, false);
   assert_eq!(processor.position(), 0);
   processor.next_token(false);
   assert_eq!(processor.position(), 5);
@@ -417,3 +417,64 @@ fn provides_correct_position() {
   // processor.next_token(false);
   // assert_eq!(processor.position(), 12);
 }
+
+#[test]
+fn iterates_tokens() {
+  let tokens: Vec<Token> = Tokenizer::new("a {}", false).collect();
+  assert_eq!(tokens, tokenize("a {}", false));
+}
+
+#[test]
+fn rewinds_to_checkpoint() {
+  let mut processor = Tokenizer::new("url(a) url(b)", false);
+  processor.next();
+  processor.next();
+  let checkpoint = processor.checkpoint();
+  let rest: Vec<Token> = processor.by_ref().collect();
+  assert_eq!(
+    rest,
+    vec![
+      Token::new(TokenType::Space, 6, 7),
+      Token::new(TokenType::Word, 7, 10),
+      Token::new(TokenType::Brackets, 10, 13),
+    ]
+  );
+  processor.rewind(checkpoint);
+  assert_eq!(processor.position(), 6);
+  assert_eq!(processor.collect::<Vec<Token>>(), rest);
+
+  let mut processor = Tokenizer::new("url(a b)", false);
+  processor.next();
+  let checkpoint = processor.checkpoint();
+  let first = processor.next();
+  processor.rewind(checkpoint);
+  assert_eq!(processor.next(), first);
+
+  // the word in front of the last one is still there for the second `(`
+  let mut processor = Tokenizer::new("url x(a) (b)", false);
+  processor.by_ref().take(3).for_each(drop);
+  let checkpoint = processor.checkpoint();
+  let rest: Vec<Token> = processor.by_ref().collect();
+  assert_eq!(rest.last(), Some(&Token::new(TokenType::Brackets, 9, 12)));
+  processor.rewind(checkpoint);
+  assert_eq!(processor.collect::<Vec<Token>>(), rest);
+}
+
+#[test]
+fn can_be_shared_across_threads() {
+  fn assert_send_sync<T: Send + Sync>() {}
+  assert_send_sync::<Tokenizer>();
+}
+
+#[test]
+fn tokenizes_words_starting_with_non_ascii() {
+  run(
+    " 🦀你好 @媒体",
+    vec![
+      Token::new(TokenType::Space, 0, 1),
+      Token::new(TokenType::Word, 1, 11),
+      Token::new(TokenType::Space, 11, 12),
+      Token::new(TokenType::AtWord, 12, 19),
+    ],
+  );
+}